@@ -45,6 +45,45 @@ pub fn normalize_refname(name: &str) -> String {
                         // into their lower-case counterparts
 }
 
+/// The prefix of the target labels generated for Sphinx glossary terms.
+pub const GLOSSARY_TERM_LABEL_PREFIX: &str = "term-";
+
+/// Generates the target label of a Sphinx glossary term from the (unnormalized) term.
+/// As in Sphinx, the words of the term are joined with hyphens, so that the label contains no spaces.
+pub fn glossary_term_label(term: &str) -> String {
+    format!(
+        "{}{}",
+        GLOSSARY_TERM_LABEL_PREFIX,
+        normalize_refname(term).split_whitespace().collect::<Vec<&str>>().join("-")
+    )
+}
+
+/// The prefix of the target labels generated for the labeled lines of A+ `lineref-code-block`s.
+pub const LINEREF_LABEL_PREFIX: &str = "lineref-";
+
+/// Generates the target label of a labeled code line from the label written in the code block.
+pub fn lineref_label(label: &str) -> String {
+    format!("{}{}", LINEREF_LABEL_PREFIX, normalize_refname(label))
+}
+
+/// The characters that may be used to escape into LaTeX in a `linerefcode` listing,
+/// in order of preference.
+pub const LINEREF_ESCAPE_CHARS: &[char] = &['|', '!', '@', '`', '?', ';'];
+
+/// The prefix of the target labels generated for the productions of a Sphinx `productionlist`.
+pub const PRODUCTION_LABEL_PREFIX: &str = "grammar-token-";
+
+/// Generates the target label of a production in a Sphinx `productionlist`,
+/// from the name of the production and the optional production group it belongs to.
+pub fn production_label(group: Option<&str>, name: &str) -> String {
+    match group {
+        Some(group) if ! group.is_empty() => {
+            format!("{}{}-{}", PRODUCTION_LABEL_PREFIX, normalize_refname(group), normalize_refname(name))
+        }
+        _ => format!("{}{}", PRODUCTION_LABEL_PREFIX, normalize_refname(name)),
+    }
+}
+
 /// A whitespace-aware function for stripping indentation
 /// from `String`s. Returns `Ok(String)` if successful.
 /// If non-whitespace characters are encountered before
@@ -68,6 +107,203 @@ pub fn strip_indent(line: String, amount: usize) -> Result<String, &'static str>
     Ok(chars.as_str().to_string())
 }
 
+// ==================================
+// Sphinx "only" expression evaluation
+// ==================================
+
+/// Evaluates the boolean expression of a Sphinx `only` directive, such as `html and (latex or not draft)`,
+/// with the given set of defined tags. Tag names evaluate to `true` if they are contained in the set.
+/// The operator `not` binds tighter than `and`, which in turn binds tighter than `or`.
+/// Returns an `Err(message)` if the expression is malformed.
+pub fn evaluate_only_expression(
+    expression: &str,
+    tags: &std::collections::HashSet<String>,
+) -> Result<bool, String> {
+
+    // Split the expression into parentheses and words
+    let mut tokens: Vec<String> = Vec::new();
+    for word in expression.split_whitespace() {
+        let mut current = String::new();
+        for c in word.chars() {
+            if c == '(' || c == ')' {
+                if ! current.is_empty() {
+                    tokens.push(current.drain(..).collect());
+                }
+                tokens.push(c.to_string());
+            } else {
+                current.push(c);
+            }
+        }
+        if ! current.is_empty() {
+            tokens.push(current);
+        }
+    }
+
+    let mut position = 0;
+    let value = only_or_expression(&tokens, &mut position, tags)?;
+    if position < tokens.len() {
+        return Err(format!("unexpected \"{}\" in \"{}\"", tokens[position], expression));
+    }
+    Ok(value)
+}
+
+/// Parses and evaluates a disjunction of conjunctions, starting at the given token position.
+fn only_or_expression(
+    tokens: &[String],
+    position: &mut usize,
+    tags: &std::collections::HashSet<String>,
+) -> Result<bool, String> {
+    let mut value = only_and_expression(tokens, position, tags)?;
+    while tokens.get(*position).map(|token| token.as_str()) == Some("or") {
+        *position += 1;
+        let rhs = only_and_expression(tokens, position, tags)?;
+        value = value || rhs;
+    }
+    Ok(value)
+}
+
+/// Parses and evaluates a conjunction of possibly negated terms, starting at the given token position.
+fn only_and_expression(
+    tokens: &[String],
+    position: &mut usize,
+    tags: &std::collections::HashSet<String>,
+) -> Result<bool, String> {
+    let mut value = only_not_expression(tokens, position, tags)?;
+    while tokens.get(*position).map(|token| token.as_str()) == Some("and") {
+        *position += 1;
+        let rhs = only_not_expression(tokens, position, tags)?;
+        value = value && rhs;
+    }
+    Ok(value)
+}
+
+/// Parses and evaluates a possibly negated tag name or parenthesized expression.
+fn only_not_expression(
+    tokens: &[String],
+    position: &mut usize,
+    tags: &std::collections::HashSet<String>,
+) -> Result<bool, String> {
+    let token = match tokens.get(*position) {
+        Some(token) => token.as_str(),
+        None => return Err(String::from("unexpected end of expression")),
+    };
+    *position += 1;
+    match token {
+        "not" => Ok(! only_not_expression(tokens, position, tags)?),
+        "(" => {
+            let value = only_or_expression(tokens, position, tags)?;
+            if tokens.get(*position).map(|token| token.as_str()) == Some(")") {
+                *position += 1;
+                Ok(value)
+            } else {
+                Err(String::from("missing closing parenthesis"))
+            }
+        }
+        ")" | "and" | "or" => Err(format!("unexpected \"{}\"", token)),
+        tag => {
+            if tag.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '-') {
+                Ok(tags.contains(tag))
+            } else {
+                Err(format!("invalid tag name \"{}\"", tag))
+            }
+        }
+    }
+}
+
+// =====================
+// Sphinx index entries
+// =====================
+
+/// The types of index entries recognized by the Sphinx `index` directive and role.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SphinxIndexEntryKind {
+    /// A single entry with an optional subentry, as in `single: loop; while`.
+    Single,
+    /// A pair of entries, listed under both terms, as in `pair: loop; statement`.
+    Pair,
+    /// A triple of entries, listed under each of the three terms.
+    Triple,
+    /// A reference from the first term to the second one, as in `see: loop; iteration`.
+    See,
+    /// Like `See`, but the first term is expected to have entries of its own.
+    SeeAlso,
+}
+
+/// A single index entry of a Sphinx `index` directive or role.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SphinxIndexEntry {
+    pub kind: SphinxIndexEntryKind,
+    /// The `;`-separated terms of the entry.
+    pub terms: Vec<String>,
+    /// Whether the entry was marked as the main entry of its terms with a `!`.
+    pub main: bool,
+}
+
+/// Parses a single line of Sphinx index entries, such as `single: loop; while`, `!pair: loop; statement`
+/// or the shorthand `loop, iteration`, where each comma-separated word is a single entry.
+/// Returns an `Err(message)` if a typed entry has the wrong number of terms.
+pub fn parse_index_entries(line: &str) -> Result<Vec<SphinxIndexEntry>, String> {
+    let line = line.trim();
+    let (main, line) = if line.starts_with("!") {
+        (true, line[1..].trim_start())
+    } else {
+        (false, line)
+    };
+
+    const INDEX_ENTRY_TYPES: &[(&str, SphinxIndexEntryKind, usize, usize)] = &[
+        ("single", SphinxIndexEntryKind::Single, 1, 2),
+        ("pair", SphinxIndexEntryKind::Pair, 2, 2),
+        ("triple", SphinxIndexEntryKind::Triple, 3, 3),
+        ("see", SphinxIndexEntryKind::See, 2, 2),
+        ("seealso", SphinxIndexEntryKind::SeeAlso, 2, 2),
+    ];
+
+    for (type_name, kind, min_terms, max_terms) in INDEX_ENTRY_TYPES {
+        if let Some(value) = line.strip_prefix(type_name).and_then(|rest| rest.strip_prefix(":")) {
+            let value = value.trim();
+            let (main, value) = if value.starts_with("!") {
+                (true, value[1..].trim_start())
+            } else {
+                (main, value)
+            };
+            let terms = value
+                .split(";")
+                .map(|term| term.trim().to_string())
+                .collect::<Vec<String>>();
+            if terms.len() < *min_terms || terms.len() > *max_terms || terms.iter().any(|term| term.is_empty()) {
+                return Err(format!(
+                    "invalid {} index entry \"{}\"", type_name, value
+                ));
+            }
+            return Ok(vec![SphinxIndexEntry {
+                kind: *kind,
+                terms: terms,
+                main: main,
+            }]);
+        }
+    }
+
+    // Shorthand notation: a comma-separated list of single entries
+    let mut entries = Vec::new();
+    for (index, value) in line.split(",").enumerate() {
+        let value = value.trim();
+        let (main, value) = if value.starts_with("!") {
+            (true, value[1..].trim_start())
+        } else {
+            (main && index == 0, value)
+        };
+        if value.is_empty() {
+            continue;
+        }
+        entries.push(SphinxIndexEntry {
+            kind: SphinxIndexEntryKind::Single,
+            terms: vec![value.to_string()],
+            main: main,
+        });
+    }
+    Ok(entries)
+}
+
 // ============
 // Type aliases
 // ============
@@ -181,6 +417,112 @@ pub enum Reference {
     EMail(String),
 }
 
+/// The segments of a production definition in a Sphinx `productionlist`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SphinxProductionSegment {
+    /// Plain grammar text, such as terminals and operators.
+    Text(String),
+    /// A reference to another production, written inside backticks in the source text.
+    Token { text: String, target_label: String },
+}
+
+/// The levels of the system messages generated during parsing, in increasing order of severity.
+/// These follow the levels of the docutils system messages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SystemMessageLevel {
+    /// Information that is only of interest when debugging a document.
+    Info,
+    /// A problem that did not prevent the construct from being processed.
+    Warning,
+    /// A problem that caused a construct to be ignored or written out as is.
+    Error,
+    /// A problem that caused the rest of the document to be lost.
+    Severe,
+}
+
+impl std::str::FromStr for SystemMessageLevel {
+    type Err = ();
+
+    /// Converts a level name or a docutils level number into a level.
+    fn from_str(level: &str) -> Result<Self, Self::Err> {
+        match level.trim().to_lowercase().as_str() {
+            "info" | "1" => Ok(Self::Info),
+            "warning" | "2" => Ok(Self::Warning),
+            "error" | "3" => Ok(Self::Error),
+            "severe" | "4" => Ok(Self::Severe),
+            _ => Err(()),
+        }
+    }
+}
+
+impl SystemMessageLevel {
+    /// Returns the docutils level number of the level.
+    pub fn number(&self) -> usize {
+        match self {
+            Self::Info => 1,
+            Self::Warning => 2,
+            Self::Error => 3,
+            Self::Severe => 4,
+        }
+    }
+}
+
+impl std::fmt::Display for SystemMessageLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let name = match self {
+            Self::Info => "INFO",
+            Self::Warning => "WARNING",
+            Self::Error => "ERROR",
+            Self::Severe => "SEVERE",
+        };
+        write!(f, "{}/{}", name, self.number())
+    }
+}
+
+/// The kinds of authors given by the Sphinx directives `sectionauthor` and `codeauthor`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SphinxAuthorKind {
+    /// The author of the enclosing section.
+    Section,
+    /// The author of the code described in the enclosing section.
+    Code,
+}
+
+/// An enumeration of the Sphinx cross-referencing roles supported by ruSTLa.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SphinxCrossReferenceKind {
+    /// `:ref:`, a reference to an arbitrary label or section.
+    Ref,
+    /// `:doc:`, a reference to another document.
+    Doc,
+    /// `:numref:`, a numbered reference to a figure, table, code block or section.
+    NumRef,
+    /// `:eq:`, a reference to a labeled equation.
+    Eq,
+    /// `:lineref:`, a reference to a labeled line of an A+ `lineref-code-block`.
+    LineRef,
+}
+
+/// The lines of an A+ REPL session.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AplusREPLLine {
+    /// A line typed in by the user, after the given prompt.
+    /// Continuation lines of multiline input have the prompt `|`.
+    Input { prompt: String, text: String },
+    /// A line printed by the REPL.
+    Output(String),
+}
+
+/// The segments of the contents of the Sphinx roles `:samp:` and `:file:`.
+/// Variable parts are written inside curly braces in the source text and emphasized in the output.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SphinxSampleSegment {
+    /// Literal text, taken as is.
+    Text(String),
+    /// A variable part of the sample, written inside curly braces.
+    Variable(String),
+}
+
 /// There are 3 types of interpreted inline text, such as math:
 /// 1. where the given role precedes the interpreted content and
 /// 2. where the interpreted content precedes the given role.
@@ -353,7 +695,8 @@ pub enum ParsingResult {
     },
 
     /// A simple failure type. This will be returned when there was clearly no way to recover.
-    Failure { message: String, doctree: DocTree },
+    /// The line is the source line the parser was on when it failed.
+    Failure { message: String, line: usize, doctree: DocTree },
 }
 
 impl ParsingResult {
@@ -394,6 +737,94 @@ pub enum AplusExerciseStatus {
     Maintenance,
 }
 
+/// A point in time given in the A+ round settings, such as the opening time of a module.
+/// A+ interprets the times in the timezone of the course.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct AplusDateTime {
+    pub year: u32,
+    pub month: u32,
+    pub day: u32,
+    pub hour: u32,
+    pub minute: u32,
+    pub second: u32,
+}
+
+impl std::fmt::Display for AplusDateTime {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{:04}-{:02}-{:02} {:02}:{:02}",
+            self.year, self.month, self.day, self.hour, self.minute
+        )?;
+        if self.second > 0 {
+            write!(f, ":{:02}", self.second)?;
+        }
+        Ok(())
+    }
+}
+
+/// The audience of an A+ module or chapter.
+/// * internal: Only the students of the organization.
+/// * external: Only external students.
+/// * registered: All registered students.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AplusAudience {
+    Internal,
+    External,
+    Registered,
+}
+
+impl std::fmt::Display for AplusAudience {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let name = match self {
+            Self::Internal => "internal",
+            Self::External => "external",
+            Self::Registered => "registered",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// The kinds of A+ feedback questionnaires. Each kind is set with a questionnaire option
+/// of the same name, and the questionnaire is given a CSS class of that name as well.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AplusFeedbackKind {
+    Feedback,
+    Chapter,
+    Weekly,
+    Appendix,
+    Course,
+}
+
+impl AplusFeedbackKind {
+    /// The questionnaire options that turn a questionnaire into a feedback questionnaire,
+    /// paired with the kind they stand for.
+    pub const OPTIONS: [(&'static str, Self); 5] = [
+        ("feedback", Self::Feedback),
+        ("chapter-feedback", Self::Chapter),
+        ("weekly-feedback", Self::Weekly),
+        ("appendix-feedback", Self::Appendix),
+        ("course-feedback", Self::Course),
+    ];
+
+    /// The exercise key of a feedback questionnaire is always `feedback`.
+    pub const KEY: &'static str = "feedback";
+
+    /// The default exercise category of a feedback questionnaire.
+    pub const CATEGORY: &'static str = "feedback";
+
+    /// The CSS class given to a feedback questionnaire of this kind.
+    pub fn css_class(&self) -> &'static str {
+        match self {
+            Self::Feedback => "feedback",
+            Self::Chapter => "chapter-feedback",
+            Self::Weekly => "weekly-feedback",
+            Self::Appendix => "appendix-feedback",
+            Self::Course => "course-feedback",
+        }
+    }
+}
+
 ///
 /// An enumeration of the different tokenizers offered by the A+ Radar tokenizer.
 ///
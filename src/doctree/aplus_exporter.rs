@@ -0,0 +1,395 @@
+/*!
+A submodule that contains the A+ questionnaire exporter of the doctree.
+The exporter writes the questionnaires of a document into the JSON exercise
+configuration format read by the A+ MOOC-grader, one file per questionnaire key.
+As JSON is a subset of YAML, the files can also be read as YAML.
+The texts of the questions are exported from the reStructuredText source stored on the nodes,
+as the inline nodes of the document tree hold text that has already been escaped for LaTeX.
+
+Copyright © 2020 Santtu Söderholm
+*/
+
+use std::io::Write;
+
+use super::*;
+use crate::common::QuizPoints;
+
+/// The file suffix of the exported exercise configurations.
+const APLUS_EXPORT_FILE_SUFFIX: &str = ".json";
+
+/// The view type of the grader, that renders questionnaires as forms.
+const APLUS_QUESTIONNAIRE_VIEW_TYPE: &str = "access.types.stdsync.createForm";
+
+/// The exercise category of questionnaires that are not given one.
+const APLUS_QUESTIONNAIRE_DEFAULT_CATEGORY: &str = "questionnaire";
+
+impl DocTree {
+
+    /// Writes the A+ questionnaires of the document into the given folder as MOOC-grader
+    /// exercise configurations. The folder is created, if it does not exist yet.
+    /// The files are named after the source file stem and the questionnaire key,
+    /// as in `chapter_1.json`. Returns the doctree and the paths of the written files.
+    ///
+    /// Duplicate keys are reported as errors by the parser. Only the first questionnaire
+    /// with a given key is exported, so that a later one does not overwrite its file.
+    pub fn write_aplus_exercises(mut self, folder: &std::path::Path) -> Result<(Self, Vec<std::path::PathBuf>), String> {
+
+        self = self.walk_to_root();
+
+        let mut exercises = Vec::new();
+        collect_aplus_questionnaires(self.shared_node(), &mut exercises);
+
+        if exercises.is_empty() {
+            return Ok((self, Vec::new()));
+        }
+
+        if let Err(e) = std::fs::create_dir_all(folder) {
+            return Err(format!("Could not create A+ export folder {:#?}: {}", folder, e));
+        }
+
+        let mut written_paths = Vec::new();
+        let mut written_keys = std::collections::HashSet::new();
+        for (key, exercise) in exercises {
+            if !written_keys.insert(key.clone()) {
+                continue;
+            }
+            let file_name = if self.filename_stem.is_empty() {
+                key + APLUS_EXPORT_FILE_SUFFIX
+            } else {
+                format!("{}_{}{}", self.filename_stem, key, APLUS_EXPORT_FILE_SUFFIX)
+            };
+            let mut path = std::path::PathBuf::from(folder);
+            path.push(file_name);
+
+            eprintln!("Writing A+ exercise configuration to {:#?}...", path);
+
+            let mut file = match std::fs::OpenOptions::new()
+                .write(true)
+                .truncate(true)
+                .create(true)
+                .open(&path)
+            {
+                Ok(file) => file,
+                Err(e) => return Err(format!("Could not open A+ exercise file {:#?} for writing: {}", path, e)),
+            };
+            if let Err(e) = file.write_all(format!("{}\n", exercise).as_bytes()) {
+                return Err(format!("Could not write A+ exercise file {:#?}: {}", path, e));
+            }
+            written_paths.push(path);
+        }
+
+        Ok((self, written_paths))
+    }
+}
+
+/// Goes over the given subtree in pre-order and converts each A+ questionnaire into
+/// its exercise configuration, paired with the key of the questionnaire.
+fn collect_aplus_questionnaires(node: &TreeNode, exercises: &mut Vec<(String, JSONValue)>) {
+    if let TreeNodeType::AplusQuestionnaire { key, .. } = node.shared_data() {
+        exercises.push((key.clone(), aplus_questionnaire_config(node)));
+        return;
+    }
+    if let Some(children) = node.shared_children() {
+        for child in children {
+            collect_aplus_questionnaires(child, exercises);
+        }
+    }
+}
+
+/// Builds the exercise configuration of an A+ questionnaire node.
+fn aplus_questionnaire_config(node: &TreeNode) -> JSONValue {
+    let (
+        key,
+        max_points,
+        points_from_children,
+        difficulty,
+        submissions,
+        points_to_pass,
+        feedback,
+        title,
+        pick_randomly,
+        category,
+        status,
+        instructions,
+    ) = if let TreeNodeType::AplusQuestionnaire {
+        key,
+        max_points,
+        points_from_children,
+        difficulty,
+        submissions,
+        points_to_pass,
+        feedback,
+        title,
+        pick_randomly,
+        category,
+        status,
+        instructions,
+        ..
+    } = node.shared_data()
+    {
+        (
+            key,
+            max_points,
+            points_from_children,
+            difficulty,
+            submissions,
+            points_to_pass,
+            feedback,
+            title,
+            pick_randomly,
+            category,
+            status,
+            instructions,
+        )
+    } else {
+        unreachable!("Tried to export a non-questionnaire node as an A+ questionnaire. Computer says no...")
+    };
+
+    let mut fields = Vec::new();
+    if let Some(children) = node.shared_children() {
+        for child in children {
+            match child.shared_data() {
+                TreeNodeType::AplusPickOne { .. }
+                | TreeNodeType::AplusPickAny { .. }
+                | TreeNodeType::AplusFreeText { .. } => {
+                    let field_index = fields.len();
+                    fields.push(aplus_question_config(child, field_index))
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let mut config = vec![
+        ("key", JSONValue::String(key.clone())),
+        ("title", JSONValue::String(title.clone().unwrap_or_default())),
+        ("category", JSONValue::String(
+            category.clone().unwrap_or(APLUS_QUESTIONNAIRE_DEFAULT_CATEGORY.to_string())
+        )),
+        ("max_points", JSONValue::Number(max_points.unwrap_or(*points_from_children))),
+        ("points_to_pass", JSONValue::Number(parse_number(points_to_pass).unwrap_or(0))),
+        ("feedback", JSONValue::Bool(feedback.is_some())),
+        ("view_type", JSONValue::String(APLUS_QUESTIONNAIRE_VIEW_TYPE.to_string())),
+    ];
+    if let Some(difficulty) = difficulty {
+        config.push(("difficulty", JSONValue::String(difficulty.clone())));
+    }
+    if let Some(submissions) = parse_number(submissions) {
+        config.push(("max_submissions", JSONValue::Number(submissions)));
+    }
+    if let Some(status) = status {
+        config.push(("status", JSONValue::String(status.trim().to_string())));
+    }
+    if let Some(kind) = feedback {
+        config.push(("class", JSONValue::String(kind.css_class().to_string())));
+    }
+    if !instructions.is_empty() {
+        config.push(("instructions", JSONValue::String(instructions.join("\n\n"))));
+    }
+
+    let mut field_group = vec![
+        ("title", JSONValue::String(String::new())),
+        ("fields", JSONValue::Array(fields)),
+    ];
+    if let Some(pick_randomly) = parse_number(pick_randomly) {
+        field_group.push(("pick_randomly", JSONValue::Number(pick_randomly)));
+    }
+    config.push(("fieldgroups", JSONValue::Array(vec![JSONValue::Object(field_group)])));
+
+    JSONValue::Object(config)
+}
+
+/// Builds the form field configuration of an A+ questionnaire question.
+/// Questions without a key are given the key `field_N`, where `N` is the index of the question.
+fn aplus_question_config(node: &TreeNode, field_index: usize) -> JSONValue {
+    let default_key = format!("field_{}", field_index);
+
+    let mut config = match node.shared_data() {
+        TreeNodeType::AplusPickOne { points, class, required, key, dropdown, assignment, .. } => {
+            let mut config = vec![
+                ("key", JSONValue::String(key.clone().unwrap_or(default_key))),
+                ("title", JSONValue::String(assignment.clone())),
+                ("type", JSONValue::String(if *dropdown { "dropdown" } else { "radio" }.to_string())),
+                ("points", JSONValue::Number(*points)),
+                ("required", JSONValue::Bool(*required)),
+            ];
+            if let Some(class) = class {
+                config.push(("class", JSONValue::String(class.clone())));
+            }
+            config
+        }
+        TreeNodeType::AplusPickAny {
+            points, class, required, key, partial_points, randomized, correct_count, assignment, ..
+        } => {
+            let mut config = vec![
+                ("key", JSONValue::String(key.clone().unwrap_or(default_key))),
+                ("title", JSONValue::String(assignment.clone())),
+                ("type", JSONValue::String("checkbox".to_string())),
+                ("points", JSONValue::Number(*points)),
+                ("required", JSONValue::Bool(*required)),
+                ("partial_points", JSONValue::Bool(*partial_points)),
+                ("randomized", JSONValue::Bool(*randomized)),
+            ];
+            if let Some(correct_count) = correct_count {
+                config.push(("correct_count", JSONValue::Number(*correct_count)));
+            }
+            if let Some(class) = class {
+                config.push(("class", JSONValue::String(class.clone())));
+            }
+            config
+        }
+        TreeNodeType::AplusFreeText {
+            points, compare_method, model_answer, class, required, key, length, height, assignment, ..
+        } => {
+            let mut config = vec![
+                ("key", JSONValue::String(key.clone().unwrap_or(default_key))),
+                ("title", JSONValue::String(assignment.clone())),
+                ("type", JSONValue::String(if height.is_some() { "textarea" } else { "text" }.to_string())),
+                ("points", JSONValue::Number(*points)),
+                ("required", JSONValue::Bool(required.is_some())),
+                ("compare_method", JSONValue::String(compare_method.clone())),
+                ("model", JSONValue::String(model_answer.clone())),
+            ];
+            if let Some(length) = parse_number(length) {
+                config.push(("length", JSONValue::Number(length)));
+            }
+            if let Some(height) = parse_number(height) {
+                config.push(("height", JSONValue::Number(height)));
+            }
+            if let Some(class) = class {
+                config.push(("class", JSONValue::String(class.clone())));
+            }
+            config
+        }
+        _ => unreachable!("Tried to export a non-question node as an A+ question. Computer says no..."),
+    };
+
+    let mut options = Vec::new();
+    let mut hints = Vec::new();
+    if let Some(children) = node.shared_children() {
+        for child in children {
+            match child.shared_data() {
+                TreeNodeType::AplusPickChoices { .. } => {
+                    for choice in child.shared_children().into_iter().flatten() {
+                        if let TreeNodeType::AplusPickChoice {
+                            label, is_correct, is_pre_selected, is_neutral, answer
+                        } = choice.shared_data() {
+                            let mut option = vec![
+                                ("value", JSONValue::String(label.clone())),
+                                ("label", JSONValue::String(answer.clone())),
+                                ("correct", JSONValue::Bool(*is_correct)),
+                            ];
+                            if *is_pre_selected {
+                                option.push(("selected", JSONValue::Bool(true)));
+                            }
+                            if *is_neutral {
+                                option.push(("neutral", JSONValue::Bool(true)));
+                            }
+                            options.push(JSONValue::Object(option));
+                        }
+                    }
+                }
+                TreeNodeType::AplusQuestionnaireHints { .. } => {
+                    for hint_node in child.shared_children().into_iter().flatten() {
+                        if let TreeNodeType::AplusQuestionnaireHint {
+                            label, show_when_not_selected, hint, ..
+                        } = hint_node.shared_data() {
+                            hints.push(JSONValue::Object(vec![
+                                ("label", JSONValue::String(label.clone())),
+                                ("not", JSONValue::Bool(*show_when_not_selected)),
+                                ("value", JSONValue::String(hint.clone())),
+                            ]));
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    if let TreeNodeType::AplusPickOne { .. } | TreeNodeType::AplusPickAny { .. } = node.shared_data() {
+        config.push(("options", JSONValue::Array(options)));
+    }
+    config.push(("feedback", JSONValue::Array(hints)));
+
+    JSONValue::Object(config)
+}
+
+/// Parses an optional questionnaire option into a number, ignoring invalid values.
+fn parse_number(option: &Option<String>) -> Option<QuizPoints> {
+    option.as_ref().and_then(|value| value.trim().parse().ok())
+}
+
+/// A minimal representation of JSON values, enough for writing out A+ exercise configurations.
+/// The fields of an object are kept in insertion order.
+enum JSONValue {
+    Bool(bool),
+    Number(QuizPoints),
+    String(String),
+    Array(Vec<JSONValue>),
+    Object(Vec<(&'static str, JSONValue)>),
+}
+
+impl JSONValue {
+
+    /// Writes the value into the given string, indenting nested arrays and objects by two spaces per level.
+    fn write_indented(&self, output: &mut String, indent: usize) {
+        const INDENT: &str = "  ";
+        match self {
+            Self::Bool(value) => output.push_str(if *value { "true" } else { "false" }),
+            Self::Number(value) => output.push_str(value.to_string().as_str()),
+            Self::String(value) => output.push_str(json_string(value).as_str()),
+            Self::Array(values) if values.is_empty() => output.push_str("[]"),
+            Self::Array(values) => {
+                output.push_str("[\n");
+                for (index, value) in values.iter().enumerate() {
+                    output.push_str(INDENT.repeat(indent + 1).as_str());
+                    value.write_indented(output, indent + 1);
+                    output.push_str(if index + 1 < values.len() { ",\n" } else { "\n" });
+                }
+                output.push_str(INDENT.repeat(indent).as_str());
+                output.push(']');
+            }
+            Self::Object(fields) if fields.is_empty() => output.push_str("{}"),
+            Self::Object(fields) => {
+                output.push_str("{\n");
+                for (index, (name, value)) in fields.iter().enumerate() {
+                    output.push_str(INDENT.repeat(indent + 1).as_str());
+                    output.push_str(json_string(name).as_str());
+                    output.push_str(": ");
+                    value.write_indented(output, indent + 1);
+                    output.push_str(if index + 1 < fields.len() { ",\n" } else { "\n" });
+                }
+                output.push_str(INDENT.repeat(indent).as_str());
+                output.push('}');
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for JSONValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let mut output = String::new();
+        self.write_indented(&mut output, 0);
+        write!(f, "{}", output)
+    }
+}
+
+/// Quotes the given string as a JSON string literal, escaping quotes, backslashes and control characters.
+fn json_string(string: &str) -> String {
+    let mut quoted = String::with_capacity(string.len() + 2);
+    quoted.push('"');
+    for c in string.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if (c as u32) < 0x20 => quoted.push_str(format!("\\u{:04x}", c as u32).as_str()),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
@@ -0,0 +1,36 @@
+/*!
+A submodule that defines the container `ExerciseData`, that a `DocTree` holds on to.
+A+ identifies the exercises of a chapter by their keys, so the keys given in a single
+document have to be unique. This is especially true for feedback questionnaires,
+whose key is always `feedback`.
+
+Copyright © 2020 Santtu Söderholm
+*/
+
+use std::collections::HashMap;
+
+/// A container for the keys of the A+ exercises found in a document.
+pub struct ExerciseData {
+    /// The exercise keys, mapped to the lines the exercises were defined on.
+    keys: HashMap<String, usize>,
+}
+
+impl ExerciseData {
+    pub fn new() -> Self {
+        Self {
+            keys: HashMap::new(),
+        }
+    }
+
+    /// Records the key of an exercise defined on the given line.
+    /// If the key was already in use, the line of the previous definition is returned
+    /// and the original record is kept.
+    pub fn register_key(&mut self, key: &str, line: usize) -> Option<usize> {
+        if let Some(previous_line) = self.keys.get(key) {
+            Some(*previous_line)
+        } else {
+            self.keys.insert(key.to_string(), line);
+            None
+        }
+    }
+}
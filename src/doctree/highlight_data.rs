@@ -0,0 +1,44 @@
+/*!
+A submodule that defines the container `HighlightData`, that a `DocTree` holds on to.
+If a Sphinx `highlight` directive is encountered, the language and line number threshold
+specified by it are stored in the container. Literal and code blocks encountered after
+the directive then inherit these settings.
+
+Copyright © 2020 Santtu Söderholm
+*/
+
+/// A container for the default code highlighting settings of a document.
+pub struct HighlightData {
+    language: Option<String>,
+    linenothreshold: Option<usize>,
+}
+
+impl HighlightData {
+    pub fn new() -> Self {
+        Self {
+            language: None,
+            linenothreshold: None,
+        }
+    }
+
+    /// Replaces the current default language and line number threshold with the given ones.
+    pub fn set(&mut self, language: &str, linenothreshold: Option<usize>) {
+        self.language = Some(language.to_string());
+        self.linenothreshold = linenothreshold;
+    }
+
+    /// Retrieves a shared reference to the current default language, if one has been set.
+    pub fn shared_language(&self) -> Option<&String> {
+        self.language.as_ref()
+    }
+
+    /// Checks whether a block with the given number of lines reaches the line number threshold,
+    /// meaning it should be displayed with line numbers.
+    pub fn linenos(&self, n_of_lines: usize) -> bool {
+        if let Some(threshold) = self.linenothreshold {
+            n_of_lines >= threshold
+        } else {
+            false
+        }
+    }
+}
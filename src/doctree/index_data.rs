@@ -0,0 +1,37 @@
+/*!
+A submodule that defines the container `IndexData`, that a `DocTree` holds on to.
+The entries of Sphinx `index` directives and roles are collected here, in the order they
+were encountered, so that the document knows whether it needs an index to be printed.
+
+Copyright © 2020 Santtu Söderholm
+*/
+
+use crate::common::SphinxIndexEntry;
+
+/// A container for the index entries of a document.
+pub struct IndexData {
+    entries: Vec<SphinxIndexEntry>,
+}
+
+impl IndexData {
+    pub fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+
+    /// Appends the given entries to the known index entries.
+    pub fn add_entries(&mut self, entries: &[SphinxIndexEntry]) {
+        self.entries.extend_from_slice(entries)
+    }
+
+    /// Retrieves a shared reference to the known index entries.
+    pub fn shared_entries(&self) -> &Vec<SphinxIndexEntry> {
+        &self.entries
+    }
+
+    /// Checks whether any index entries have been encountered.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
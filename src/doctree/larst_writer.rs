@@ -10,8 +10,10 @@ use std::io::Write;
 
 use super::*;
 use crate::common::AplusExerciseStatus;
+use crate::common::SphinxProductionSegment;
 use crate::rustla_options::ruSTLaOptions;
 use crate::rustla_options::OutputStream;
+use crate::rustla_options::UnknownDirectiveOutput;
 
 const LATEX_OPTION_DELIM: &str = ",";
 
@@ -139,7 +141,9 @@ impl TreeNode {
 
         let refnames = self.shared_target_labels().as_ref();
 
-        let pre_string = self.shared_data().larst_pre_order_string(refnames, rustla_options);
+        let classes = self.shared_classes().as_ref();
+
+        let pre_string = self.shared_data().larst_pre_order_string(refnames, classes, rustla_options);
         match output_stream.write(pre_string.as_bytes()) {
             Ok(_) => {}
             Err(_) => panic!(
@@ -183,13 +187,24 @@ impl TreeNode {
 impl TreeNodeType {
 
     /// Defines the text pattern each `TreeNodeType` variant starts with.
-    fn larst_pre_order_string(&self, ref_names: Option<&Vec<String>>, rustla_options: &ruSTLaOptions) -> String {
+    fn larst_pre_order_string(
+        &self,
+        ref_names: Option<&Vec<String>>,
+        classes: Option<&Vec<String>>,
+        rustla_options: &ruSTLaOptions
+    ) -> String {
         let pre_string = match self {
-            Self::Abbreviation { .. } => todo!(),
+            Self::Abbreviation { text, explanation } => match explanation {
+                Some(explanation) => format!("\\textsc{{{}}}\\footnote{{{}}}", text, explanation),
+                None => format!("\\textsc{{{}}}", text),
+            },
             Self::AbsoluteURI { text } => {
                 format!(r"\url{{{}}}", text)
             }
-            Self::Acronym { .. } => todo!(),
+            Self::Acronym { text, explanation } => match explanation {
+                Some(explanation) => format!("\\textsc{{{}}}\\footnote{{{}}}", text, explanation),
+                None => format!("\\textsc{{{}}}", text),
+            },
             Self::Address => todo!(),
             Self::Admonition {
                 content_indent,
@@ -290,13 +305,27 @@ impl TreeNodeType {
                     format!(": {}", classifiers.join(", "))
                 };
 
-                format!("\\item \\textbf{{{}}}{}\n\n", term, classifiers)
+                // Only the terms of a glossary are labeled, so that the :term: role can refer to them
+                let anchors = ref_names
+                    .into_iter()
+                    .flatten()
+                    .filter(|name| name.starts_with(crate::common::GLOSSARY_TERM_LABEL_PREFIX))
+                    .map(|name| format!("\\label{{{}}}\n", name))
+                    .collect::<String>();
+
+                format!("\\item \\textbf{{{}}}{}\n{}\n", term, classifiers, anchors)
             }
             Self::Description => todo!(),
             Self::DocInfo => todo!(),
             Self::DoctestBlock { .. } => todo!(),
-            Self::Document { .. } => if rustla_options.is_full_document() {
-                format!("\\documentclass{{aplus}}\n\\begin{{document}}\n\n")
+            Self::Document { print_index, authors } => if rustla_options.is_full_document() {
+                let makeindex = if *print_index { "\\makeindex\n" } else { "" };
+                let author = if authors.is_empty() {
+                    String::new()
+                } else {
+                    format!("\\author{{{}}}\n", authors.join(" \\and "))
+                };
+                format!("\\documentclass{{aplus}}\n{}{}\\begin{{document}}\n\n", author, makeindex)
             } else {
                 String::new()
             },
@@ -434,9 +463,12 @@ impl TreeNodeType {
                 header_rows,
                 stub_columns,
                 align,
+                column_spec,
                 ..
             } => {
-                let widths = if let Some(widths) = widths {
+                let widths = if let Some(spec) = column_spec {
+                    spec.to_string()
+                } else if let Some(widths) = widths {
                     match widths {
                         TableColWidths::Auto => String::new(),
                         TableColWidths::Columns(vals) => {
@@ -464,9 +496,21 @@ impl TreeNodeType {
                 format!("\\begin{{tabular}}{}{{{}}}\n", options_string, widths)
             }
             Self::Literal { text } => format!("\\texttt{{{}}}", text),
-            Self::LiteralBlock { text } => {
+            Self::LiteralBlock { text, language, linenos } => {
                 use crate::utf8_to_latex::unicode_text_to_latex;
-                format!("\\begin{{codeblock}}\n{}", unicode_text_to_latex(text))
+                let mut options = Vec::<String>::new();
+                if let Some(language) = language {
+                    options.push(language.to_string())
+                }
+                if *linenos {
+                    options.push(String::from("linenos"))
+                }
+                let option_string = if options.is_empty() {
+                    String::new()
+                } else {
+                    format!("[{}]", options.join(LATEX_OPTION_DELIM))
+                };
+                format!("\\begin{{codeblock}}{}\n{}", option_string, unicode_text_to_latex(text))
             }
             Self::Math { text, class, name } => {
                 format!(r"\({}\)", crate::utf8_to_latex::unicode_math_to_latex(text))
@@ -474,13 +518,22 @@ impl TreeNodeType {
             Self::MathBlock {
                 math_block,
                 name,
-                class,
+                nowrap,
+                ..
             } => {
                 let ref_labels = self.anchor_string(ref_names);
-                format!(
-                    "{}\\begin{{equation}}\n{}\n",
-                    ref_labels, crate::utf8_to_latex::unicode_math_to_latex(math_block)
-                )
+                let math = crate::utf8_to_latex::unicode_math_to_latex(math_block);
+                if *nowrap {
+                    format!("{}{}\n", ref_labels, math)
+                } else {
+                    let (environment, aligned) = math_block_environment(math_block, name.is_some());
+                    let label = match name {
+                        Some(name) => format!("\\label{{{}}}", name),
+                        None => String::new(),
+                    };
+                    let aligned = if aligned { "\\begin{aligned}" } else { "" };
+                    format!("{}\\begin{{{}}}{}{}\n{}\n", ref_labels, environment, label, aligned, math)
+                }
             }
             Self::OptionList { .. } => todo!(),
             Self::OptionListItem { .. } => todo!(),
@@ -489,7 +542,9 @@ impl TreeNodeType {
             Self::Paragraph { .. } => "".to_string(),
             Self::ParsedLiteralBlock { .. } => todo!(),
             Self::Pending { .. } => todo!(),
-            Self::Problematic { .. } => todo!(),
+            Self::Problematic { text, message_label } => {
+                format!("\\hyperref[{}]{{\\problematic{{{}}}}}", message_label, text)
+            }
             Self::Raw { .. } => "\\begin{codeblock}\n".to_string(),
             Self::Reference {
                 displayed_text,
@@ -529,6 +584,7 @@ impl TreeNodeType {
                 title_text,
                 level,
                 line_style,
+                ..
             } => {
                 let (command, subs) = if *level == 1 {
                     ("chapter", "")
@@ -563,7 +619,30 @@ impl TreeNodeType {
             Self::Superscript { text } => {
                 format!(r"\textsuperscript{{{}}}", text)
             }
-            Self::SystemMessage { .. } => todo!(),
+            Self::SystemMessage { level, line, text, label, referenced } => {
+                // Only the messages linked to from the text need to be labeled
+                let label = if *referenced {
+                    format!("\\label{{{}}}", label)
+                } else {
+                    String::new()
+                };
+                if rustla_options.reports(*level) {
+                    let title = match line {
+                        Some(line) => format!("{} on line {}", level, line),
+                        None => level.to_string(),
+                    };
+                    format!(
+                        "\\begin{{systemmessage}}{{{}}}{}\n{}\n\\end{{systemmessage}}\n\n",
+                        title,
+                        label,
+                        crate::utf8_to_latex::unicode_text_to_latex(text)
+                    )
+                } else if *referenced {
+                    format!("\\phantomsection{}\n", label)
+                } else {
+                    String::new()
+                }
+            }
             Self::Table { .. } => todo!(),
             Self::Target { .. } => todo!(),
             Self::TBody { .. } => "".to_string(),
@@ -589,30 +668,49 @@ impl TreeNodeType {
                 directive_name,
                 argument,
                 options,
+                line,
+                source_lines,
                 ..
-            } => {
-                let arg_str: String = if argument.trim().is_empty() {
-                    String::new()
-                } else {
-                    format!("{{{}}}", argument)
-                };
-                let mut option_vec = Vec::new();
-                for (key, val) in options.keys().zip(options.values()) {
-                    option_vec.push(format!("{}={}", key, val))
+            } => match rustla_options.shared_unknown_directive_output() {
+                UnknownDirectiveOutput::Comment => {
+                    let mut comment = format!("% Unknown directive \"{}\" on line {}:\n", directive_name, line);
+                    for source_line in source_lines {
+                        if source_line.is_empty() {
+                            comment += "%\n";
+                        } else {
+                            comment += &format!("% {}\n", source_line);
+                        }
+                    }
+                    comment + "\n"
                 }
-                let option_str = if option_vec.is_empty() {
-                    String::new()
-                } else {
-                    format!("[{}]", option_vec.join(LATEX_OPTION_DELIM))
-                };
-
-                format!(
-                    "\\begin{{{}}}{}{}\n",
-                    directive_name.to_lowercase(),
-                    option_str,
-                    arg_str
-                )
-            }
+                UnknownDirectiveOutput::Literal => {
+                    format!("\\begin{{codeblock}}\n{}\n\\end{{codeblock}}\n\n", source_lines.join("\n"))
+                }
+                UnknownDirectiveOutput::Drop | UnknownDirectiveOutput::Error => String::new(),
+                UnknownDirectiveOutput::Environment => {
+                    let arg_str: String = if argument.trim().is_empty() {
+                        String::new()
+                    } else {
+                        format!("{{{}}}", argument)
+                    };
+                    let mut option_vec = Vec::new();
+                    for (key, val) in options.keys().zip(options.values()) {
+                        option_vec.push(format!("{}={}", key, val))
+                    }
+                    let option_str = if option_vec.is_empty() {
+                        String::new()
+                    } else {
+                        format!("[{}]", option_vec.join(LATEX_OPTION_DELIM))
+                    };
+
+                    format!(
+                        "\\begin{{{}}}{}{}\n",
+                        directive_name.to_lowercase(),
+                        option_str,
+                        arg_str
+                    )
+                }
+            },
             Self::Version { .. } => todo!(),
             Self::WhiteSpace { text } => {
                 format!("{}", text)
@@ -621,12 +719,9 @@ impl TreeNodeType {
             // ============================
             //  Sphinx specific directives
             // ============================
-            Self::SphinxOnly {
-                expression,
-                body_indent,
-            } => {
-                format!("\\begin{{only}}[{}]\n", expression)
-            }
+            // The expressions of the remaining "only" nodes evaluated to true,
+            // so their contents are written as is.
+            Self::SphinxOnly { .. } => "".to_string(),
 
             Self::SphinxCodeBlock {
                 language,
@@ -673,8 +768,132 @@ impl TreeNodeType {
 
                 let option_string = options.join(",");
 
-                // LarST does not support many of the given options yet, so they are not written to the resulting file...
-                format!("\\begin{{codeblock}}[{}]\n{}", language, code_text)
+                // LarST does not support many of the given options yet, so only the language
+                // and line numbering are written to the resulting file...
+                let language_options = if *linenos {
+                    format!("{}{}linenos", language, LATEX_OPTION_DELIM)
+                } else {
+                    language.to_string()
+                };
+                format!("\\begin{{codeblock}}[{}]\n{}", language_options, code_text)
+            }
+            Self::SphinxVersionAdded { version, .. } => {
+                format!("\\begin{{versionmodified}}{{New in version}}{{{}}}\n", version)
+            }
+            Self::SphinxVersionChanged { version, .. } => {
+                format!("\\begin{{versionmodified}}{{Changed in version}}{{{}}}\n", version)
+            }
+            Self::SphinxDeprecated { version, .. } => {
+                format!("\\begin{{versionmodified}}{{Deprecated since version}}{{{}}}\n", version)
+            }
+            Self::SphinxSeeAlso { .. } => "\\begin{seealso}\n".to_string(),
+            Self::SphinxCentered => "\\begin{center}\n".to_string(),
+            Self::SphinxHList { columns, .. } => format!("\\begin{{hlist}}{{{}}}\n", columns),
+            Self::SphinxHListColumn { .. } => "\\begin{hlistcol}\n".to_string(),
+            Self::SphinxGlossary { .. } => "\\begin{glossarylist}\n".to_string(),
+            Self::SphinxTermReference {
+                displayed_text,
+                target_label,
+                ..
+            } => {
+                format!("\\hyperref[{}]{{{}}}", target_label, displayed_text)
+            }
+            Self::SphinxProductionList { .. } => "\\begin{productionlist}\n".to_string(),
+            Self::SphinxProduction { name, target_label, definition } => {
+                let mut rows = String::new();
+                for (line_index, line) in definition.iter().enumerate() {
+                    let text = line
+                        .iter()
+                        .map(|segment| match segment {
+                            SphinxProductionSegment::Text(text) => text.to_string(),
+                            SphinxProductionSegment::Token { text, target_label } => {
+                                format!("\\productiontoken{{{}}}{{{}}}", target_label, text)
+                            }
+                        })
+                        .collect::<String>();
+                    if line_index == 0 {
+                        rows += &format!("\\production{{{}}}{{{}}}{{{}}}\\\\\n", target_label, name, text);
+                    } else {
+                        rows += &format!("\\productioncontinued{{{}}}\\\\\n", text);
+                    }
+                }
+                rows
+            }
+            Self::SphinxAuthor { kind, name, email } => {
+                use crate::common::SphinxAuthorKind;
+                use crate::rustla_options::AuthorDisplay;
+                let label = match kind {
+                    SphinxAuthorKind::Section => "Section author",
+                    SphinxAuthorKind::Code => "Code author",
+                };
+                let author = match email {
+                    Some(email) => format!("\\href{{mailto:{}}}{{{}}}", email, name),
+                    None => name.to_string(),
+                };
+                match rustla_options.shared_author_display() {
+                    AuthorDisplay::None => String::new(),
+                    AuthorDisplay::Footnote => format!("\\authorfootnote{{{}}}{{{}}}\n", label, author),
+                    AuthorDisplay::MarginNote => format!("\\authormarginnote{{{}}}{{{}}}\n", label, author),
+                }
+            }
+            Self::SphinxIndex { entries } => {
+                let anchors = self.anchor_string(ref_names);
+                let commands = index_commands(entries)
+                    .iter()
+                    .map(|command| format!("{}%\n", command))
+                    .collect::<String>();
+                format!("{}{}", anchors, commands)
+            }
+            Self::SphinxInlineIndex { displayed_text, entries } => {
+                format!("{}{}", displayed_text, index_commands(entries).join(""))
+            }
+            Self::SphinxKeyboard { keys } => keys
+                .iter()
+                .enumerate()
+                .map(|(index, key)| {
+                    // Keys and separators alternate, starting from a key
+                    if index % 2 == 0 {
+                        format!("\\kbd{{{}}}", key)
+                    } else {
+                        key.to_string()
+                    }
+                })
+                .collect::<String>(),
+            Self::SphinxFile { segments } => {
+                format!("\\filepath{{{}}}", sample_segments_to_string(segments))
+            }
+            Self::SphinxSample { segments } => {
+                format!("\\samp{{{}}}", sample_segments_to_string(segments))
+            }
+            Self::SphinxCommand { text } => format!("\\command{{{}}}", text),
+            Self::SphinxGUILabel { text } => format!("\\guilabel{{{}}}", text),
+            Self::SphinxMenuSelection { items } => format!(
+                "\\menuselection{{{}}}",
+                items.join("\\menuselectionseparator{}")
+            ),
+            Self::SphinxCrossReference {
+                kind,
+                displayed_text,
+                target_label,
+                ..
+            } => {
+                use crate::common::SphinxCrossReferenceKind;
+                match (kind, displayed_text) {
+                    (SphinxCrossReferenceKind::Eq, _) => format!("\\eqref{{{}}}", target_label),
+                    (SphinxCrossReferenceKind::LineRef, None) => format!("\\ref{{{}}}", target_label),
+                    (SphinxCrossReferenceKind::NumRef, None) => format!("\\autoref{{{}}}", target_label),
+                    (SphinxCrossReferenceKind::NumRef, Some(text)) => {
+                        // The number of the target is inserted in place of "%s" or "{number}"
+                        let number = format!("\\ref*{{{}}}", target_label);
+                        format!(
+                            "\\hyperref[{}]{{{}}}",
+                            target_label,
+                            text.replace("%s", &number).replace("{number}", &number)
+                        )
+                    }
+                    (_, Some(text)) => format!("\\hyperref[{}]{{{}}}", target_label, text),
+                    (_, None) => format!("\\hyperref[{}]{{{}}}", target_label, target_label),
+                }
             }
 
             // ========================
@@ -764,7 +983,19 @@ impl TreeNodeType {
                     points_from_children
                 };
 
-                format!("\\begin{{quiz}}{{{}}}{{{}}}\n", key, *max_points)
+                if let Some(kind) = feedback {
+                    let mut options = String::new();
+                    options = options + "class=" + kind.css_class() + LATEX_OPTION_DELIM;
+                    if let Some(category) = category {
+                        options = options + "category=" + category + LATEX_OPTION_DELIM
+                    };
+                    if let Some(title) = title {
+                        options = options + "title=" + title + LATEX_OPTION_DELIM
+                    };
+                    format!("\\begin{{feedback}}[{}]{{{}}}{{{}}}\n", options, key, *max_points)
+                } else {
+                    format!("\\begin{{quiz}}{{{}}}{{{}}}\n", key, *max_points)
+                }
             }
             Self::AplusPickOne {
                 points,
@@ -859,6 +1090,7 @@ impl TreeNodeType {
                 is_correct,
                 is_pre_selected,
                 is_neutral,
+                ..
             } => {
                 let is_correct = if *is_neutral {
                     "\\undet"
@@ -874,6 +1106,7 @@ impl TreeNodeType {
                 label,
                 show_when_not_selected,
                 question_type,
+                ..
             } => {
                 let show_when_not_selected = if *show_when_not_selected { "" } else { "!" };
 
@@ -886,7 +1119,7 @@ impl TreeNodeType {
                     AplusQuestionnaireType::FreeText => String::new(),
                 };
 
-                format!("\\feedback{{{}{}}}{{", show_when_not_selected, reference)
+                format!("\\quizhint{{{}{}}}{{", show_when_not_selected, reference)
             }
             Self::AplusSubmit {
                 body_indent,
@@ -945,8 +1178,9 @@ impl TreeNodeType {
                 if *allow_assistant_viewing { options.push(String::from("allow-assistant-viewing")) };
                 if *allow_assistant_grading { options.push(String::from("allow-assistant-grading")) };
                 if *quiz { options.push(String::from("quiz")) };
-                if !lti.is_empty() { options.push(String::from("lti")) };
-                if !lti_resource_link_id.is_empty() { options.push(format!("resource_link_id={}", lti_resource_link_id))};
+                if !url.is_empty() { options.push(format!("url={}", url)) };
+                if !lti.is_empty() { options.push(format!("lti={}", lti)) };
+                if !lti_resource_link_id.is_empty() { options.push(format!("lti_resource_link_id={}", lti_resource_link_id))};
                 if *lti_open_in_iframe { options.push(String::from("lti_open_in_iframe")) };
                 if *lti_aplus_get_and_post { options.push(String::from("lti_aplus_get_and_post")) };
 
@@ -1119,6 +1353,136 @@ impl TreeNodeType {
                     options, key_for_output, inputs, title
                 )
             }
+
+            Self::AplusHiddenBlock { name, link_text, visible, .. } => {
+                /// The link text of A+ hidden blocks without a `label` option.
+                const DEFAULT_HIDDEN_BLOCK_LINK_TEXT: &str = "Show/hide";
+
+                format!(
+                    "\\begin{{hiddenblock}}{}{{{}}}{{{}}}\n",
+                    if *visible { "[visible]" } else { "" },
+                    name,
+                    match link_text {
+                        Some(text) => text.as_str(),
+                        None => DEFAULT_HIDDEN_BLOCK_LINK_TEXT,
+                    }
+                )
+            }
+            Self::AplusAnnotated { code_lines, callouts, n_of_annotations, .. } => {
+                let mut code = String::new();
+                for (index, line) in code_lines.iter().enumerate() {
+                    code += &escape_command_chars(line);
+                    for (_, number) in callouts.iter().filter(|(line_index, _)| *line_index == index) {
+                        code += &format!(" \\annotationcallout{{{}}}", number);
+                    }
+                    code += "\n";
+                }
+                format!(
+                    "\\begin{{annotatedcode}}\n{}\\end{{annotatedcode}}\n{}",
+                    code,
+                    if *n_of_annotations > 0 { "\\begin{annotations}\n" } else { "" }
+                )
+            }
+            Self::AplusAnnotation { number, .. } => format!("\\annotationitem{{{}}}\n", number),
+            Self::AplusDiv { .. } => match classes {
+                Some(classes) => format!("\\begin{{htmldiv}}[{}]\n", classes.join(" ")),
+                None => String::from("\\begin{htmldiv}\n"),
+            },
+            Self::AplusStyledTopic { title, .. } => match classes {
+                Some(classes) => format!("\\begin{{styledtopic}}[{}]{{{}}}\n", classes.join(" "), title),
+                None => format!("\\begin{{styledtopic}}{{{}}}\n", title),
+            },
+            Self::AplusRoundSettings {
+                open_time,
+                close_time,
+                late_time,
+                late_penalty,
+                audience,
+                hidden,
+                points_to_pass,
+                introduction,
+            } => {
+                let mut options = Vec::<String>::new();
+                if let Some(time) = open_time { options.push(format!("open-time={}", time)) }
+                if let Some(time) = close_time { options.push(format!("close-time={}", time)) }
+                if let Some(time) = late_time { options.push(format!("late-time={}", time)) }
+                if let Some(penalty) = late_penalty { options.push(format!("late-penalty={}", penalty)) }
+                if let Some(audience) = audience { options.push(format!("audience={}", audience)) }
+                if *hidden { options.push(String::from("hidden")) }
+                if let Some(points) = points_to_pass { options.push(format!("points-to-pass={}", points)) }
+                format!(
+                    "\\aplusroundsettings[{}]{{{}}}\n\n",
+                    options.join(LATEX_OPTION_DELIM),
+                    introduction.as_ref().map(|text| text.as_str()).unwrap_or("")
+                )
+            }
+            Self::AplusYouTube { video_id, title, duration, poster } => media_placeholder(
+                "YouTube video",
+                title,
+                poster,
+                Some(&format!("https://www.youtube.com/watch?v={}", video_id)),
+                &[("Duration", duration)],
+            ),
+            Self::AplusLocalVideo { video_path, title, duration, poster } => media_placeholder(
+                "Video",
+                title,
+                poster,
+                Some(video_path),
+                &[("Duration", duration)],
+            ),
+            Self::AplusEmbeddedPage { url, title, width, height } => {
+                let size = match (width, height) {
+                    (Some(width), Some(height)) => Some(format!("{} \\texttimes{{}} {}", width, height)),
+                    (Some(size), None) | (None, Some(size)) => Some(size.clone()),
+                    (None, None) => None,
+                };
+                media_placeholder("Embedded page", title, &None, Some(url), &[("Size", &size)])
+            }
+            Self::AplusStory { source, title } => {
+                media_placeholder("Interactive story", title, &None, Some(source), &[])
+            }
+            Self::AplusJSVee { visualization_id, title } => media_placeholder(
+                "Program visualization",
+                title,
+                &None,
+                None,
+                &[("Visualization", &Some(crate::utf8_to_latex::unicode_text_to_latex(visualization_id)))],
+            ),
+            Self::AplusREPLSession { lines } => {
+                use crate::common::AplusREPLLine;
+                let mut session = String::from("\\begin{repl}\n");
+                for line in lines.iter() {
+                    session += &match line {
+                        AplusREPLLine::Input { prompt, text } => format!(
+                            "\\replprompt{{{}}} \\replinput{{{}}}\n",
+                            escape_command_chars(prompt),
+                            escape_command_chars(text)
+                        ),
+                        AplusREPLLine::Output(text) if text.is_empty() => String::from("\n"),
+                        AplusREPLLine::Output(text) => {
+                            format!("\\reploutput{{{}}}\n", escape_command_chars(text))
+                        }
+                    };
+                }
+                session
+            }
+            Self::AplusLineRefCodeBlock { code_lines, line_labels, escape_char, .. } => {
+                // The labels are written between escape characters, that do not occur in the code
+                let mut code = String::new();
+                for (index, line) in code_lines.iter().enumerate() {
+                    code += line;
+                    if let Some(escape_char) = escape_char {
+                        for (_, label) in line_labels.iter().filter(|(line_index, _)| *line_index == index) {
+                            code += &format!("{}\\label{{{}}}{}", escape_char, label, escape_char);
+                        }
+                    }
+                    code += "\n";
+                }
+                match escape_char {
+                    Some(c) => format!("\\begin{{linerefcode}}[escapechar={}]\n{}", c, code),
+                    None => format!("\\begin{{linerefcode}}\n{}", code),
+                }
+            }
         };
 
         pre_string
@@ -1127,9 +1491,9 @@ impl TreeNodeType {
     /// Defines the text pattern each `TreeNodeType` variant ends with.
     fn larst_post_order_string(&self, ref_names:  Option<&Vec<String>>, rustla_options: &ruSTLaOptions) -> String {
         let post_string = match self {
-            Self::Abbreviation { .. } => todo!(),
+            Self::Abbreviation { .. } => "".to_string(),
             Self::AbsoluteURI { .. } => "".to_string(),
-            Self::Acronym { .. } => todo!(),
+            Self::Acronym { .. } => "".to_string(),
             Self::Address => todo!(),
             Self::Admonition { variant, .. } => {
                 use crate::doctree::directives::AdmonitionType;
@@ -1166,8 +1530,9 @@ impl TreeNodeType {
             Self::Description => todo!(),
             Self::DocInfo => todo!(),
             Self::DoctestBlock { .. } => todo!(),
-            Self::Document { .. } => if rustla_options.is_full_document() {
-                "\\end{document}\n".to_string()
+            Self::Document { print_index, .. } => if rustla_options.is_full_document() {
+                let printindex = if *print_index { "\\printindex\n" } else { "" };
+                format!("{}\\end{{document}}\n", printindex)
             } else {
                 String::new()
             },
@@ -1213,7 +1578,13 @@ impl TreeNodeType {
             Self::Literal { .. } => "".to_string(),
             Self::LiteralBlock { .. } => "\n\\end{codeblock}\n\n".to_string(),
             Self::Math { .. } => "".to_string(),
-            Self::MathBlock { .. } => "\\end{equation}\n\n".to_string(),
+            Self::MathBlock { math_block, name, nowrap, .. } => if *nowrap {
+                "\n".to_string()
+            } else {
+                let (environment, aligned) = math_block_environment(math_block, name.is_some());
+                let aligned = if aligned { "\\end{aligned}" } else { "" };
+                format!("{}\\end{{{}}}\n\n", aligned, environment)
+            },
             Self::OptionList { .. } => "\n".to_string(),
             Self::OptionListItem { .. } => "\n".to_string(),
             Self::OptionString { .. } => todo!(),
@@ -1221,7 +1592,7 @@ impl TreeNodeType {
             Self::Paragraph { .. } => "\n\n".to_string(),
             Self::ParsedLiteralBlock { .. } => "\n\n".to_string(),
             Self::Pending { .. } => todo!(),
-            Self::Problematic { .. } => todo!(),
+            Self::Problematic { .. } => "".to_string(),
             Self::Raw { .. } => "\\end{raw}\n\n".to_string(),
             Self::Reference { .. } => "".to_string(),
             Self::Revision { .. } => todo!(),
@@ -1236,7 +1607,7 @@ impl TreeNodeType {
             Self::SubstitutionReference { .. } => "".to_string(),
             Self::Subtitle { .. } => "".to_string(),
             Self::Superscript { .. } => "".to_string(),
-            Self::SystemMessage { .. } => todo!(),
+            Self::SystemMessage { .. } => "".to_string(),
             Self::Table { .. } => "\n".to_string(),
             Self::Target { .. } => "\n".to_string(),
             Self::TBody { .. } => "\n".to_string(),
@@ -1250,7 +1621,12 @@ impl TreeNodeType {
             Self::Topic { .. } => todo!(),
             Self::Transition { .. } => "\n".to_string(),
             Self::UnknownDirective { directive_name, .. } => {
-                format!("\\end{{{}}}\n\n", directive_name.to_lowercase())
+                match rustla_options.shared_unknown_directive_output() {
+                    UnknownDirectiveOutput::Environment => {
+                        format!("\\end{{{}}}\n\n", directive_name.to_lowercase())
+                    }
+                    _ => String::new(),
+                }
             }
             Self::Version { .. } => todo!(),
             Self::WhiteSpace { .. } => "".to_string(),
@@ -1258,18 +1634,40 @@ impl TreeNodeType {
             // ============================
             //  Sphinx specific directives
             // ============================
-            Self::SphinxOnly {
-                expression,
-                body_indent,
-            } => "\\end{only}\n\n".to_string(),
+            Self::SphinxOnly { .. } => "".to_string(),
             Self::SphinxCodeBlock { .. } => String::from("\\end{codeblock}\n\n"),
+            Self::SphinxVersionAdded { .. }
+            | Self::SphinxVersionChanged { .. }
+            | Self::SphinxDeprecated { .. } => "\\end{versionmodified}\n\n".to_string(),
+            Self::SphinxSeeAlso { .. } => "\\end{seealso}\n\n".to_string(),
+            Self::SphinxCentered => "\n\\end{center}\n\n".to_string(),
+            Self::SphinxHList { .. } => "\\end{hlist}\n\n".to_string(),
+            Self::SphinxHListColumn { .. } => "\\end{hlistcol}%\n".to_string(),
+            Self::SphinxGlossary { .. } => "\\end{glossarylist}\n\n".to_string(),
+            Self::SphinxTermReference { .. } => "".to_string(),
+            Self::SphinxCrossReference { .. } => "".to_string(),
+            Self::SphinxProductionList { .. } => "\\end{productionlist}\n\n".to_string(),
+            Self::SphinxProduction { .. } => "".to_string(),
+            Self::SphinxAuthor { .. } => "".to_string(),
+            Self::SphinxIndex { .. } => "".to_string(),
+            Self::SphinxInlineIndex { .. } => "".to_string(),
+            Self::SphinxKeyboard { .. } => "".to_string(),
+            Self::SphinxFile { .. } => "".to_string(),
+            Self::SphinxSample { .. } => "".to_string(),
+            Self::SphinxCommand { .. } => "".to_string(),
+            Self::SphinxGUILabel { .. } => "".to_string(),
+            Self::SphinxMenuSelection { .. } => "".to_string(),
 
             // ========================
             //  A+ specific directives
             // ========================
             Self::AplusPOI { .. } => "\\end{poi}\n\n".to_string(),
             Self::AplusColBreak => "".to_string(),
-            Self::AplusQuestionnaire { .. } => "\\end{quiz}\n\n".to_string(),
+            Self::AplusQuestionnaire { feedback, .. } => if feedback.is_some() {
+                "\\end{feedback}\n\n".to_string()
+            } else {
+                "\\end{quiz}\n\n".to_string()
+            },
             Self::AplusPickOne { .. } => "\\end{pick}\n\n".to_string(),
             Self::AplusPickAny { .. } => "\\end{pick}\n\n".to_string(),
             Self::AplusFreeText { .. } => "\\end{freetext}\n\n".to_string(),
@@ -1283,6 +1681,23 @@ impl TreeNodeType {
             Self::AplusSubmit { .. } => "\\end{submit}\n\n".to_string(),
             Self::AplusActiveElementInput { .. } => "\n\n".to_string(),
             Self::AplusActiveElementOutput { .. } => "\n\n".to_string(),
+            Self::AplusHiddenBlock { .. } => "\\end{hiddenblock}\n\n".to_string(),
+            Self::AplusAnnotated { n_of_annotations, .. } => if *n_of_annotations > 0 {
+                "\\end{annotations}\n\n".to_string()
+            } else {
+                "\n".to_string()
+            },
+            Self::AplusAnnotation { .. } => "".to_string(),
+            Self::AplusREPLSession { .. } => "\\end{repl}\n\n".to_string(),
+            Self::AplusDiv { .. } => "\\end{htmldiv}\n\n".to_string(),
+            Self::AplusRoundSettings { .. } => "".to_string(),
+            Self::AplusYouTube { .. } => "".to_string(),
+            Self::AplusLocalVideo { .. } => "".to_string(),
+            Self::AplusEmbeddedPage { .. } => "".to_string(),
+            Self::AplusStory { .. } => "".to_string(),
+            Self::AplusJSVee { .. } => "".to_string(),
+            Self::AplusStyledTopic { .. } => "\\end{styledtopic}\n\n".to_string(),
+            Self::AplusLineRefCodeBlock { .. } => "\\end{linerefcode}\n\n".to_string(),
         };
 
         post_string
@@ -1361,7 +1776,7 @@ impl TreeNodeType {
             Self::Literal { .. } => (None, ""),
             Self::LiteralBlock { .. } => (None, ""),
             Self::Math { .. } => (None, ""),
-            Self::MathBlock { name, .. } => (name.as_ref(), "rstlabel"),
+            Self::MathBlock { .. } => (None, "rstlabel"),
             Self::OptionList { .. } => (None, ""),
             Self::OptionListItem { .. } => (None, ""),
             Self::OptionString { .. } => (None, ""),
@@ -1406,6 +1821,27 @@ impl TreeNodeType {
             // ============================
             Self::SphinxOnly { .. } => (None, ""),
             Self::SphinxCodeBlock { .. } => (None, ""),
+            Self::SphinxVersionAdded { .. } => (None, ""),
+            Self::SphinxVersionChanged { .. } => (None, ""),
+            Self::SphinxDeprecated { .. } => (None, ""),
+            Self::SphinxSeeAlso { .. } => (None, ""),
+            Self::SphinxCentered => (None, ""),
+            Self::SphinxHList { .. } => (None, ""),
+            Self::SphinxHListColumn { .. } => (None, ""),
+            Self::SphinxGlossary { .. } => (None, ""),
+            Self::SphinxTermReference { .. } => (None, ""),
+            Self::SphinxCrossReference { .. } => (None, ""),
+            Self::SphinxProductionList { .. } => (None, ""),
+            Self::SphinxProduction { .. } => (None, ""),
+            Self::SphinxAuthor { .. } => (None, ""),
+            Self::SphinxIndex { .. } => (None, "label"),
+            Self::SphinxInlineIndex { .. } => (None, ""),
+            Self::SphinxKeyboard { .. } => (None, ""),
+            Self::SphinxFile { .. } => (None, ""),
+            Self::SphinxSample { .. } => (None, ""),
+            Self::SphinxCommand { .. } => (None, ""),
+            Self::SphinxGUILabel { .. } => (None, ""),
+            Self::SphinxMenuSelection { .. } => (None, ""),
 
             // ========================
             //  A+ specific directives
@@ -1423,6 +1859,19 @@ impl TreeNodeType {
             Self::AplusSubmit { .. } => (None, ""),
             Self::AplusActiveElementInput { .. } => (None, ""),
             Self::AplusActiveElementOutput { .. } => (None, ""),
+            Self::AplusHiddenBlock { .. } => (None, ""),
+            Self::AplusAnnotated { .. } => (None, ""),
+            Self::AplusAnnotation { .. } => (None, ""),
+            Self::AplusREPLSession { .. } => (None, ""),
+            Self::AplusDiv { .. } => (None, ""),
+            Self::AplusStyledTopic { .. } => (None, ""),
+            Self::AplusRoundSettings { .. } => (None, ""),
+            Self::AplusYouTube { .. } => (None, ""),
+            Self::AplusLocalVideo { .. } => (None, ""),
+            Self::AplusEmbeddedPage { .. } => (None, ""),
+            Self::AplusStory { .. } => (None, ""),
+            Self::AplusJSVee { .. } => (None, ""),
+            Self::AplusLineRefCodeBlock { .. } => (None, ""),
         };
 
         // TODO get ridi of ths first block by having directive options add the refnames and HTML classes
@@ -1473,6 +1922,165 @@ impl TreeNodeType {
 //  HELPERS
 // =========
 
+/// Chooses the LaTeX environment of a math block. Blocks with multiple lines separated by `\\\\`
+/// outside of nested environments and groups are aligned, and numbered blocks receive a single equation number.
+/// Returns the name of the environment and whether the contents need to be wrapped in an `aligned` environment.
+fn math_block_environment(math_block: &str, numbered: bool) -> (&'static str, bool) {
+    let mut depth: i32 = 0;
+    let mut multiline = false;
+    let mut chars = math_block.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' => depth += 1,
+            '}' => depth -= 1,
+            '\\' => {
+                let mut command = String::new();
+                while let Some(c) = chars.peek() {
+                    if c.is_ascii_alphabetic() {
+                        command.push(*c);
+                        chars.next();
+                    } else {
+                        break
+                    }
+                }
+                match command.as_str() {
+                    "begin" => depth += 1,
+                    "end" => depth -= 1,
+                    "" if chars.peek() == Some(&'\\') => {
+                        chars.next();
+                        if depth == 0 {
+                            multiline = true;
+                        }
+                    }
+                    "" => {
+                        // Skip escaped characters such as \{
+                        chars.next();
+                    }
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+    match (multiline, numbered) {
+        (true, true) => ("equation", true),
+        (true, false) => ("align*", false),
+        (false, true) => ("equation", false),
+        (false, false) => ("equation*", false),
+    }
+}
+
+/// Joins the segments of a `:samp:` or `:file:` role into a string,
+/// emphasizing the variable parts.
+fn sample_segments_to_string(segments: &[crate::common::SphinxSampleSegment]) -> String {
+    use crate::common::SphinxSampleSegment;
+    segments
+        .iter()
+        .map(|segment| match segment {
+            SphinxSampleSegment::Text(text) => text.to_string(),
+            SphinxSampleSegment::Variable(variable) => format!("\\emph{{{}}}", variable),
+        })
+        .collect()
+}
+
+/// Generates the `\\index` commands corresponding to the given Sphinx index entries.
+/// Pairs and triples are listed under each of their terms, as in Sphinx.
+fn index_commands(entries: &[crate::common::SphinxIndexEntry]) -> Vec<String> {
+    use crate::common::SphinxIndexEntryKind;
+
+    let mut commands = Vec::<String>::new();
+    for entry in entries {
+        let terms = entry
+            .terms
+            .iter()
+            .map(|term| index_term(term))
+            .collect::<Vec<String>>();
+        let format = if entry.main { "|textbf" } else { "" };
+        match entry.kind {
+            SphinxIndexEntryKind::Single => {
+                commands.push(format!("{}{}", terms.join("!"), format))
+            }
+            SphinxIndexEntryKind::Pair => {
+                commands.push(format!("{}!{}{}", terms[0], terms[1], format));
+                commands.push(format!("{}!{}{}", terms[1], terms[0], format));
+            }
+            SphinxIndexEntryKind::Triple => {
+                commands.push(format!("{}!{} {}{}", terms[0], terms[1], terms[2], format));
+                commands.push(format!("{}!{}, {}{}", terms[1], terms[2], terms[0], format));
+                commands.push(format!("{}!{} {}{}", terms[2], terms[0], terms[1], format));
+            }
+            SphinxIndexEntryKind::See => {
+                commands.push(format!("{}|see{{{}}}", terms[0], terms[1]))
+            }
+            SphinxIndexEntryKind::SeeAlso => {
+                commands.push(format!("{}|seealso{{{}}}", terms[0], terms[1]))
+            }
+        }
+    }
+    commands
+        .into_iter()
+        .map(|command| format!("\\index{{{}}}", command))
+        .collect()
+}
+
+/// Converts an index term to LaTeX and quotes the characters that have a special meaning to `makeindex`.
+fn index_term(term: &str) -> String {
+    use crate::utf8_to_latex::unicode_text_to_latex;
+    let term = unicode_text_to_latex(term);
+    let mut quoted = String::with_capacity(term.len());
+    for c in term.chars() {
+        if let '!' | '@' | '|' | '"' = c {
+            quoted.push('"');
+        }
+        quoted.push(c);
+    }
+    quoted
+}
+
+/// Generates the framed placeholder of an A+ media element, that cannot be shown on paper.
+/// The placeholder displays the title, a local poster image, a link to the media and the given details.
+fn media_placeholder(
+    kind: &str,
+    title: &Option<String>,
+    poster: &Option<String>,
+    url: Option<&String>,
+    details: &[(&str, &Option<String>)],
+) -> String {
+    let mut placeholder = format!(
+        "\\begin{{mediaplaceholder}}{{{}}}{{{}}}\n",
+        kind,
+        title.as_ref().map(|title| title.as_str()).unwrap_or("")
+    );
+    if let Some(poster) = poster {
+        placeholder += &format!("\\mediaposter{{{}}}\n", poster);
+    }
+    if let Some(url) = url {
+        placeholder += &format!("\\mediaurl{{{}}}\n", url);
+    }
+    for (name, value) in details.iter() {
+        if let Some(value) = value {
+            placeholder += &format!("\\mediadetail{{{}}}{{{}}}\n", name, value);
+        }
+    }
+    placeholder + "\\end{mediaplaceholder}\n\n"
+}
+
+/// Escapes the characters of a code line that are interpreted as commands
+/// in the verbatim environments of the A+ class file that allow commands,
+/// such as `annotatedcode` and `repl`.
+fn escape_command_chars(line: &str) -> String {
+    let mut escaped = String::with_capacity(line.len());
+    for c in line.chars() {
+        match c {
+            '\\' => escaped += "\\cmdbs{}",
+            '{' => escaped += "\\cmdlb{}",
+            '}' => escaped += "\\cmdrb{}",
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
 /// Returns the contents of the LaTeX class file required by Larst projects
 /// being compiled by `pdflatex` or `lualatex` as a `&'static str`.
 /// The string was authored by Tomi Janhunen.
@@ -1490,6 +2098,15 @@ fn aplus_cls_contents() -> &'static str {
 \NeedsTeXFormat{LaTeX2e}
 \ProvidesClass{aplus}
 
+% Class options: with "nohiddenblocks", the contents of A+ hidden blocks
+% that are not visible by default are left out of the document
+
+\newif\ifaplushiddenblocks
+\aplushiddenblockstrue
+\DeclareOption{nohiddenblocks}{\aplushiddenblocksfalse}
+\DeclareOption*{\PassOptionsToClass{\CurrentOption}{book}}
+\ProcessOptions\relax
+
 \LoadClass{book}
 \RequirePackage{url}
 \RequirePackage{graphicx}
@@ -1503,6 +2120,8 @@ fn aplus_cls_contents() -> &'static str {
 \RequirePackage{comment}
 \RequirePackage{environ}
 \RequirePackage{fancyvrb}
+\RequirePackage{listings}
+\RequirePackage{makeidx}
 \RequirePackage{hyperref}
 
 % Font issues
@@ -1562,10 +2181,155 @@ fn aplus_cls_contents() -> &'static str {
 
 \newcommand{\download}[2]{\par\texttt{#1}\footnote{\url{#2}}}
 \newcommand{\rstclass}[1]{}
-\newcommand{\feedback}[2]{\par\textbf{#1}. #2 \\}
+\newcommand{\quizhint}[2]{\par\textbf{#1}. #2 \\}
 
 \newenvironment{toggle}[1]{\textbf{#1}. }{}
 
+% Paragraph-level markup (Sphinx)
+
+\newenvironment{versionmodified}[2]{%
+\par\marginpar{\footnotesize\textbf{#1 #2}}\itshape%
+}{\par}
+
+\newenvironment{seealso}{%
+\par\noindent\begin{tcolorbox}[width=\textwidth,adjusted title=See also]%
+}{%
+\end{tcolorbox}}
+
+\newcommand{\hlistcolumns}{2}
+\newenvironment{hlist}[1]{%
+\par\noindent\renewcommand{\hlistcolumns}{#1}%
+}{\par}
+\newenvironment{hlistcol}{%
+\begin{minipage}[t]{\dimexpr\linewidth/\hlistcolumns\relax}%
+}{\end{minipage}}
+
+\newenvironment{glossarylist}{\par}{\par}
+
+\newcommand{\authorfootnote}[2]{\footnote{#1: #2}}
+\newcommand{\authormarginnote}[2]{\marginpar{\footnotesize\textbf{#1}: #2}}
+
+\newenvironment{productionlist}{%
+\par\noindent\begin{tabular}{@{}r@{\enspace}c@{\enspace}l@{}}%
+}{\end{tabular}\par}
+\newcommand{\production}[3]{\phantomsection\label{#1}\texttt{#2} & $::=$ & \texttt{#3}}
+\newcommand{\productioncontinued}[1]{& & \texttt{#1}}
+\newcommand{\productiontoken}[2]{\hyperref[#1]{#2}}
+
+% Inline semantic markup (Sphinx)
+
+\newcommand{\kbd}[1]{\fbox{\footnotesize\texttt{#1}}}
+\newcommand{\filepath}[1]{\texttt{#1}}
+\newcommand{\samp}[1]{\texttt{#1}}
+\newcommand{\command}[1]{\textbf{\texttt{#1}}}
+\newcommand{\guilabel}[1]{\textsf{\textbf{#1}}}
+\newcommand{\menuselectionseparator}{\,$\triangleright$\,}
+\newcommand{\menuselection}[1]{\guilabel{#1}}
+
+% System messages (docutils)
+
+\newenvironment{systemmessage}[1]{%
+\par\noindent\begin{tcolorbox}[width=\textwidth,colframe=red!75!black,adjusted title=System message #1]%
+}{%
+\end{tcolorbox}}
+\newcommand{\problematic}[1]{\textcolor{red}{#1}}
+
+
+% Hidden blocks (collapsible content such as model solutions)
+
+\makeatletter
+\newif\ifhiddenblock@shown
+\def\hiddenblock@collect{\Collect@Body\notext}
+\newenvironment{hiddenblock}[3][]{%
+  \hiddenblock@showntrue
+  \ifthenelse{\boolean{aplushiddenblocks}\OR\equal{#1}{visible}}{}{\hiddenblock@shownfalse}%
+  \ifhiddenblock@shown
+    \par\noindent\begin{tcolorbox}[width=\textwidth,breakable,adjusted title=#3]%
+  \else
+    \expandafter\hiddenblock@collect
+  \fi
+}{%
+  \ifhiddenblock@shown\end{tcolorbox}\fi
+}
+\makeatother
+
+% Escaped command characters in verbatim environments that allow commands
+
+\newcommand{\cmdbs}{\char92\relax}
+\newcommand{\cmdlb}{\char123\relax}
+\newcommand{\cmdrb}{\char125\relax}
+
+% Annotated code blocks: numbered callouts in the code, annotations below it
+
+\DefineVerbatimEnvironment{annotatedcode}{Verbatim}{commandchars=\\\{\}}
+\newcommand{\annotationcallout}[1]{\fbox{\scriptsize\textbf{#1}}}
+\newenvironment{annotations}{%
+\begin{list}{}{\setlength{\leftmargin}{2.5em}\setlength{\labelwidth}{2em}}%
+}{\end{list}}
+\newcommand{\annotationitem}[1]{\item[\annotationcallout{#1}]}
+
+% HTML div elements and CSS styled topics. The classes are only meaningful in HTML.
+
+\newenvironment{htmldiv}[1][]{\par}{\par}
+\newenvironment{styledtopic}[2][]{%
+\par\noindent\begin{tcolorbox}[width=\textwidth,adjusted title=#2]%
+}{%
+\end{tcolorbox}}
+
+% Exercise round settings (A+ meta directive), typeset as a schedule box
+
+\makeatletter
+\define@key{roundsettings}{open-time}{\def\rs@open{#1}}
+\define@key{roundsettings}{close-time}{\def\rs@close{#1}}
+\define@key{roundsettings}{late-time}{\def\rs@late{#1}}
+\define@key{roundsettings}{late-penalty}{\def\rs@penalty{#1}}
+\define@key{roundsettings}{audience}{\def\rs@audience{#1}}
+\define@key{roundsettings}{points-to-pass}{\def\rs@ptp{#1}}
+\define@key{roundsettings}{hidden}[]{\def\rs@hidden{Yes}}
+\newcommand{\rs@row}[2]{\ifx#2\@undefined\else\textbf{#1}: #2\par\fi}
+\newcommand{\aplusroundsettings}[2][]{%
+\begingroup
+\let\rs@open\@undefined\let\rs@close\@undefined\let\rs@late\@undefined
+\let\rs@penalty\@undefined\let\rs@audience\@undefined\let\rs@ptp\@undefined
+\let\rs@hidden\@undefined
+\setkeys{roundsettings}{#1}%
+\par\noindent\begin{tcolorbox}[width=\textwidth,adjusted title=Schedule]%
+\rs@row{Opens}{\rs@open}%
+\rs@row{Closes}{\rs@close}%
+\rs@row{Late submissions until}{\rs@late}%
+\rs@row{Late penalty}{\rs@penalty}%
+\rs@row{Points to pass}{\rs@ptp}%
+\rs@row{Audience}{\rs@audience}%
+\rs@row{Hidden}{\rs@hidden}%
+\ifthenelse{\equal{#2}{}}{}{\par #2}%
+\end{tcolorbox}%
+\endgroup}
+\makeatother
+
+% Placeholders of A+ media elements (videos, embedded pages and visualizations) on paper
+
+\newenvironment{mediaplaceholder}[2]{%
+\ifthenelse{\equal{#2}{}}{\def\mediatitle{#1}}{\def\mediatitle{#1: #2}}%
+\par\noindent\begin{tcolorbox}[width=\textwidth,adjusted title=\mediatitle]%
+}{%
+\end{tcolorbox}}
+\newcommand{\mediaposter}[1]{\begin{center}\includegraphics[width=0.6\linewidth]{#1}\end{center}}
+\newcommand{\mediaurl}{\par\url}
+\newcommand{\mediadetail}[2]{\par\textbf{#1}: #2}
+
+% REPL sessions: the input typed after a prompt is set apart from the output
+
+\DefineVerbatimEnvironment{repl}{Verbatim}{commandchars=\\\{\},frame=leftline}
+\newcommand{\replprompt}[1]{\textcolor{gray}{#1}}
+\newcommand{\replinput}[1]{\textbf{#1}}
+\newcommand{\reploutput}[1]{\textcolor{blue!50!black}{#1}}
+
+% Code blocks with line references: the labels are placed on numbered listing lines
+
+\lstnewenvironment{linerefcode}[1][]{%
+\lstset{basicstyle=\ttfamily\small,columns=fullflexible,keepspaces=true,%
+numbers=left,numberstyle=\tiny,#1}%
+}{}
 
 % Points of interest (slide-type objects within material)
 
@@ -1615,6 +2379,9 @@ fn aplus_cls_contents() -> &'static str {
 \define@key{submit}{lti}[]{\def\sbm@lti{#1}}
 \define@key{submit}{ajax}[]{\def\sbm@ajax{true}}
 \define@key{submit}{quiz}[]{\def\sbm@quiz{true}}
+\define@key{submit}{lti_resource_link_id}[]{\def\sbm@ltiresourcelinkid{#1}}
+\define@key{submit}{lti_open_in_iframe}[]{\def\sbm@ltiiframe{true}}
+\define@key{submit}{lti_aplus_get_and_post}[]{\def\sbm@ltigetandpost{true}}
 \makeatother
 
 \newenvironment{submit}[2][]{%
@@ -1652,6 +2419,19 @@ fn aplus_cls_contents() -> &'static str {
 \section*{Quiz #2}}{\setcounter{question}{1}}
 \makeatother
 
+% Feedback questionnaires
+
+\makeatletter
+\define@key{feedback}{class}[]{\def\fb@class{#1}}
+\define@key{feedback}{category}[]{\def\fb@category{#1}}
+\define@key{feedback}{title}[]{\def\fb@title{#1}}
+\newenvironment{feedback}[3][]{%
+\def\fb@title{Feedback}%
+\setkeys{feedback}{#1}%
+\setcounter{question}{1}%
+\section*{\fb@title}}{\setcounter{question}{1}}
+\makeatother
+
 % Pick
 
 \makeatletter
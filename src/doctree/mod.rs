@@ -9,6 +9,7 @@ Copyright © 2020 Santtu Söderholm
 */
 use std::collections::HashMap;
 
+mod aplus_exporter;
 mod larst_writer;
 mod node_categories;
 mod restructuredtext_transforms;
@@ -25,6 +26,19 @@ mod class_data;
 use class_data::ClassData;
 mod section_data;
 use section_data::SectionData;
+mod highlight_data;
+use highlight_data::HighlightData;
+mod repl_data;
+use repl_data::REPLData;
+mod exercise_data;
+use exercise_data::ExerciseData;
+mod index_data;
+use index_data::IndexData;
+mod table_data;
+use table_data::TableData;
+mod unknown_directive_report;
+pub mod system_message_data;
+use system_message_data::SystemMessageData;
 mod walkers;
 
 use crate::common::{
@@ -65,6 +79,27 @@ pub struct DocTree {
 
     /// A container that keeps track of known section styles and section levels corresponding to them.
     section_data: SectionData,
+
+    /// A container for the default code highlighting language set by the Sphinx `highlight` directive.
+    highlight_data: HighlightData,
+
+    /// A container for the result counter of the A+ REPL sessions.
+    repl_data: REPLData,
+
+    /// A container for the keys of the A+ exercises in the document.
+    exercise_data: ExerciseData,
+
+    /// A container for the entries of the Sphinx `index` directives and roles.
+    index_data: IndexData,
+
+    /// A container for the column specification of the next table, set by the Sphinx `tabularcolumns` directive.
+    table_data: TableData,
+
+    /// A container for the errors and warnings generated while processing the document.
+    system_message_data: SystemMessageData,
+
+    /// The tags that are considered defined, when evaluating the expressions of Sphinx `only` directives.
+    sphinx_only_tags: std::collections::HashSet<String>,
 }
 
 use std::path::PathBuf;
@@ -75,7 +110,10 @@ impl DocTree {
     /// A `DocTree` constructor.
     pub fn new(doc_name: PathBuf) -> Self {
         let root_id: NodeId = 0;
-        let root_data = TreeNodeType::Document;
+        let root_data = TreeNodeType::Document {
+            print_index: false,
+            authors: Vec::new(),
+        };
         let root_node = TreeNode::new(root_data, root_id, None, None);
 
         let file_stem: String = if let Some(path_os_str) = doc_name.file_stem() {
@@ -108,9 +146,21 @@ impl DocTree {
             hyperref_data: HyperrefData::new(),
             class_data: ClassData::new(),
             section_data: SectionData::new(),
+            highlight_data: HighlightData::new(),
+            repl_data: REPLData::new(),
+            exercise_data: ExerciseData::new(),
+            index_data: IndexData::new(),
+            table_data: TableData::new(),
+            system_message_data: SystemMessageData::new(),
+            sphinx_only_tags: std::collections::HashSet::new(),
         }
     }
 
+    /// Returns a shared reference to the path of the folder the source document is located in.
+    pub fn shared_file_folder(&self) -> &String {
+        &self.file_folder
+    }
+
     /// Returns the value of the contnained node counter.
     pub fn n_of_nodes(&self) -> NodeId {
         self.node_count
@@ -167,6 +217,7 @@ impl DocTree {
     pub fn push_data_and_focus(mut self, mut node_data: TreeNodeType) -> Result<Self, Self> {
         let target_labels = self.hyperref_actions(&mut node_data);
         let classes = self.classes();
+        self.table_actions(&mut node_data);
         match self
             .tree
             .push_data_and_focus(node_data, self.node_count, target_labels, classes)
@@ -189,6 +240,7 @@ impl DocTree {
     pub fn push_data(mut self, mut node_data: TreeNodeType) -> Result<Self, Self> {
         let target_labels = self.hyperref_actions(&mut node_data);
         let classes = self.classes();
+        self.table_actions(&mut node_data);
         match self
             .tree
             .push_data(node_data, self.node_count, target_labels, classes)
@@ -361,10 +413,71 @@ impl DocTree {
                     self.node_count,
                 );
             }
+            TreeNodeType::DefinitionListItem { term, .. } => {
+                // Terms of a glossary definition list can be referenced with the :term: role
+                if let Some(TreeNodeType::SphinxGlossary { .. }) = self.shared_parent_data() {
+                    target_labels.push(crate::common::glossary_term_label(term));
+                }
+                for label in target_labels.iter() {
+                    self.add_target(label, self.node_count);
+                }
+            }
+            TreeNodeType::SphinxTermReference { target_label, .. } => {
+                self.add_reference(target_label, self.node_count);
+            }
+            TreeNodeType::SphinxCrossReference { target_label, .. } => {
+                self.add_reference(target_label, self.node_count);
+            }
+            TreeNodeType::SphinxProduction { target_label, definition, .. } => {
+                self.add_target(target_label, self.node_count);
+                target_labels.push(target_label.clone());
+                for segment in definition.iter().flatten() {
+                    if let crate::common::SphinxProductionSegment::Token { target_label, .. } = segment {
+                        self.add_reference(target_label, self.node_count);
+                    }
+                }
+            }
+            TreeNodeType::SphinxIndex { entries } | TreeNodeType::SphinxInlineIndex { entries, .. } => {
+                self.index_data.add_entries(entries);
+                for label in target_labels.iter() {
+                    self.add_target(label, self.node_count);
+                }
+            }
+            TreeNodeType::MathBlock { name, .. } => {
+                // Named equations can be referenced with the :eq: role
+                if let Some(name) = name {
+                    *name = normalize_refname(name);
+                    self.add_target(name, self.node_count);
+                }
+                for label in target_labels.iter() {
+                    self.add_target(label, self.node_count);
+                }
+            }
+            TreeNodeType::AplusLineRefCodeBlock { line_labels, .. } => {
+                // The labeled lines can be referenced with the :lineref: role
+                for (_, label) in line_labels.iter() {
+                    self.add_target(label, self.node_count);
+                }
+                for label in target_labels.iter() {
+                    self.add_target(label, self.node_count);
+                }
+            }
+            TreeNodeType::Figure { name, .. } => {
+                // Figures can be referenced by their names with the :ref: and :numref: roles
+                // and the name is normalized so that the generated \label matches the references.
+                if let Some(name) = name {
+                    *name = normalize_refname(name);
+                    self.add_target(name, self.node_count);
+                }
+                for label in target_labels.iter() {
+                    self.add_target(label, self.node_count);
+                }
+            }
             TreeNodeType::Section {
                 title_text,
                 level,
                 line_style,
+                ..
             } => {
                 target_labels.push(normalize_refname(title_text));
                 for label in target_labels.iter() {
@@ -383,6 +496,30 @@ impl DocTree {
     }
 
 
+    /// Gives a pending column specification to the given node, if the node is a table.
+    fn table_actions(&mut self, node_data: &mut TreeNodeType) {
+        match node_data {
+            TreeNodeType::ListTable { column_spec, .. }
+            | TreeNodeType::CSVTable { column_spec, .. }
+            | TreeNodeType::Table { column_spec, .. } => {
+                if let Some(spec) = self.table_data.take_column_spec() {
+                    *column_spec = Some(spec);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Sets the LaTeX column specification of the next table in the document.
+    pub fn set_incoming_column_spec(&mut self, spec: &str) {
+        self.table_data.set_column_spec(spec)
+    }
+
+    /// Adds a class to the incoming classes, that are given to the next node pushed into the tree.
+    pub fn push_class(&mut self, class: &str) {
+        self.class_data.push_class(class)
+    }
+
     /// Returns the stack of incoming classes, if there are any.
     fn classes(&mut self) -> Option<Vec<String>> {
         let classes = self.class_data.mut_classes();
@@ -393,6 +530,81 @@ impl DocTree {
         }
     }
 
+    /// Sets the default language and line number threshold of the literal and code blocks that follow.
+    pub fn set_highlight_language(&mut self, language: &str, linenothreshold: Option<usize>) {
+        self.highlight_data.set(language, linenothreshold)
+    }
+
+    /// Returns the current default language of literal and code blocks, if one has been set.
+    pub fn shared_highlight_language(&self) -> Option<&String> {
+        self.highlight_data.shared_language()
+    }
+
+    /// Checks whether a literal or code block with the given number of lines should be given line numbers,
+    /// based on the `linenothreshold` of the latest `highlight` directive.
+    pub fn highlight_linenos(&self, n_of_lines: usize) -> bool {
+        self.highlight_data.linenos(n_of_lines)
+    }
+
+    /// Returns the number of the next result of an A+ REPL session,
+    /// recording it for the result number written in the source.
+    pub fn next_repl_res_number(&mut self, source_number: &str) -> usize {
+        self.repl_data.next_res_number(source_number)
+    }
+
+    /// Returns the number given to the A+ REPL result numbered `source_number` in the source.
+    pub fn repl_res_number(&self, source_number: &str) -> Option<usize> {
+        self.repl_data.res_number(source_number)
+    }
+
+    /// Starts the numbering of A+ REPL session results from zero.
+    pub fn reset_repl_res_count(&mut self) {
+        self.repl_data.reset()
+    }
+
+    /// Sets the tags that are considered defined in the expressions of Sphinx `only` directives.
+    /// The expressions are evaluated as the directives are parsed, so the tags have to be set before parsing.
+    pub fn set_sphinx_only_tags(&mut self, tags: std::collections::HashSet<String>) {
+        self.sphinx_only_tags = tags
+    }
+
+    /// Returns a shared reference to the tags defined for Sphinx `only` directives.
+    pub fn shared_sphinx_only_tags(&self) -> &std::collections::HashSet<String> {
+        &self.sphinx_only_tags
+    }
+
+    /// Records the key of an A+ exercise defined on the given line.
+    /// If the key is already taken in this document, returns the line of the earlier definition.
+    pub fn register_aplus_exercise_key(&mut self, key: &str, line: usize) -> Option<usize> {
+        self.exercise_data.register_key(key, line)
+    }
+
+    /// Records a new system message and returns the label generated for it.
+    /// The message is appended to the end of the document, when the tree is transformed.
+    pub fn add_system_message(
+        &mut self,
+        level: crate::common::SystemMessageLevel,
+        line: Option<usize>,
+        text: String,
+    ) -> String {
+        self.system_message_data.add_message(level, line, text)
+    }
+
+    /// Sets the given source line for the recently added system messages, whose line is not yet known.
+    pub fn set_missing_system_message_lines(&mut self, line: usize) {
+        self.system_message_data.set_missing_lines(line)
+    }
+
+    /// Returns a shared reference to the system messages generated so far.
+    pub fn shared_system_messages(&self) -> &Vec<system_message_data::SystemMessage> {
+        self.system_message_data.shared_messages()
+    }
+
+    /// Returns a shared reference to the index entries encountered so far.
+    pub fn shared_index_entries(&self) -> &Vec<crate::common::SphinxIndexEntry> {
+        self.index_data.shared_entries()
+    }
+
     /// Returns a shared reference to the current node .
     pub fn shared_node(&self) -> &TreeNode {
         self.tree.shared_node()
@@ -682,6 +894,7 @@ impl DocTree {
             level: section_level,
             title_text: title_text.to_string(),
             line_style: section_style,
+            authors: Vec::new(),
         }
     }
 
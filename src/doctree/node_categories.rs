@@ -272,6 +272,59 @@ pub const SPHINX_ONLY_CATEGORIES: &[NodeCategory] =
 pub const SPHINX_CODE_BLOCK_CATEGORIES: &[NodeCategory] =
     &[NodeCategory::Body, NodeCategory::SimpleBody];
 
+pub const SPHINX_VERSION_ADDED_CATEGORIES: &[NodeCategory] =
+    &[NodeCategory::Body, NodeCategory::CompoundBody];
+
+pub const SPHINX_VERSION_CHANGED_CATEGORIES: &[NodeCategory] =
+    &[NodeCategory::Body, NodeCategory::CompoundBody];
+
+pub const SPHINX_DEPRECATED_CATEGORIES: &[NodeCategory] =
+    &[NodeCategory::Body, NodeCategory::CompoundBody];
+
+pub const SPHINX_SEE_ALSO_CATEGORIES: &[NodeCategory] =
+    &[NodeCategory::Body, NodeCategory::CompoundBody];
+
+pub const SPHINX_CENTERED_CATEGORIES: &[NodeCategory] =
+    &[NodeCategory::Body, NodeCategory::SimpleBody];
+
+pub const SPHINX_HLIST_CATEGORIES: &[NodeCategory] =
+    &[NodeCategory::Body, NodeCategory::CompoundBody];
+
+pub const SPHINX_HLIST_COLUMN_CATEGORIES: &[NodeCategory] =
+    &[NodeCategory::SubBody, NodeCategory::CompoundSubBody];
+
+pub const SPHINX_GLOSSARY_CATEGORIES: &[NodeCategory] =
+    &[NodeCategory::Body, NodeCategory::CompoundBody];
+
+pub const SPHINX_TERM_REFERENCE_CATEGORIES: &[NodeCategory] =
+    &[NodeCategory::Inline, NodeCategory::Referential];
+
+pub const SPHINX_CROSS_REFERENCE_CATEGORIES: &[NodeCategory] =
+    &[NodeCategory::Inline, NodeCategory::Referential];
+
+pub const SPHINX_PRODUCTION_LIST_CATEGORIES: &[NodeCategory] =
+    &[NodeCategory::Body, NodeCategory::CompoundBody];
+
+pub const SPHINX_PRODUCTION_CATEGORIES: &[NodeCategory] = &[NodeCategory::SubBody];
+
+pub const SPHINX_AUTHOR_CATEGORIES: &[NodeCategory] = &[NodeCategory::Body];
+
+pub const SPHINX_INDEX_CATEGORIES: &[NodeCategory] = &[NodeCategory::Body];
+
+pub const SPHINX_INLINE_INDEX_CATEGORIES: &[NodeCategory] = &[NodeCategory::Inline];
+
+pub const SPHINX_KEYBOARD_CATEGORIES: &[NodeCategory] = &[NodeCategory::Inline];
+
+pub const SPHINX_FILE_CATEGORIES: &[NodeCategory] = &[NodeCategory::Inline];
+
+pub const SPHINX_SAMPLE_CATEGORIES: &[NodeCategory] = &[NodeCategory::Inline];
+
+pub const SPHINX_COMMAND_CATEGORIES: &[NodeCategory] = &[NodeCategory::Inline];
+
+pub const SPHINX_GUI_LABEL_CATEGORIES: &[NodeCategory] = &[NodeCategory::Inline];
+
+pub const SPHINX_MENU_SELECTION_CATEGORIES: &[NodeCategory] = &[NodeCategory::Inline];
+
 // ========================
 //  A+ specific directives
 // ========================
@@ -315,3 +368,30 @@ pub const APLUS_ACTIVE_ELEMENT_INPUT_CATEGORIES: &[NodeCategory] =
 
 pub const APLUS_ACTIVE_ELEMENT_OUTPUT_CATEGORIES: &[NodeCategory] =
     &[NodeCategory::Body, NodeCategory::SimpleBody];
+
+pub const APLUS_HIDDEN_BLOCK_CATEGORIES: &[NodeCategory] =
+    &[NodeCategory::Body, NodeCategory::CompoundBody];
+
+pub const APLUS_ANNOTATED_CATEGORIES: &[NodeCategory] =
+    &[NodeCategory::Body, NodeCategory::CompoundBody];
+
+pub const APLUS_ANNOTATION_CATEGORIES: &[NodeCategory] =
+    &[NodeCategory::SubBody, NodeCategory::CompoundSubBody];
+
+pub const APLUS_LINEREF_CODE_BLOCK_CATEGORIES: &[NodeCategory] =
+    &[NodeCategory::Body, NodeCategory::SimpleBody];
+
+pub const APLUS_REPL_SESSION_CATEGORIES: &[NodeCategory] =
+    &[NodeCategory::Body, NodeCategory::SimpleBody];
+
+pub const APLUS_DIV_CATEGORIES: &[NodeCategory] =
+    &[NodeCategory::Body, NodeCategory::CompoundBody];
+
+pub const APLUS_STYLED_TOPIC_CATEGORIES: &[NodeCategory] =
+    &[NodeCategory::Body, NodeCategory::CompoundBody];
+
+pub const APLUS_ROUND_SETTINGS_CATEGORIES: &[NodeCategory] =
+    &[NodeCategory::Body, NodeCategory::SimpleBody];
+
+pub const APLUS_MEDIA_CATEGORIES: &[NodeCategory] =
+    &[NodeCategory::Body, NodeCategory::SimpleBody];
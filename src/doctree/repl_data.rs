@@ -0,0 +1,47 @@
+/*!
+A submodule that defines the container `REPLData`, that a `DocTree` holds on to.
+The Scala REPL names the results of unnamed expressions `res0`, `res1` and so on.
+The A+ `repl` sessions of a document continue the numbering of the previous session,
+until an A+ `repl-res-count-reset` directive starts it from zero again.
+
+Copyright © 2020 Santtu Söderholm
+*/
+
+use std::collections::HashMap;
+
+/// A container for the REPL result counter of a document.
+pub struct REPLData {
+    res_count: usize,
+
+    /// The result numbers written in the source, mapped to the numbers given to them in the document.
+    res_numbers: HashMap<String, usize>,
+}
+
+impl REPLData {
+    pub fn new() -> Self {
+        Self {
+            res_count: 0,
+            res_numbers: HashMap::new(),
+        }
+    }
+
+    /// Returns the number of the next REPL result and increments the counter.
+    /// The number is recorded for the result number written in the source.
+    pub fn next_res_number(&mut self, source_number: &str) -> usize {
+        let number = self.res_count;
+        self.res_count += 1;
+        self.res_numbers.insert(source_number.to_string(), number);
+        number
+    }
+
+    /// Returns the number given to the REPL result, that is numbered `source_number` in the source.
+    pub fn res_number(&self, source_number: &str) -> Option<usize> {
+        self.res_numbers.get(source_number).copied()
+    }
+
+    /// Starts the numbering of REPL results from zero.
+    pub fn reset(&mut self) {
+        self.res_count = 0;
+        self.res_numbers.clear();
+    }
+}
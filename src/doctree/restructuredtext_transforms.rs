@@ -7,16 +7,137 @@ Copyright © 2020 Santtu Söderholm
 */
 use crate::doctree::tree_node::TreeNode;
 use crate::doctree::tree_zipper::TreeZipper;
+use crate::doctree::unknown_directive_report::UnknownDirectiveReport;
 use crate::doctree::DocTree;
+use crate::rustla_options::UnknownDirectiveOutput;
+
+use crate::common::NodeId;
+
+use std::collections::{HashMap, HashSet};
 
 impl DocTree {
     /// Modifies `self.tree` with the known reStrucuturedText transforms.
     pub fn perform_restructuredtext_transforms(mut self) -> Self {
         self = self.walk_to_root();
+        self.report_unresolved_term_references();
+        let has_index_entries = ! self.index_data.is_empty();
+        let mut author_names = Vec::<String>::new();
+        self.shared_node().collect_sphinx_author_names(&mut author_names);
+        if let crate::doctree::tree_node_types::TreeNodeType::Document { print_index, authors } = self.mut_node_data() {
+            *print_index = has_index_entries;
+            *authors = author_names;
+        }
+        self.resolve_sphinx_cross_references();
         self.tree = self.tree.perform_restructuredtext_transforms();
         self = self.walk_to_root();
+        self = self.place_system_messages();
+        self
+    }
+
+    /// Appends the system messages that have not yet been placed into the tree
+    /// to the end of the document as `SystemMessage` nodes.
+    /// Expects `self` to be focused on the root node.
+    fn place_system_messages(mut self) -> Self {
+        use crate::doctree::tree_node_types::TreeNodeType;
+
+        let mut referenced_labels = HashSet::<String>::new();
+        self.shared_node().collect_problematic_message_labels(&mut referenced_labels);
+
+        for message in self.system_message_data.take_unplaced_messages() {
+            let message_data = TreeNodeType::SystemMessage {
+                level: message.level,
+                line: message.line,
+                text: message.text,
+                referenced: referenced_labels.contains(&message.label),
+                label: message.label,
+            };
+            self = match self.push_data(message_data) {
+                Ok(tree) => tree,
+                Err(tree) => {
+                    eprintln!("Could not append a system message to the end of the document...");
+                    tree
+                }
+            };
+        }
+        self
+    }
+
+    /// Generates a report of the directives in the document that ruSTLa does not recognize.
+    /// Expects `self` to be focused on the root node.
+    pub fn unknown_directive_report(&self) -> UnknownDirectiveReport {
+        let mut report = UnknownDirectiveReport::new();
+        self.shared_node().collect_unknown_directives(&mut report);
+        report
+    }
+
+    /// Prepares the unknown directives of the document for writing with the given output mode.
+    /// Dropped directives are removed along with their contents, and directives written out
+    /// as comments or literal text lose their parsed contents, as their source lines are written instead.
+    pub fn perform_unknown_directive_transforms(mut self, output: &UnknownDirectiveOutput) -> Self {
+        self = self.walk_to_root();
+        self.mut_node().transform_unknown_directive_children(output);
         self
     }
+
+    /// Reports references to glossary terms, that do not match any of the terms
+    /// defined in the glossaries of the document.
+    /// Expects `self` to be focused on the root node.
+    fn report_unresolved_term_references(&mut self) {
+        let mut term_references = Vec::<(String, Option<usize>)>::new();
+        self.shared_node().collect_term_references(&mut term_references);
+        let mut unresolved_references = term_references
+            .into_iter()
+            .filter(|(label, _)| ! self.shared_targets().contains_key(label))
+            .collect::<Vec<(String, Option<usize>)>>();
+        // Each undefined term is reported once, on the first line it is referenced on
+        unresolved_references.sort();
+        unresolved_references.dedup_by(|(label, _), (previous_label, _)| label == previous_label);
+        for (label, line) in unresolved_references {
+            self.add_system_message(
+                crate::common::SystemMessageLevel::Warning,
+                line,
+                format!(
+                    "Reference to an undefined glossary term \"{}\".",
+                    label.trim_start_matches(crate::common::GLOSSARY_TERM_LABEL_PREFIX)
+                ),
+            );
+        }
+    }
+
+    /// Looks up the targets of the Sphinx cross-references in the document.
+    /// A `:ref:` without an explicit title to a section receives the title of the section
+    /// as its displayed text, and references to unknown targets are reported.
+    /// Expects `self` to be focused on the root node.
+    fn resolve_sphinx_cross_references(&mut self) {
+        let mut section_titles = HashMap::<NodeId, String>::new();
+        self.shared_node().collect_section_titles(&mut section_titles);
+        let label_titles = self
+            .shared_targets()
+            .iter()
+            .filter_map(|(label, id)| {
+                section_titles
+                    .get(id)
+                    .map(|title| (label.clone(), title.clone()))
+            })
+            .collect::<HashMap<String, String>>();
+        let targets = self.shared_targets().clone();
+        let mut unresolved_labels = Vec::<(String, Option<usize>)>::new();
+        self.mut_node().resolve_sphinx_cross_references(
+            &targets,
+            &label_titles,
+            &mut unresolved_labels,
+        );
+        // Each undefined label is reported once, on the first line it is referenced on
+        unresolved_labels.sort();
+        unresolved_labels.dedup_by(|(label, _), (previous_label, _)| label == previous_label);
+        for (label, line) in unresolved_labels {
+            self.add_system_message(
+                crate::common::SystemMessageLevel::Warning,
+                line,
+                format!("Cross-reference to an undefined label \"{}\".", label),
+            );
+        }
+    }
 }
 
 impl TreeZipper {
@@ -232,6 +353,27 @@ impl TreeNode {
             // ============================
             TreeNodeType::SphinxOnly { .. } => {}
             TreeNodeType::SphinxCodeBlock { .. } => {}
+            TreeNodeType::SphinxVersionAdded { .. } => {}
+            TreeNodeType::SphinxVersionChanged { .. } => {}
+            TreeNodeType::SphinxDeprecated { .. } => {}
+            TreeNodeType::SphinxSeeAlso { .. } => {}
+            TreeNodeType::SphinxCentered => {}
+            TreeNodeType::SphinxHList { .. } => {}
+            TreeNodeType::SphinxHListColumn { .. } => {}
+            TreeNodeType::SphinxGlossary { .. } => {}
+            TreeNodeType::SphinxTermReference { .. } => {}
+            TreeNodeType::SphinxCrossReference { .. } => {}
+            TreeNodeType::SphinxProductionList { .. } => {}
+            TreeNodeType::SphinxProduction { .. } => {}
+            TreeNodeType::SphinxAuthor { .. } => {}
+            TreeNodeType::SphinxIndex { .. } => {}
+            TreeNodeType::SphinxInlineIndex { .. } => {}
+            TreeNodeType::SphinxKeyboard { .. } => {}
+            TreeNodeType::SphinxFile { .. } => {}
+            TreeNodeType::SphinxSample { .. } => {}
+            TreeNodeType::SphinxCommand { .. } => {}
+            TreeNodeType::SphinxGUILabel { .. } => {}
+            TreeNodeType::SphinxMenuSelection { .. } => {}
 
             // ========================
             //  A+ specific directives
@@ -249,6 +391,19 @@ impl TreeNode {
             TreeNodeType::AplusSubmit { .. } => {}
             TreeNodeType::AplusActiveElementInput { .. } => {}
             TreeNodeType::AplusActiveElementOutput { .. } => {}
+            TreeNodeType::AplusHiddenBlock { .. } => {}
+            TreeNodeType::AplusAnnotated { .. } => {}
+            TreeNodeType::AplusAnnotation { .. } => {}
+            TreeNodeType::AplusLineRefCodeBlock { .. } => {}
+            TreeNodeType::AplusREPLSession { .. } => {}
+            TreeNodeType::AplusDiv { .. } => {}
+            TreeNodeType::AplusStyledTopic { .. } => {}
+            TreeNodeType::AplusRoundSettings { .. } => {}
+            TreeNodeType::AplusYouTube { .. } => {}
+            TreeNodeType::AplusLocalVideo { .. } => {}
+            TreeNodeType::AplusEmbeddedPage { .. } => {}
+            TreeNodeType::AplusStory { .. } => {}
+            TreeNodeType::AplusJSVee { .. } => {}
         };
 
         if let Some(children) = self.mut_children() {
@@ -258,3 +413,163 @@ impl TreeNode {
         }
     }
 }
+
+impl TreeNode {
+    /// Records the unknown directives in the subtree rooted at `self` into the given report.
+    fn collect_unknown_directives(&self, report: &mut UnknownDirectiveReport) {
+        use crate::doctree::tree_node_types::TreeNodeType;
+
+        if let TreeNodeType::UnknownDirective { directive_name, options, line, .. } = self.shared_data() {
+            report.add_occurrence(directive_name, *line, options.keys());
+        }
+        if let Some(children) = self.shared_children() {
+            for child in children.iter() {
+                child.collect_unknown_directives(report);
+            }
+        }
+    }
+
+    /// Recursively removes or empties the `UnknownDirective` children of `self`,
+    /// based on the given output mode.
+    fn transform_unknown_directive_children(&mut self, output: &UnknownDirectiveOutput) {
+        use crate::doctree::tree_node_types::TreeNodeType;
+
+        if let Some(children) = self.mut_children() {
+            if let UnknownDirectiveOutput::Drop = output {
+                children.retain(|child| match child.shared_data() {
+                    TreeNodeType::UnknownDirective { .. } => false,
+                    _ => true,
+                });
+            }
+            for child in children.iter_mut() {
+                match (child.shared_data(), output) {
+                    (TreeNodeType::UnknownDirective { .. }, UnknownDirectiveOutput::Comment)
+                    | (TreeNodeType::UnknownDirective { .. }, UnknownDirectiveOutput::Literal) => {
+                        if let Some(grandchildren) = child.mut_children() {
+                            grandchildren.clear()
+                        }
+                    }
+                    _ => child.transform_unknown_directive_children(output),
+                }
+            }
+        }
+    }
+
+    /// Collects the target labels and source lines of the `:term:` references
+    /// in the subtree rooted at `self` into `references`.
+    fn collect_term_references(&self, references: &mut Vec<(String, Option<usize>)>) {
+        use crate::doctree::tree_node_types::TreeNodeType;
+
+        if let TreeNodeType::SphinxTermReference { target_label, line, .. } = self.shared_data() {
+            references.push((target_label.clone(), *line));
+        }
+        if let Some(children) = self.shared_children() {
+            for child in children.iter() {
+                child.collect_term_references(references);
+            }
+        }
+    }
+
+    /// Collects the system message labels the `Problematic` nodes in the subtree rooted at `self` link to.
+    fn collect_problematic_message_labels(&self, labels: &mut HashSet<String>) {
+        use crate::doctree::tree_node_types::TreeNodeType;
+
+        if let TreeNodeType::Problematic { message_label, .. } = self.shared_data() {
+            labels.insert(message_label.clone());
+        }
+        if let Some(children) = self.shared_children() {
+            for child in children.iter() {
+                child.collect_problematic_message_labels(labels);
+            }
+        }
+    }
+
+    /// Collects the names of the authors given by Sphinx `sectionauthor` and `codeauthor` directives
+    /// in the subtree rooted at `self` into `names`, in document order and without duplicates.
+    fn collect_sphinx_author_names(&self, names: &mut Vec<String>) {
+        use crate::doctree::tree_node_types::TreeNodeType;
+
+        if let TreeNodeType::SphinxAuthor { name, .. } = self.shared_data() {
+            if ! names.contains(name) {
+                names.push(name.clone());
+            }
+        }
+        if let Some(children) = self.shared_children() {
+            for child in children.iter() {
+                child.collect_sphinx_author_names(names);
+            }
+        }
+    }
+
+    /// Collects the titles of the sections in the subtree rooted at `self` into `titles`,
+    /// keyed by the node identifiers of the sections.
+    fn collect_section_titles(&self, titles: &mut HashMap<NodeId, String>) {
+        use crate::doctree::tree_node_types::TreeNodeType;
+
+        if let TreeNodeType::Section { title_text, .. } = self.shared_data() {
+            titles.insert(self.id(), title_text.clone());
+        }
+        if let Some(children) = self.shared_children() {
+            for child in children.iter() {
+                child.collect_section_titles(titles);
+            }
+        }
+    }
+
+    /// Fills in the missing titles of the Sphinx `:ref:`s in the subtree rooted at `self`
+    /// from `label_titles` and records the labels not found in `targets`.
+    /// Document references are not checked, as the other documents are not known.
+    /// Instead, those not pointing to a label of this document are turned into plain text,
+    /// so that no links to undefined labels are written.
+    /// The same is done for the tokens of Sphinx production lists that name no production.
+    fn resolve_sphinx_cross_references(
+        &mut self,
+        targets: &HashMap<String, NodeId>,
+        label_titles: &HashMap<String, String>,
+        unresolved_labels: &mut Vec<(String, Option<usize>)>,
+    ) {
+        use crate::common::{SphinxCrossReferenceKind, SphinxProductionSegment};
+        use crate::doctree::tree_node_types::TreeNodeType;
+
+        if let TreeNodeType::SphinxCrossReference {
+            kind,
+            displayed_text,
+            target_label,
+            line,
+        } = self.mut_data()
+        {
+            if *kind != SphinxCrossReferenceKind::Doc && !targets.contains_key(target_label) {
+                unresolved_labels.push((target_label.clone(), *line));
+            }
+            if *kind == SphinxCrossReferenceKind::Ref && displayed_text.is_none() {
+                if let Some(title) = label_titles.get(target_label) {
+                    *displayed_text = Some(title.clone());
+                }
+            }
+            if *kind == SphinxCrossReferenceKind::Doc && !targets.contains_key(target_label) {
+                let text = displayed_text.take().unwrap_or(target_label.clone());
+                *self.mut_data() = TreeNodeType::Text {
+                    text: crate::utf8_to_latex::unicode_text_to_latex(text.as_str()),
+                };
+            }
+        }
+        if let TreeNodeType::SphinxProduction { definition, .. } = self.mut_data() {
+            for segment in definition.iter_mut().flatten() {
+                let unresolved_text = match segment {
+                    SphinxProductionSegment::Token { text, target_label } if !targets.contains_key(target_label) => {
+                        Some(text.clone())
+                    }
+                    _ => None,
+                };
+                if let Some(text) = unresolved_text {
+                    *segment = SphinxProductionSegment::Text(text);
+                }
+            }
+        }
+        if let Some(children) = self.mut_children() {
+            for child in children.iter_mut() {
+                child.resolve_sphinx_cross_references(targets, label_titles, unresolved_labels);
+            }
+        }
+    }
+}
@@ -0,0 +1,75 @@
+/*!
+A submodule that defines the container `SystemMessageData`, that a `DocTree` holds on to.
+The errors and warnings generated while a document is parsed and transformed are collected here,
+before they are appended to the end of the document tree as `SystemMessage` nodes.
+
+Copyright © 2020 Santtu Söderholm
+*/
+
+use crate::common::SystemMessageLevel;
+
+/// A single error or warning generated while processing a document.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SystemMessage {
+    pub level: SystemMessageLevel,
+    /// The source line the message concerns, if known.
+    pub line: Option<usize>,
+    pub text: String,
+    /// The label that `Problematic` nodes use to link to this message.
+    pub label: String,
+}
+
+/// The prefix of the labels generated for system messages.
+const SYSTEM_MESSAGE_LABEL_PREFIX: &str = "system-message-";
+
+/// A container for the system messages of a document.
+pub struct SystemMessageData {
+    /// All messages generated thus far, in the order they were generated.
+    messages: Vec<SystemMessage>,
+
+    /// The number of messages that have already been appended to the document tree.
+    n_of_placed_messages: usize,
+}
+
+impl SystemMessageData {
+    pub fn new() -> Self {
+        Self {
+            messages: Vec::new(),
+            n_of_placed_messages: 0,
+        }
+    }
+
+    /// Records a new message and returns the label generated for it.
+    pub fn add_message(&mut self, level: SystemMessageLevel, line: Option<usize>, text: String) -> String {
+        let label = format!("{}{}", SYSTEM_MESSAGE_LABEL_PREFIX, self.messages.len() + 1);
+        self.messages.push(SystemMessage {
+            level: level,
+            line: line,
+            text: text,
+            label: label.clone(),
+        });
+        label
+    }
+
+    /// Sets the given line for the messages whose source line is not yet known.
+    pub fn set_missing_lines(&mut self, line: usize) {
+        for message in self.messages.iter_mut().skip(self.n_of_placed_messages) {
+            if message.line.is_none() {
+                message.line = Some(line)
+            }
+        }
+    }
+
+    /// Retrieves a shared reference to all of the messages generated thus far.
+    pub fn shared_messages(&self) -> &Vec<SystemMessage> {
+        &self.messages
+    }
+
+    /// Returns the messages that have not yet been placed into the document tree
+    /// and marks them as placed.
+    pub fn take_unplaced_messages(&mut self) -> Vec<SystemMessage> {
+        let unplaced = self.messages[self.n_of_placed_messages..].to_vec();
+        self.n_of_placed_messages = self.messages.len();
+        unplaced
+    }
+}
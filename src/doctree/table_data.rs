@@ -0,0 +1,31 @@
+/*!
+A submodule that defines the container `TableData`, that a `DocTree` holds on to.
+If a Sphinx `tabularcolumns` directive is encountered, the LaTeX column specification
+given by it is stored in the container, until the next table node takes it over.
+
+Copyright © 2020 Santtu Söderholm
+*/
+
+/// A container for table settings that are waiting for the next table in the document.
+pub struct TableData {
+    incoming_column_spec: Option<String>,
+}
+
+impl TableData {
+    pub fn new() -> Self {
+        Self {
+            incoming_column_spec: None,
+        }
+    }
+
+    /// Sets the column specification that will be given to the next table,
+    /// replacing a possible previous specification that was not used.
+    pub fn set_column_spec(&mut self, spec: &str) {
+        self.incoming_column_spec = Some(spec.to_string())
+    }
+
+    /// Removes the incoming column specification from the container and returns it, if there is one.
+    pub fn take_column_spec(&mut self) -> Option<String> {
+        self.incoming_column_spec.take()
+    }
+}
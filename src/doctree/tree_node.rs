@@ -50,6 +50,7 @@ impl TreeNode {
         }) {
             Some(Vec::new())
         } else if let TreeNodeType::Paragraph { .. }
+        | TreeNodeType::SphinxCentered
         | TreeNodeType::AplusPickChoice { .. }
         | TreeNodeType::AplusQuestionnaireHint { .. } = data_variant
         {
@@ -133,6 +134,11 @@ impl TreeNode {
         &self.refnames
     }
 
+    /// Returns a shared reference to the HTML classes of the node.
+    pub fn shared_classes(&self) -> &Option<Vec<String>> {
+        &self.classes
+    }
+
     /// Sets the target label of the node to given `Option<Vec<String>>`.
     pub fn set_target_label(&mut self, label: Option<Vec<String>>) {
         self.refnames = label;
@@ -153,21 +159,9 @@ impl TreeNode {
         use crate::doctree::node_categories::NodeCategory;
 
         match self.data {
-            TreeNodeType::Abbreviation { .. } => {
-                if node_data.node_categories().any(|cat| {
-                    if let NodeCategory::Inline = cat {
-                        true
-                    } else {
-                        false
-                    }
-                }) {
-                    true
-                } else {
-                    false
-                }
-            }
+            TreeNodeType::Abbreviation { .. } => false,
             TreeNodeType::AbsoluteURI { .. } => false,
-            TreeNodeType::Acronym { .. } => false, // No documentation on docutils!
+            TreeNodeType::Acronym { .. } => false,
             TreeNodeType::Address => {
                 if node_data.node_categories().any(|cat| {
                     if let NodeCategory::Inline = cat {
@@ -711,9 +705,7 @@ impl TreeNode {
             TreeNodeType::Pending { .. } => {
                 todo!("No information on \"Pending\" node children in docutils documentation...")
             }
-            TreeNodeType::Problematic { .. } => todo!(
-                "No information on \"Problematic\" node children in docutils documentation..."
-            ),
+            TreeNodeType::Problematic { .. } => false,
             TreeNodeType::Raw { .. } => {
                 todo!("What is a \"Raw\" element supposed to be, exactly...?")
             }
@@ -824,7 +816,7 @@ impl TreeNode {
                     false
                 }
             }
-            TreeNodeType::SystemMessage { .. } => todo!(),
+            TreeNodeType::SystemMessage { .. } => false,
             TreeNodeType::Table { .. } => todo!(),
             TreeNodeType::Target { .. } => false,
             TreeNodeType::TBody { .. } => todo!(),
@@ -900,6 +892,99 @@ impl TreeNode {
                 }
             }
             TreeNodeType::SphinxCodeBlock { .. } => false,
+            TreeNodeType::SphinxVersionAdded { .. } => {
+                if node_data.node_categories().any(|cat| {
+                    if let NodeCategory::Body = cat {
+                        true
+                    } else {
+                        false
+                    }
+                }) {
+                    true
+                } else {
+                    false
+                }
+            }
+            TreeNodeType::SphinxVersionChanged { .. } => {
+                if node_data.node_categories().any(|cat| {
+                    if let NodeCategory::Body = cat {
+                        true
+                    } else {
+                        false
+                    }
+                }) {
+                    true
+                } else {
+                    false
+                }
+            }
+            TreeNodeType::SphinxDeprecated { .. } => {
+                if node_data.node_categories().any(|cat| {
+                    if let NodeCategory::Body = cat {
+                        true
+                    } else {
+                        false
+                    }
+                }) {
+                    true
+                } else {
+                    false
+                }
+            }
+            TreeNodeType::SphinxSeeAlso { .. } => {
+                if node_data.node_categories().any(|cat| {
+                    if let NodeCategory::Body = cat {
+                        true
+                    } else {
+                        false
+                    }
+                }) {
+                    true
+                } else {
+                    false
+                }
+            }
+            TreeNodeType::SphinxCentered => {
+                if node_data.node_categories().any(|cat| {
+                    if let NodeCategory::Inline = cat {
+                        true
+                    } else {
+                        false
+                    }
+                }) {
+                    true
+                } else {
+                    false
+                }
+            }
+            TreeNodeType::SphinxHList { .. } => match node_data {
+                TreeNodeType::EmptyLine | TreeNodeType::SphinxHListColumn { .. } => true,
+                _ => false,
+            },
+            TreeNodeType::SphinxHListColumn { .. } => match node_data {
+                TreeNodeType::EmptyLine | TreeNodeType::BulletList { .. } => true,
+                _ => false,
+            },
+            TreeNodeType::SphinxGlossary { .. } => match node_data {
+                TreeNodeType::EmptyLine | TreeNodeType::DefinitionList { .. } => true,
+                _ => false,
+            },
+            TreeNodeType::SphinxTermReference { .. } => false,
+            TreeNodeType::SphinxCrossReference { .. } => false,
+            TreeNodeType::SphinxProductionList { .. } => match node_data {
+                TreeNodeType::SphinxProduction { .. } => true,
+                _ => false,
+            },
+            TreeNodeType::SphinxProduction { .. } => false,
+            TreeNodeType::SphinxAuthor { .. } => false,
+            TreeNodeType::SphinxIndex { .. } => false,
+            TreeNodeType::SphinxInlineIndex { .. } => false,
+            TreeNodeType::SphinxKeyboard { .. } => false,
+            TreeNodeType::SphinxFile { .. } => false,
+            TreeNodeType::SphinxSample { .. } => false,
+            TreeNodeType::SphinxCommand { .. } => false,
+            TreeNodeType::SphinxGUILabel { .. } => false,
+            TreeNodeType::SphinxMenuSelection { .. } => false,
 
             // ========================
             //  A+ specific directives
@@ -995,6 +1080,27 @@ impl TreeNode {
             }
             TreeNodeType::AplusActiveElementInput { .. } => false,
             TreeNodeType::AplusActiveElementOutput { .. } => false,
+            TreeNodeType::AplusHiddenBlock { .. } => node_data
+                .node_categories()
+                .any(|cat| if let NodeCategory::Body = cat { true } else { false }),
+            TreeNodeType::AplusAnnotated { .. } => match node_data {
+                TreeNodeType::AplusAnnotation { .. } => true,
+                _ => false,
+            },
+            TreeNodeType::AplusAnnotation { .. } => node_data
+                .node_categories()
+                .any(|cat| if let NodeCategory::Body = cat { true } else { false }),
+            TreeNodeType::AplusLineRefCodeBlock { .. } => false,
+            TreeNodeType::AplusREPLSession { .. } => false,
+            TreeNodeType::AplusDiv { .. } | TreeNodeType::AplusStyledTopic { .. } => node_data
+                .node_categories()
+                .any(|cat| if let NodeCategory::Body = cat { true } else { false }),
+            TreeNodeType::AplusRoundSettings { .. } => false,
+            TreeNodeType::AplusYouTube { .. }
+            | TreeNodeType::AplusLocalVideo { .. }
+            | TreeNodeType::AplusEmbeddedPage { .. }
+            | TreeNodeType::AplusStory { .. }
+            | TreeNodeType::AplusJSVee { .. } => false,
         }
     }
 
@@ -4,12 +4,21 @@ A submodule that contains the different tree node types as a single huge enumera
 Copyright © 2020 Santtu Söderholm
 */
 use super::*;
+use crate::common::AplusAudience;
+use crate::common::AplusDateTime;
+use crate::common::AplusFeedbackKind;
 use crate::common::AplusActiveElementClear;
 use crate::common::AplusActiveElementInputType;
 use crate::common::AplusActiveElementOutputType;
 use crate::common::AplusExerciseStatus;
 use crate::common::AplusRadarTokenizer;
 use crate::common::QuizPoints;
+use crate::common::SphinxAuthorKind;
+use crate::common::SphinxCrossReferenceKind;
+use crate::common::AplusREPLLine;
+use crate::common::SphinxIndexEntry;
+use crate::common::SphinxProductionSegment;
+use crate::common::SphinxSampleSegment;
 use crate::doctree::directives::AdmonitionType;
 
 
@@ -23,16 +32,20 @@ use crate::doctree::directives::AdmonitionType;
 pub enum TreeNodeType {
 
     /// The abbreviation element is an inline element used to represent an abbreviation being used in the document. An example of an abbreviation is 'St' being used instead of 'Street'.
+    /// The optional explanation is given in parentheses after the abbreviation, as in `:abbr:`LIFO (last-in, first-out)``.
     Abbreviation {
-        name: Option<String>,
-        classes: Option<String>,
+        text: String,
+        explanation: Option<String>,
     },
 
     /// A reference to a web address.
     AbsoluteURI { text: String },
 
-    /// TODO
-    Acronym,
+    /// An acronym, such as "NASA". Like an `Abbreviation`, an acronym may come with an optional explanation.
+    Acronym {
+        text: String,
+        explanation: Option<String>,
+    },
 
     /// The address element holds the surface mailing address information for the author (individual or group) of the document, or a third-party contact address. Its structure is identical to that of the literal_block element: whitespace is significant, especially newlines.
     Address,
@@ -229,6 +242,8 @@ pub enum TreeNodeType {
         keepspace: Option<bool>,
         escape: Option<char>,
         align: Option<HorizontalAlignment>,
+        /// A LaTeX column specification given by a preceding Sphinx `tabularcolumns` directive.
+        column_spec: Option<String>,
     },
 
     /// Document metadata
@@ -268,7 +283,12 @@ pub enum TreeNodeType {
     DoctestBlock,
 
     /// The root node of an reStructuredText document tree.
-    Document,
+    /// If the document contains Sphinx index entries, an index is printed at the end of a full document.
+    /// The authors given by Sphinx `sectionauthor` and `codeauthor` directives are summarized in `authors`.
+    Document {
+        print_index: bool,
+        authors: Vec<String>,
+    },
 
     /// Emphasised or italicized text.
     Emphasis { text: String },
@@ -492,6 +512,9 @@ pub enum TreeNodeType {
         stub_columns: Option<u32>,
         /// The horizontal alignment of the table. (New in Docutils 0.13)
         align: Option<HorizontalAlignment>,
+        /// A LaTeX column specification such as `|p{4cm}|l|`, given by a preceding Sphinx `tabularcolumns` directive.
+        /// Overrides the column widths in the LarST output.
+        column_spec: Option<String>,
     },
 
     /// Literal text, usually reserved for code.
@@ -500,7 +523,12 @@ pub enum TreeNodeType {
     /// Paragraph (possibly empty) ending in a "::" signifies the start of a literal block of text.
     /// Text contained in a literal block is not interpreted in any way,
     /// but simply stored in this node as is.
-    LiteralBlock { text: String },
+    /// The language and line numbering are inherited from a possible preceding Sphinx `highlight` directive.
+    LiteralBlock {
+        text: String,
+        language: Option<String>,
+        linenos: bool,
+    },
 
     /// The "math" directive inserts blocks with mathematical content (display formulas, equations)
     /// into the document. The input format is subset of LaTeX math syntax with support for Unicode symbols.
@@ -514,11 +542,14 @@ pub enum TreeNodeType {
     },
 
     /// A node for display-style mathematics (LaTeX).
+    /// A block with a `name` (or a Sphinx `label`) is numbered and can be referenced with the `:eq:` role.
+    /// If `nowrap` is set, the block is written as is, without a surrounding math environment.
     MathBlock {
         math_block: String,
 
         name: Option<String>,
         class: Option<String>,
+        nowrap: bool,
     },
 
     /// A two-column list of command line options, such as the ones typically seen on unix `man` pages.
@@ -559,7 +590,9 @@ pub enum TreeNodeType {
 
     Pending,
 
-    Problematic,
+    /// Inline source text that could not be parsed.
+    /// Links to the system message that describes the problem via `message_label`.
+    Problematic { text: String, message_label: String },
 
     Raw,
 
@@ -588,6 +621,8 @@ pub enum TreeNodeType {
         title_text: String,
         level: usize,
         line_style: SectionLineStyle,
+        /// The authors of the section, given by Sphinx `sectionauthor` directives.
+        authors: Vec<String>,
     },
 
     /// Sidebars are like miniature, parallel documents that occur inside other documents, providing related or reference material.
@@ -633,8 +668,16 @@ pub enum TreeNodeType {
     /// Inline superscripted text.
     Superscript { text: String },
 
-    /// An error node (currently unused).
-    SystemMessage,
+    /// An error or a warning generated while processing the document,
+    /// with the source line it concerns, if known.
+    SystemMessage {
+        level: crate::common::SystemMessageLevel,
+        line: Option<usize>,
+        text: String,
+        label: String,
+        /// Whether a `Problematic` node links to the message via its label.
+        referenced: bool,
+    },
 
     /// The "table" directive is used to associate a title with a table or specify options.
     ///
@@ -645,6 +688,8 @@ pub enum TreeNodeType {
         align: Option<HorizontalAlignment>,
         widths: Option<Vec<usize>>,
         width: Option<Length>,
+        /// A LaTeX column specification given by a preceding Sphinx `tabularcolumns` directive.
+        column_spec: Option<String>,
     },
 
     Target,
@@ -694,11 +739,15 @@ pub enum TreeNodeType {
     /// A node corresponding to LaTeX's `\hrulefill` command.
     Transition,
 
+    /// A directive ruSTLa does not recognize. The line number and the source lines of the directive
+    /// are stored, so that the directive can be reported and written out as a comment or literal text.
     UnknownDirective {
         directive_name: String,
         argument: String,
         options: std::collections::HashMap<String, String>,
         body_indent: usize,
+        line: usize,
+        source_lines: Vec<String>,
     },
 
     /// Document metadata
@@ -729,6 +778,119 @@ pub enum TreeNodeType {
         code_text: String,
     },
 
+    /// A Sphinx `versionadded` note. Contains the version the documented feature
+    /// was added in and an optional explanation as body elements.
+    SphinxVersionAdded {
+        version: String,
+        body_indent: usize,
+    },
+
+    /// A Sphinx `versionchanged` note. Works like `SphinxVersionAdded`,
+    /// but describes a change in the documented feature.
+    SphinxVersionChanged {
+        version: String,
+        body_indent: usize,
+    },
+
+    /// A Sphinx `deprecated` note. Works like `SphinxVersionAdded`,
+    /// but states the version in which the documented feature was deprecated.
+    SphinxDeprecated {
+        version: String,
+        body_indent: usize,
+    },
+
+    /// A Sphinx `seealso` block, that contains references to related material as body elements.
+    SphinxSeeAlso { body_indent: usize },
+
+    /// A Sphinx `centered` directive. Contains a line of centered inline text.
+    SphinxCentered,
+
+    /// A Sphinx `hlist` directive. Contains the items of a bullet list
+    /// distributed into `columns` instances of `SphinxHListColumn`.
+    SphinxHList {
+        columns: usize,
+        body_indent: usize,
+    },
+
+    /// A single column of a `SphinxHList`. Contains a bullet list
+    /// with the items assigned to this column.
+    SphinxHListColumn { body_indent: usize },
+
+    /// A Sphinx `glossary`. Contains a definition list, the terms of which
+    /// can be referenced with the `:term:` role.
+    SphinxGlossary {
+        sorted: bool,
+        body_indent: usize,
+    },
+
+    /// A reference to a term in a Sphinx glossary, generated by the `:term:` role.
+    /// The source line is used to report references to undefined terms.
+    SphinxTermReference {
+        displayed_text: String,
+        target_label: String,
+        line: Option<usize>,
+    },
+
+    /// A Sphinx `productionlist`, that holds on to the `SphinxProduction`s of a grammar.
+    /// The optional production group of the list is used to distinguish between the tokens of different grammars.
+    SphinxProductionList { group: Option<String> },
+
+    /// A single production rule `name: definition` of a Sphinx `productionlist`.
+    /// Each continuation line of the definition is stored as a separate line of segments.
+    SphinxProduction {
+        name: String,
+        target_label: String,
+        definition: Vec<Vec<SphinxProductionSegment>>,
+    },
+
+    /// An author given by a Sphinx `sectionauthor` or `codeauthor` directive.
+    /// The author belongs to the enclosing section, or to the document, if there is no such section.
+    SphinxAuthor {
+        kind: SphinxAuthorKind,
+        name: String,
+        email: Option<String>,
+    },
+
+    /// The entries of a Sphinx `index` directive. The entries refer to the location of the directive.
+    SphinxIndex { entries: Vec<SphinxIndexEntry> },
+
+    /// Text generated by the Sphinx `:index:` role, that is displayed and added to the index.
+    SphinxInlineIndex {
+        displayed_text: String,
+        entries: Vec<SphinxIndexEntry>,
+    },
+
+    /// A key or a combination of keys, generated by the Sphinx role `:kbd:`.
+    /// The keys and the separators between them, such as `+` or `-`, are stored in alternating order,
+    /// starting and ending with a key.
+    SphinxKeyboard { keys: Vec<String> },
+
+    /// A file path generated by the Sphinx role `:file:`. Parts inside curly braces are variable.
+    SphinxFile { segments: Vec<SphinxSampleSegment> },
+
+    /// A piece of literal text generated by the Sphinx role `:samp:`. Parts inside curly braces are variable.
+    SphinxSample { segments: Vec<SphinxSampleSegment> },
+
+    /// The name of an operating system level command, generated by the Sphinx role `:command:`.
+    SphinxCommand { text: String },
+
+    /// A label of a graphical user interface element, generated by the Sphinx role `:guilabel:`.
+    SphinxGUILabel { text: String },
+
+    /// A path through graphical menus, generated by the Sphinx role `:menuselection:`.
+    /// The items are separated by `-->` in the source text.
+    SphinxMenuSelection { items: Vec<String> },
+
+    /// A Sphinx cross-reference, generated by one of the roles `:ref:`, `:doc:`, `:numref:` or `:eq:`.
+    /// A missing explicit title of a `:ref:` to a section is filled in with the section title,
+    /// once the targets of the document are known. The source line is used to report undefined labels.
+    SphinxCrossReference {
+        kind: SphinxCrossReferenceKind,
+        displayed_text: Option<String>,
+        target_label: String,
+        line: Option<usize>,
+    },
+
     // ========================
     //  A+ specific directives
     // ========================
@@ -778,7 +940,7 @@ pub enum TreeNodeType {
         // source: https://github.com/apluslms/a-plus-rst-tools#1-graded-questionnaire
         submissions: Option<String>,
         points_to_pass: Option<String>,
-        feedback: Option<String>,
+        feedback: Option<AplusFeedbackKind>,
         title: Option<String>,
         no_override: Option<String>,
         pick_randomly: Option<String>,
@@ -789,6 +951,9 @@ pub enum TreeNodeType {
         show_model: Option<String>,
         allow_assistant_viewing: Option<String>,
         allow_assistant_grading: Option<String>,
+
+        /// The source text of the paragraphs between the questions, for exporting the questionnaire.
+        instructions: Vec<String>,
     },
 
     /// See the documentation for the `quoestionnaire` directive:
@@ -802,6 +967,9 @@ pub enum TreeNodeType {
         required: bool,
         key: Option<String>,
         dropdown: bool,
+
+        /// The source text of the assignment, for exporting the question.
+        assignment: String,
     },
 
     /// See the documentation for the `quoestionnaire` directive:
@@ -818,6 +986,9 @@ pub enum TreeNodeType {
         randomized: bool,
         correct_count: Option<u32>,
         preserve_questions_between_attempts: bool,
+
+        /// The source text of the assignment, for exporting the question.
+        assignment: String,
     },
 
     /// See the documentation for the `quoestionnaire` directive:
@@ -842,6 +1013,9 @@ pub enum TreeNodeType {
         key: Option<String>,
         length: Option<String>,
         height: Option<String>,
+
+        /// The source text of the assignment, for exporting the question.
+        assignment: String,
     },
 
     /// A node that contains the choices of an A+ `pick` type question.
@@ -853,6 +1027,8 @@ pub enum TreeNodeType {
         is_correct: bool,
         is_pre_selected: bool,
         is_neutral: bool, // Only set with pick-any nodes
+        /// The source text of the answer, for exporting the question.
+        answer: String,
     },
 
     /// A list type node for containing `AplusQuestionnaireHint`s.
@@ -863,6 +1039,8 @@ pub enum TreeNodeType {
         label: String,
         show_when_not_selected: bool,
         question_type: crate::common::AplusQuestionnaireType,
+        /// The source text of the hint, for exporting the question.
+        hint: String,
     },
 
     /// A+ submit-type exercise. See https://github.com/apluslms/a-plus-rst-tools#3-submittable-exercise.
@@ -918,6 +1096,107 @@ pub enum TreeNodeType {
         scale_size: bool,
         status: AplusExerciseStatus,
     },
+
+    /// A block of content that is hidden until a link is clicked.
+    /// The directive argument names the block and the optional `label` option gives the link text.
+    /// With the `visible` option the content is shown by default.
+    AplusHiddenBlock {
+        name: String,
+        link_text: Option<String>,
+        visible: bool,
+        body_indent: usize,
+    },
+
+    /// A code block with numbered callouts, followed by the annotations the callouts refer to.
+    /// The callouts are stored as pairs of a code line index and an annotation number,
+    /// as the `«N»` markers are removed from the code lines.
+    AplusAnnotated {
+        code_lines: Vec<String>,
+        callouts: Vec<(usize, usize)>,
+        n_of_annotations: usize,
+        body_indent: usize,
+    },
+
+    /// A code block, whose lines may be referenced with the `:lineref:` role.
+    /// The `::label::` markers are removed from the code lines and stored as pairs
+    /// of a code line index and a target label.
+    AplusLineRefCodeBlock {
+        language: Option<String>,
+        code_lines: Vec<String>,
+        line_labels: Vec<(usize, String)>,
+        /// A character that does not occur in the code, used to escape the labels into LaTeX.
+        escape_char: Option<char>,
+    },
+
+    /// A (Scala) REPL session, with the user input separated from the output of the REPL.
+    AplusREPLSession { lines: Vec<AplusREPLLine> },
+
+    /// A generic A+ container, that corresponds to an HTML `<div>` element.
+    /// The arguments of the directive are stored as the classes of the tree node.
+    AplusDiv {
+        body_indent: usize,
+    },
+
+    /// An embedded YouTube video. The video is identified by its YouTube id.
+    /// The poster is an image file in the source folder, that is shown in place of the video.
+    AplusYouTube {
+        video_id: String,
+        title: Option<String>,
+        duration: Option<String>,
+        poster: Option<String>,
+    },
+
+    /// A video file served with the course material.
+    AplusLocalVideo {
+        video_path: String,
+        title: Option<String>,
+        duration: Option<String>,
+        poster: Option<String>,
+    },
+
+    /// An external web page embedded into the material.
+    AplusEmbeddedPage {
+        url: String,
+        title: Option<String>,
+        width: Option<String>,
+        height: Option<String>,
+    },
+
+    /// An interactive story, read from the given source.
+    AplusStory {
+        source: String,
+        title: Option<String>,
+    },
+
+    /// A JSVee program visualization, identified by its id.
+    AplusJSVee {
+        visualization_id: String,
+        title: Option<String>,
+    },
+
+    /// A titled A+ topic. The class used to style it is stored as the classes of the tree node.
+    AplusStyledTopic {
+        title: String,
+        body_indent: usize,
+    },
+
+    /// The settings of an A+ module (exercise round), given with the `meta` directive.
+    /// The late penalty is the fraction of points deducted from submissions
+    /// made after the closing time, but before the late submission deadline.
+    AplusRoundSettings {
+        open_time: Option<AplusDateTime>,
+        close_time: Option<AplusDateTime>,
+        late_time: Option<AplusDateTime>,
+        late_penalty: Option<f64>,
+        audience: Option<AplusAudience>,
+        hidden: bool,
+        points_to_pass: Option<QuizPoints>,
+        introduction: Option<String>,
+    },
+
+    /// The explanation of a callout of an annotated code block.
+    /// The annotations are numbered in the order they appear in.
+    AplusAnnotation { number: usize, body_indent: usize },
 }
 
 use crate::doctree::node_categories::*;
@@ -1040,6 +1319,27 @@ impl TreeNodeType {
             // ========================
             Self::SphinxOnly { body_indent, .. } => Some(*body_indent),
             Self::SphinxCodeBlock { .. } => None,
+            Self::SphinxVersionAdded { body_indent, .. } => Some(*body_indent),
+            Self::SphinxVersionChanged { body_indent, .. } => Some(*body_indent),
+            Self::SphinxDeprecated { body_indent, .. } => Some(*body_indent),
+            Self::SphinxSeeAlso { body_indent } => Some(*body_indent),
+            Self::SphinxCentered => None,
+            Self::SphinxHList { body_indent, .. } => Some(*body_indent),
+            Self::SphinxHListColumn { body_indent } => Some(*body_indent),
+            Self::SphinxGlossary { body_indent, .. } => Some(*body_indent),
+            Self::SphinxTermReference { .. } => None,
+            Self::SphinxCrossReference { .. } => None,
+            Self::SphinxProductionList { .. } => None,
+            Self::SphinxProduction { .. } => None,
+            Self::SphinxAuthor { .. } => None,
+            Self::SphinxIndex { .. } => None,
+            Self::SphinxInlineIndex { .. } => None,
+            Self::SphinxKeyboard { .. } => None,
+            Self::SphinxFile { .. } => None,
+            Self::SphinxSample { .. } => None,
+            Self::SphinxCommand { .. } => None,
+            Self::SphinxGUILabel { .. } => None,
+            Self::SphinxMenuSelection { .. } => None,
 
             // ========================
             //  A+ specific directives
@@ -1057,6 +1357,19 @@ impl TreeNodeType {
             Self::AplusSubmit { body_indent, .. } => Some(*body_indent),
             Self::AplusActiveElementInput { .. } => None,
             Self::AplusActiveElementOutput { .. } => None,
+            Self::AplusHiddenBlock { body_indent, .. } => Some(*body_indent),
+            Self::AplusAnnotated { body_indent, .. } => Some(*body_indent),
+            Self::AplusAnnotation { body_indent, .. } => Some(*body_indent),
+            Self::AplusLineRefCodeBlock { .. } => None,
+            Self::AplusREPLSession { .. } => None,
+            Self::AplusDiv { body_indent, .. } => Some(*body_indent),
+            Self::AplusStyledTopic { body_indent, .. } => Some(*body_indent),
+            Self::AplusRoundSettings { .. } => None,
+            Self::AplusYouTube { .. } => None,
+            Self::AplusLocalVideo { .. } => None,
+            Self::AplusEmbeddedPage { .. } => None,
+            Self::AplusStory { .. } => None,
+            Self::AplusJSVee { .. } => None,
         }
     }
 
@@ -1174,6 +1487,27 @@ impl TreeNodeType {
             // ============================
             Self::SphinxOnly { body_indent, .. } => &SPHINX_ONLY_CATEGORIES,
             Self::SphinxCodeBlock { .. } => &SPHINX_CODE_BLOCK_CATEGORIES,
+            Self::SphinxVersionAdded { .. } => &SPHINX_VERSION_ADDED_CATEGORIES,
+            Self::SphinxVersionChanged { .. } => &SPHINX_VERSION_CHANGED_CATEGORIES,
+            Self::SphinxDeprecated { .. } => &SPHINX_DEPRECATED_CATEGORIES,
+            Self::SphinxSeeAlso { .. } => &SPHINX_SEE_ALSO_CATEGORIES,
+            Self::SphinxCentered => &SPHINX_CENTERED_CATEGORIES,
+            Self::SphinxHList { .. } => &SPHINX_HLIST_CATEGORIES,
+            Self::SphinxHListColumn { .. } => &SPHINX_HLIST_COLUMN_CATEGORIES,
+            Self::SphinxGlossary { .. } => &SPHINX_GLOSSARY_CATEGORIES,
+            Self::SphinxTermReference { .. } => &SPHINX_TERM_REFERENCE_CATEGORIES,
+            Self::SphinxCrossReference { .. } => &SPHINX_CROSS_REFERENCE_CATEGORIES,
+            Self::SphinxProductionList { .. } => &SPHINX_PRODUCTION_LIST_CATEGORIES,
+            Self::SphinxProduction { .. } => &SPHINX_PRODUCTION_CATEGORIES,
+            Self::SphinxAuthor { .. } => &SPHINX_AUTHOR_CATEGORIES,
+            Self::SphinxIndex { .. } => &SPHINX_INDEX_CATEGORIES,
+            Self::SphinxInlineIndex { .. } => &SPHINX_INLINE_INDEX_CATEGORIES,
+            Self::SphinxKeyboard { .. } => &SPHINX_KEYBOARD_CATEGORIES,
+            Self::SphinxFile { .. } => &SPHINX_FILE_CATEGORIES,
+            Self::SphinxSample { .. } => &SPHINX_SAMPLE_CATEGORIES,
+            Self::SphinxCommand { .. } => &SPHINX_COMMAND_CATEGORIES,
+            Self::SphinxGUILabel { .. } => &SPHINX_GUI_LABEL_CATEGORIES,
+            Self::SphinxMenuSelection { .. } => &SPHINX_MENU_SELECTION_CATEGORIES,
 
             // ========================
             //  A+ specific directives
@@ -1191,6 +1525,19 @@ impl TreeNodeType {
             Self::AplusSubmit { .. } => &APLUS_SUBMIT_CATEGORIES,
             Self::AplusActiveElementInput { .. } => &APLUS_ACTIVE_ELEMENT_INPUT_CATEGORIES,
             Self::AplusActiveElementOutput { .. } => &APLUS_ACTIVE_ELEMENT_OUTPUT_CATEGORIES,
+            Self::AplusHiddenBlock { .. } => APLUS_HIDDEN_BLOCK_CATEGORIES,
+            Self::AplusAnnotated { .. } => APLUS_ANNOTATED_CATEGORIES,
+            Self::AplusAnnotation { .. } => APLUS_ANNOTATION_CATEGORIES,
+            Self::AplusLineRefCodeBlock { .. } => APLUS_LINEREF_CODE_BLOCK_CATEGORIES,
+            Self::AplusREPLSession { .. } => APLUS_REPL_SESSION_CATEGORIES,
+            Self::AplusDiv { .. } => APLUS_DIV_CATEGORIES,
+            Self::AplusStyledTopic { .. } => APLUS_STYLED_TOPIC_CATEGORIES,
+            Self::AplusRoundSettings { .. } => APLUS_ROUND_SETTINGS_CATEGORIES,
+            Self::AplusYouTube { .. } => APLUS_MEDIA_CATEGORIES,
+            Self::AplusLocalVideo { .. } => APLUS_MEDIA_CATEGORIES,
+            Self::AplusEmbeddedPage { .. } => APLUS_MEDIA_CATEGORIES,
+            Self::AplusStory { .. } => APLUS_MEDIA_CATEGORIES,
+            Self::AplusJSVee { .. } => APLUS_MEDIA_CATEGORIES,
         };
 
         categories.iter()
@@ -1311,6 +1658,27 @@ impl std::fmt::Display for TreeNodeType {
             // ============================
             Self::SphinxOnly { body_indent, .. } => "sphinx only",
             Self::SphinxCodeBlock { .. } => "sphinx code block",
+            Self::SphinxVersionAdded { .. } => "sphinx version added",
+            Self::SphinxVersionChanged { .. } => "sphinx version changed",
+            Self::SphinxDeprecated { .. } => "sphinx deprecated",
+            Self::SphinxSeeAlso { .. } => "sphinx see also",
+            Self::SphinxCentered => "sphinx centered",
+            Self::SphinxHList { .. } => "sphinx hlist",
+            Self::SphinxHListColumn { .. } => "sphinx hlist column",
+            Self::SphinxGlossary { .. } => "sphinx glossary",
+            Self::SphinxTermReference { .. } => "sphinx term reference",
+            Self::SphinxCrossReference { .. } => "sphinx cross-reference",
+            Self::SphinxProductionList { .. } => "sphinx production list",
+            Self::SphinxProduction { .. } => "sphinx production",
+            Self::SphinxAuthor { .. } => "sphinx author",
+            Self::SphinxIndex { .. } => "sphinx index",
+            Self::SphinxInlineIndex { .. } => "sphinx inline index",
+            Self::SphinxKeyboard { .. } => "sphinx keyboard",
+            Self::SphinxFile { .. } => "sphinx file",
+            Self::SphinxSample { .. } => "sphinx sample",
+            Self::SphinxCommand { .. } => "sphinx command",
+            Self::SphinxGUILabel { .. } => "sphinx GUI label",
+            Self::SphinxMenuSelection { .. } => "sphinx menu selection",
 
             // ========================
             //  A+ specific directives
@@ -1328,6 +1696,19 @@ impl std::fmt::Display for TreeNodeType {
             Self::AplusSubmit { .. } => "aplus submit",
             Self::AplusActiveElementInput { .. } => "aplus active element input",
             Self::AplusActiveElementOutput { .. } => "aplus active element output",
+            Self::AplusHiddenBlock { .. } => "aplus hidden block",
+            Self::AplusAnnotated { .. } => "aplus annotated code block",
+            Self::AplusAnnotation { .. } => "aplus annotation",
+            Self::AplusLineRefCodeBlock { .. } => "aplus lineref code block",
+            Self::AplusREPLSession { .. } => "aplus repl session",
+            Self::AplusDiv { .. } => "aplus div",
+            Self::AplusStyledTopic { .. } => "aplus styled topic",
+            Self::AplusRoundSettings { .. } => "aplus round settings",
+            Self::AplusYouTube { .. } => "aplus youtube video",
+            Self::AplusLocalVideo { .. } => "aplus local video",
+            Self::AplusEmbeddedPage { .. } => "aplus embedded page",
+            Self::AplusStory { .. } => "aplus story",
+            Self::AplusJSVee { .. } => "aplus jsvee visualization",
         };
         write!(f, "\"{}\"", node_type_str)
     }
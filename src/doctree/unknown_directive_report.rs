@@ -0,0 +1,100 @@
+/*!
+A submodule that defines the `UnknownDirectiveReport`, which lists the directives
+ruSTLa did not recognize in a document. The report is generated from the finished document tree,
+so that users know which constructs were not transpiled as intended.
+
+Copyright © 2020 Santtu Söderholm
+*/
+
+use std::collections::BTreeSet;
+
+/// A summary of the unknown directives of a document, grouped by directive name.
+/// The entries are sorted by the line on which each directive was first encountered.
+pub struct UnknownDirectiveReport {
+    entries: Vec<UnknownDirectiveEntry>,
+}
+
+/// The occurrences of a single unknown directive.
+#[derive(Debug, PartialEq)]
+pub struct UnknownDirectiveEntry {
+    /// The name of the directive, as written in the source.
+    pub directive_name: String,
+    /// The source lines, where the directive marker was encountered.
+    pub lines: Vec<usize>,
+    /// The names of all the options given to the directive in the document.
+    pub options: BTreeSet<String>,
+}
+
+impl UnknownDirectiveReport {
+    pub fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+
+    /// Records an occurrence of the directive `directive_name` on the given line.
+    pub fn add_occurrence<'a>(
+        &mut self,
+        directive_name: &str,
+        line: usize,
+        option_names: impl Iterator<Item = &'a String>,
+    ) {
+        let entry = match self
+            .entries
+            .iter_mut()
+            .position(|entry| entry.directive_name == directive_name)
+        {
+            Some(index) => &mut self.entries[index],
+            None => {
+                self.entries.push(UnknownDirectiveEntry {
+                    directive_name: directive_name.to_string(),
+                    lines: Vec::new(),
+                    options: BTreeSet::new(),
+                });
+                self.entries.last_mut().unwrap()
+            }
+        };
+        entry.lines.push(line);
+        entry.options.extend(option_names.cloned());
+    }
+
+    /// Retrieves a shared reference to the entries of the report.
+    pub fn shared_entries(&self) -> &Vec<UnknownDirectiveEntry> {
+        &self.entries
+    }
+
+    /// Returns the total number of unknown directives encountered.
+    pub fn n_of_occurrences(&self) -> usize {
+        self.entries.iter().map(|entry| entry.lines.len()).sum()
+    }
+
+    /// Checks whether the document contained any unknown directives.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl std::fmt::Display for UnknownDirectiveReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        writeln!(f, "Unknown directives:")?;
+        for entry in self.entries.iter() {
+            let lines = entry
+                .lines
+                .iter()
+                .map(|line| line.to_string())
+                .collect::<Vec<String>>()
+                .join(", ");
+            let options = if entry.options.is_empty() {
+                String::from("none")
+            } else {
+                entry.options.iter().cloned().collect::<Vec<String>>().join(", ")
+            };
+            writeln!(
+                f,
+                "  {} (lines: {}; options: {})",
+                entry.directive_name, lines, options
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -92,7 +92,14 @@ pub fn run() -> Result<(), MainError> {
 
     // Enter parser here...
 
+    let source_name = if path.as_os_str().is_empty() {
+        String::from("<stdin>")
+    } else {
+        path.display().to_string()
+    };
+
     let mut doctree = DocTree::new(path);
+    doctree.set_sphinx_only_tags(rustla_options.shared_tags().clone());
     let mut parser = Parser::new(&src_lines, doctree, 0, 0, State::Body, 0);
 
     use common::ParsingResult;
@@ -101,13 +108,51 @@ pub fn run() -> Result<(), MainError> {
         ParsingResult::EOF { doctree, .. } | ParsingResult::EmptyStateStack { doctree, .. } => {
             doctree
         }
-        ParsingResult::Failure { message, doctree } => {
-            eprintln!("Parsing error: {}", message);
+        ParsingResult::Failure { message, line, mut doctree } => {
+            doctree.add_system_message(common::SystemMessageLevel::Severe, Some(line), message);
             doctree
         }
     };
 
+    let unknown_directives = doctree.unknown_directive_report();
+    if ! unknown_directives.is_empty() {
+        eprintln!("{}", unknown_directives);
+        if rustla_options.is_strict()
+            || *rustla_options.shared_unknown_directive_output() == rustla_options::UnknownDirectiveOutput::Error
+        {
+            return Err(MainError::ParseError(format!(
+                "The document contains {} unknown directive(s). Computer says no...",
+                unknown_directives.n_of_occurrences()
+            )));
+        }
+    }
+    doctree = doctree.perform_unknown_directive_transforms(rustla_options.shared_unknown_directive_output());
+
     doctree = doctree.perform_restructuredtext_transforms();
+
+    let mut halt = false;
+    for message in doctree.shared_system_messages() {
+        if rustla_options.reports(message.level) {
+            match message.line {
+                Some(line) => eprintln!("{}:{}: ({}) {}", source_name, line, message.level, message.text),
+                None => eprintln!("{}: ({}) {}", source_name, message.level, message.text),
+            }
+        }
+        halt = halt || rustla_options.halts_on(message.level);
+    }
+    if halt {
+        return Err(MainError::ParseError(String::from(
+            "A system message reached the halt level. Computer says no...",
+        )));
+    }
+
+    if let Some(folder) = rustla_options.shared_aplus_export_folder() {
+        doctree = match doctree.write_aplus_exercises(folder) {
+            Ok((doctree, _)) => doctree,
+            Err(message) => return Err(MainError::PrintError(message)),
+        };
+    }
+
     doctree.write_to_larst(&rustla_options);
 
     Ok(())
@@ -5,7 +5,10 @@ These include Roman numeral -> integer transformations and the like.
 Copyright © 2020 Santtu Söderholm
 */
 
-use crate::common::{EnumDelims, EnumKind, HTMLAlignment, HorizontalAlignment, Length, LengthNum};
+use crate::common::{
+    AplusAudience, AplusDateTime, EnumDelims, EnumKind, HTMLAlignment, HorizontalAlignment, Length,
+    LengthNum,
+};
 
 /// Converts an enumerator &str to an integer using one of the converters, if possible.
 pub fn enum_str_to_int_and_kind(
@@ -504,3 +507,60 @@ pub fn str_to_horizontal_alignment(alignment_str: &str) -> Option<HorizontalAlig
         _ => None,
     }
 }
+
+/// Converts a date given in the A+ round settings to an `AplusDateTime`, if possible.
+/// The date is written as `YYYY-MM-DD`, optionally followed by a time `HH[:MM[:SS]]`
+/// separated from the date by a space or the letter `T`. Dates that do not exist,
+/// such as the 30th of February, are rejected.
+pub fn str_to_aplus_datetime(datetime_str: &str) -> Option<AplusDateTime> {
+
+    const APLUS_DATETIME_PATTERN: &str = r#"^(?P<year>[0-9]{4})-(?P<month>[0-9]{1,2})-(?P<day>[0-9]{1,2})(?:[ T](?P<hour>[0-9]{1,2})(?::(?P<minute>[0-9]{2})(?::(?P<second>[0-9]{2}))?)?)?$"#;
+
+    lazy_static::lazy_static! {
+        static ref APLUS_DATETIME_RE: regex::Regex = regex::Regex::new(APLUS_DATETIME_PATTERN).unwrap();
+    }
+
+    let captures = APLUS_DATETIME_RE.captures(datetime_str.trim())?;
+    let number = |name: &str| -> u32 {
+        match captures.name(name) {
+            Some(number) => number.as_str().parse().unwrap(),
+            None => 0,
+        }
+    };
+
+    let datetime = AplusDateTime {
+        year: number("year"),
+        month: number("month"),
+        day: number("day"),
+        hour: number("hour"),
+        minute: number("minute"),
+        second: number("second"),
+    };
+
+    let is_leap_year = datetime.year % 4 == 0 && (datetime.year % 100 != 0 || datetime.year % 400 == 0);
+    let days_in_month = match datetime.month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if is_leap_year => 29,
+        2 => 28,
+        _ => return None,
+    };
+
+    if datetime.day < 1 || datetime.day > days_in_month
+        || datetime.hour > 23 || datetime.minute > 59 || datetime.second > 59
+    {
+        None
+    } else {
+        Some(datetime)
+    }
+}
+
+/// Converts a given `&str` to an A+ audience, if possible.
+pub fn str_to_aplus_audience(audience_str: &str) -> Option<AplusAudience> {
+    match audience_str.trim() {
+        "internal" => Some(AplusAudience::Internal),
+        "external" => Some(AplusAudience::External),
+        "registered" => Some(AplusAudience::Registered),
+        _ => None,
+    }
+}
@@ -16,7 +16,9 @@ Copyright © 2020 Santtu Söderholm
 */
 use std::collections::HashMap;
 
+use crate::common::AplusFeedbackKind;
 use crate::common::ParsingResult;
+use crate::common::SystemMessageLevel;
 use crate::doctree::tree_node_types::TreeNodeType;
 use crate::doctree::DocTree;
 use crate::parser::line_cursor::LineCursor;
@@ -140,7 +142,7 @@ pub fn parse_standard_admonition(
             doctree,
             state_stack,
         } => (doctree, state_stack),
-        ParsingResult::Failure { message, doctree } => {
+        ParsingResult::Failure { message, doctree, .. } => {
             return TransitionResult::Failure {
                 message: format!(
                     "Error when parsing a \"{}\" on line {}: {}",
@@ -543,6 +545,8 @@ pub fn parse_math_block(
     first_indent: usize,
 ) -> TransitionResult {
 
+    let directive_line = line_cursor.sum_total() + 1;
+
     let math_after_marker = scan_directive_arguments(
         src_lines,
         line_cursor,
@@ -565,6 +569,23 @@ pub fn parse_math_block(
         (None, None, None, None)
     };
 
+    // The Sphinx label is an alias for the name of the equation.
+    // It refers to the whole directive, so only the first of multiple blocks is labeled with it.
+    let name_is_label = label.is_some();
+    let name = if let Some(label) = label {
+        if name.is_some() {
+            doctree.add_system_message(
+                SystemMessageLevel::Warning,
+                Some(directive_line),
+                String::from("Math block has both a name and a label. Using the label."),
+            );
+        }
+        Some(label)
+    } else {
+        name
+    };
+    let nowrap = nowrap.is_some();
+
     // If an equation was given as an argument, quit early
     if let Some(math) = math_after_marker {
         doctree = match doctree.push_data(
@@ -572,6 +593,7 @@ pub fn parse_math_block(
                 math_block: math.join("\n"),
                 class: classes,
                 name: name,
+                nowrap: nowrap,
             }
         ) {
             Ok(tree) => tree,
@@ -643,16 +665,18 @@ pub fn parse_math_block(
     }
 
     // A counter for separating refnames of multiple blocks generated by the same directive
-    let mut refname_counter = if blocks.len() == 1 {
+    let mut refname_counter = if blocks.len() == 1 || name_is_label {
         None
     } else {
         Some(0)
     };
-    for block in blocks {
+    for (index, block) in blocks.iter().enumerate() {
         doctree = match doctree.push_data(
             TreeNodeType::MathBlock {
                 math_block: block.trim().to_string(),
-                name: if let Some(name) = &name {
+                name: if name_is_label && index > 0 {
+                    None
+                } else if let Some(name) = &name {
                     match &mut refname_counter {
                         Some(counter) => {
                             *counter += 1;
@@ -664,6 +688,7 @@ pub fn parse_math_block(
                     None
                 },
                 class: classes.clone(),
+                nowrap: nowrap,
             }
         ) {
             Ok(tree) => tree,
@@ -727,6 +752,8 @@ pub fn parse_list_table(
     section_level: usize,
 ) -> TransitionResult {
 
+    let directive_line = line_cursor.sum_total() + 1;
+
     let table_title = if let Some(title) = scan_directive_arguments(
         src_lines,
         line_cursor,
@@ -769,28 +796,21 @@ pub fn parse_list_table(
             if widths.as_str().trim() == "auto" {
                 Some(TableColWidths::Auto)
             } else {
-                let widths = widths
+                let parsed_widths = widths
                     .split_whitespace()
-                    .filter(
-                        |s| ! s.is_empty()
-                    )
-                    .map(
-                        |int|
-                            if let Ok(result) = int.parse::<f64>() {
-                                result
-                            } else {
-                                panic!(
-                                    "Tried converting a list table column width \"{}\" into a integer on line {} but failed. Computer says no...",
-                                    int,
-                                    line_cursor.sum_total()
-                                );
-                            }
-                    )
-                    .collect::<Vec<f64>>();
-                if widths.len() == 0 {
-                    None
-                } else {
-                    Some(TableColWidths::Columns(widths))
+                    .map(|width| width.parse::<f64>())
+                    .collect::<Result<Vec<f64>, _>>();
+                match parsed_widths {
+                    Ok(widths) if widths.is_empty() => None,
+                    Ok(widths) => Some(TableColWidths::Columns(widths)),
+                    Err(_) => {
+                        doctree.add_system_message(
+                            SystemMessageLevel::Error,
+                            Some(directive_line),
+                            format!("Invalid list-table \"widths\" setting \"{}\". Ignoring.", widths.trim()),
+                        );
+                        None
+                    }
                 }
             }
         } else {
@@ -811,9 +831,10 @@ pub fn parse_list_table(
             if let Ok(result) = num.parse::<u32>() {
                 Some(result)
             } else {
-                eprintln!(
-                    "Could not parse list-table header-rows setting to integer on line {}...",
-                    line_cursor.sum_total()
+                doctree.add_system_message(
+                    SystemMessageLevel::Error,
+                    Some(directive_line),
+                    format!("Invalid list-table \"header-rows\" setting \"{}\". Ignoring.", num),
                 );
                 None
             }
@@ -824,9 +845,10 @@ pub fn parse_list_table(
             if let Ok(result) = num.parse::<u32>() {
                 Some(result)
             } else {
-                eprintln!(
-                    "Could not parse list-table stub-columns setting to integer on line {}...",
-                    line_cursor.sum_total()
+                doctree.add_system_message(
+                    SystemMessageLevel::Error,
+                    Some(directive_line),
+                    format!("Invalid list-table \"stub-columns\" setting \"{}\". Ignoring.", num),
                 );
                 None
             }
@@ -839,13 +861,18 @@ pub fn parse_list_table(
                 "center" => Some(HorizontalAlignment::Center),
                 "right" => Some(HorizontalAlignment::Right),
                 _ => {
-                    eprintln!("Found an alignment setting for list table on line {}, but setting not valid...", line_cursor.sum_total());
+                    doctree.add_system_message(
+                        SystemMessageLevel::Error,
+                        Some(directive_line),
+                        format!("Invalid list-table \"align\" setting \"{}\". Ignoring.", alignment),
+                    );
                     None
                 }
             }
         } else {
             None
         },
+        column_spec: None, // Set by the doctree from a preceding tabularcolumns directive
     };
 
     Parser::skip_empty_lines(src_lines, line_cursor);
@@ -896,7 +923,7 @@ pub fn parse_list_table(
             doctree,
             state_stack,
         } => (doctree, state_stack),
-        ParsingResult::Failure { message, doctree } => {
+        ParsingResult::Failure { message, doctree, .. } => {
             return TransitionResult::Failure {
                 message: format!(
                     "Error when parsing a list-table on line {}: {}",
@@ -1304,7 +1331,7 @@ pub fn parse_class(
             doctree,
             state_stack,
         } => (doctree, state_stack),
-        ParsingResult::Failure { message, doctree } => {
+        ParsingResult::Failure { message, doctree, .. } => {
             return TransitionResult::Failure {
                 message: format!(
                     "Error when parsing a class on line {}: {}",
@@ -1347,184 +1374,125 @@ pub fn parse_sphinx_toctree() {
     todo!()
 }
 
-pub fn parse_sphinx_versionadded() {
-    todo!()
-}
-
-pub fn parse_sphinx_versionchanged() {
-    todo!()
-}
-
-pub fn parse_sphinx_deprecated() {
-    todo!()
-}
-
-pub fn parse_sphinx_seealso() {
-    todo!()
-}
-
-pub fn parse_sphinx_centered() {
-    todo!()
-}
-
-pub fn parse_sphinx_hlist() {
-    todo!()
-}
-
-pub fn parse_sphinx_highlight() {
-    todo!()
-}
-
-/// A parser for the Sphinx-specific `code-block` directive. See https://www.sphinx-doc.org/en/master/usage/restructuredtext/directives.html#directive-code-block
-/// for explanations of different settings and arguments.
-pub fn parse_sphinx_code_block(
+/// A parser for the Sphinx directives `versionadded`, `versionchanged` and `deprecated`.
+/// The first word of the directive argument is the version.
+/// The rest of the argument is combined with the directive content
+/// and parsed as the body of the node, much like with admonitions.
+pub fn parse_sphinx_version_modified(
     src_lines: &[String],
     mut doctree: DocTree,
     line_cursor: &mut LineCursor,
-    base_indent: usize,
+    section_level: usize,
     empty_after_marker: bool,
+    first_indent: usize,
     body_indent: usize,
-    first_indent: Option<usize>,
+    directive_name: &str,
 ) -> TransitionResult {
-    // Read directive argument: the formal language (should be recognized by Pygments)
-    let formal_language = if let Some(arg) = scan_directive_arguments(
+
+    let mut arg_lines = if let Some(arg) = scan_directive_arguments(
         src_lines,
         line_cursor,
         body_indent,
-        first_indent,
+        Some(first_indent),
         empty_after_marker,
     ) {
-        arg.join("")
+        arg
     } else {
-        String::from("python") // the Sphinx "highlight_language" setting default
-    };
-
-    // Read the settings...
-    let (linenos, lineno_start, emphasize_lines, caption, name, dedent, force) =
-        if let Some(mut settings) = scan_directive_options(
-            src_lines, line_cursor, body_indent
-        ){
-            let mut linenos = if let Some(linenos) = settings.remove("linenos") {
-                true
-            } else {
-                false
-            };
-            let lineno_start = if let Some(start_line) = settings.remove("lineno-start") {
-                if let Ok(number) = start_line.parse::<usize>() {
-                    linenos = true;
-                    Some(number)
-                } else {
-                    None
-                }
-            } else {
-                None
-            };
-            let emphasize_lines = if let Some(line_numbers) = settings.remove("emphasize-lines")
-            {
-                let emph_lines = line_numbers
-                    .split(",")
-                    .filter(|s| !s.trim().is_empty())
-                    .map(|s| s.trim())
-                    .filter_map(|s| s.parse::<usize>().ok())
-                    .collect::<Vec<usize>>();
-
-                Some(emph_lines)
-            } else {
-                None
-            };
-            let caption = settings.remove("caption");
-            let name = if let Some(refname) = settings.remove("name") {
-                Some(crate::common::normalize_refname(&refname))
-            } else {
-                None
-            };
-            let dedent = if let Some(dedent) = settings.remove("dedent") {
-                if let Ok(dedent) = dedent.parse::<usize>() {
-                    Some(dedent)
-                } else {
-                    None
-                }
-            } else {
-                None
-            };
-            let force = if let Some(force) = settings.remove("force") {
-                true
-            } else {
-                false
-            };
-
-            (
-                linenos,
-                lineno_start,
-                emphasize_lines,
-                caption,
-                name,
-                dedent,
-                force,
-            )
-        } else {
-            (false, None, None, None, None, None, false)
+        return TransitionResult::Failure {
+            message: format!(
+                "Sphinx \"{}\" directive on line {} is missing a version argument. Computer says no...",
+                directive_name,
+                line_cursor.sum_total()
+            ),
+            doctree: doctree,
         };
+    };
 
-    // Construct node from settings and read content...
-
-    let (code_text, offset) = match Parser::read_indented_block(
-        src_lines,
-        line_cursor.relative_offset(),
-        false,
-        true,
-        Some(body_indent),
-        None,
-        false,
-    ) {
-        IndentedBlockResult::Ok {mut lines, minimum_indent, offset, blank_finish } => {
-            // Remove empty lines from front
-            lines = lines
-                .iter()
-                .skip_while(|line| line.is_empty())
-                .map(|s| s.to_string())
-                .collect();
+    // Separate the version from a possible explanation on the same line...
+    let first_arg_line = arg_lines.remove(0);
+    let mut first_arg_words = first_arg_line.trim().splitn(2, char::is_whitespace);
+    let version = first_arg_words.next().unwrap_or("").to_string();
+    if let Some(explanation) = first_arg_words.next() {
+        if ! explanation.trim().is_empty() {
+            arg_lines.insert(0, explanation.trim().to_string());
+        }
+    }
 
-            // Remove empty lines from back
-            while let Some(line) = lines.last_mut() {
-                if line.is_empty() {
-                    lines.pop();
-                } else {
-                    break;
-                }
+    // Read in the rest of the directive contents, if there are any...
+    let offset = if line_cursor.relative_offset() < src_lines.len() {
+        match Parser::read_indented_block(
+            src_lines,
+            line_cursor.relative_offset(),
+            false,
+            true,
+            Some(body_indent),
+            Some(body_indent),
+            false,
+        ) {
+            IndentedBlockResult::Ok { mut lines, offset, .. } => {
+                arg_lines.append(&mut lines);
+                offset
             }
-
-            (lines.join("\n") + "\n", offset)
-        }
-        _ => {
-            return TransitionResult::Failure {
+            _ => return TransitionResult::Failure {
                 message: format!(
-                    "Error when parsing a Sphinx code block on line {}.",
-                    line_cursor.sum_total(),
+                    "Error when reading in the contents of a Sphinx \"{}\" directive around line {}. Computer says no...",
+                    directive_name,
+                    line_cursor.sum_total()
                 ),
                 doctree: doctree,
-            }
+            },
         }
+    } else {
+        0
     };
 
-    let code_block_data = TreeNodeType::SphinxCodeBlock {
-        language: formal_language,
-        linenos: linenos,
-        lineno_start: lineno_start,
-        emphasize_lines: emphasize_lines,
-        caption: caption,
-        name: name,
-        dedent: dedent,
-        force: force,
-        code_text: code_text,
+    let version_node = match directive_name {
+        "versionadded" => TreeNodeType::SphinxVersionAdded {
+            version: version,
+            body_indent: body_indent,
+        },
+        "versionchanged" => TreeNodeType::SphinxVersionChanged {
+            version: version,
+            body_indent: body_indent,
+        },
+        "deprecated" => TreeNodeType::SphinxDeprecated {
+            version: version,
+            body_indent: body_indent,
+        },
+        _ => unreachable!(
+            "No Sphinx version directive \"{}\" on line {}. Computer says no...",
+            directive_name,
+            line_cursor.sum_total()
+        ),
     };
 
-    doctree = match doctree.push_data(code_block_data) {
+    // A version note without an explanation has nothing to parse inside it...
+    if arg_lines.iter().all(|line| line.trim().is_empty()) {
+        doctree = match doctree.push_data(version_node) {
+            Ok(tree) => tree,
+            Err(tree) => {
+                return TransitionResult::Failure {
+                    message: format!(
+                        "Node insertion error on line {}. Computer says no...",
+                        line_cursor.sum_total()
+                    ),
+                    doctree: tree,
+                }
+            }
+        };
+        return TransitionResult::Success {
+            doctree: doctree,
+            push_or_pop: PushOrPop::Neither,
+            line_advance: LineAdvance::Some(offset),
+        };
+    }
+
+    doctree = match doctree.push_data_and_focus(version_node) {
         Ok(tree) => tree,
         Err(tree) => {
             return TransitionResult::Failure {
                 message: format!(
-                    "Erro when parsing Sphinx code block on line {}. Computer says no...",
+                    "Node insertion error on line {}. Computer says no...",
                     line_cursor.sum_total()
                 ),
                 doctree: tree,
@@ -1532,71 +1500,105 @@ pub fn parse_sphinx_code_block(
         }
     };
 
+    // Start nested parse inside the version note...
+    let (doctree, nested_state_stack) = match Parser::new(
+        &arg_lines,
+        doctree,
+        body_indent,
+        line_cursor.sum_total(),
+        State::Body,
+        section_level,
+    ).parse() {
+        ParsingResult::EOF {
+            doctree,
+            state_stack,
+        } => (doctree, state_stack),
+        ParsingResult::EmptyStateStack {
+            doctree,
+            state_stack,
+        } => (doctree, state_stack),
+        ParsingResult::Failure { message, doctree, .. } => {
+            return TransitionResult::Failure {
+                message: format!(
+                    "Error when parsing a Sphinx \"{}\" directive on line {}: {}",
+                    directive_name,
+                    line_cursor.sum_total(),
+                    message
+                ),
+                doctree: doctree,
+            }
+        }
+    };
+
     TransitionResult::Success {
         doctree: doctree,
-        push_or_pop: PushOrPop::Neither,
+        push_or_pop: PushOrPop::Push(nested_state_stack),
         line_advance: LineAdvance::Some(offset),
     }
 }
 
-pub fn parse_sphinx_literalinclude() {
-    todo!()
-}
-
-pub fn parse_sphinx_glossary() {
-    todo!()
-}
-
-pub fn parse_sphinx_sectionauthor() {
-    todo!()
-}
-
-pub fn parse_sphinx_codeauthor() {
-    todo!()
-}
-
-pub fn parse_sphinx_index() {
-    todo!()
-}
-
-pub fn parse_sphinx_only(
+/// A parser for the Sphinx `seealso` directive.
+/// Both the argument and the content of the directive are parsed as body elements,
+/// so the short and long forms of the directive produce the same kind of node.
+pub fn parse_sphinx_seealso(
     src_lines: &[String],
     mut doctree: DocTree,
     line_cursor: &mut LineCursor,
+    section_level: usize,
     empty_after_marker: bool,
     first_indent: usize,
     body_indent: usize,
 ) -> TransitionResult {
 
-
-    /// Directive `only` tags that are always known to the Sphinx parser.
-    /// These work like expressions in predicate logic and can be combined with
-    /// ` and `, ` or ` and grouped with parentheses.
-
-    /// They should be included with the directive argument.
-    const ALWAYS_DEFINED_TAGS: &[&str] = &["html", "latex", "text"];
-
-    let expression = match scan_directive_arguments(src_lines, line_cursor, body_indent, Some(first_indent), empty_after_marker) {
-        Some(lines) => lines.join(" "),
-        None => String::new()
+    let mut arg_lines = if let Some(arg) = scan_directive_arguments(
+        src_lines,
+        line_cursor,
+        body_indent,
+        Some(first_indent),
+        empty_after_marker,
+    ) {
+        arg
+    } else {
+        Vec::new()
+    };
+
+    let offset = if line_cursor.relative_offset() < src_lines.len() {
+        match Parser::read_indented_block(
+            src_lines,
+            line_cursor.relative_offset(),
+            false,
+            true,
+            Some(body_indent),
+            Some(body_indent),
+            false,
+        ) {
+            IndentedBlockResult::Ok { mut lines, offset, .. } => {
+                arg_lines.append(&mut lines);
+                offset
+            }
+            _ => return TransitionResult::Failure {
+                message: format!(
+                    "Error when reading in the contents of a Sphinx \"seealso\" directive around line {}. Computer says no...",
+                    line_cursor.sum_total()
+                ),
+                doctree: doctree,
+            },
+        }
+    } else {
+        0
     };
 
-    if expression.is_empty() {
+    if arg_lines.iter().all(|line| line.trim().is_empty()) {
         return TransitionResult::Failure {
             message: format!(
-                r#"The expression of an "only" Sphinx directive on line {} should not be empty. Computer says no..."#,
+                "Sphinx \"seealso\" directive on line {} has no content. Computer says no...",
                 line_cursor.sum_total()
             ),
             doctree: doctree,
         };
     }
 
-    let only_node = TreeNodeType::SphinxOnly {
-        expression: expression,
-        body_indent: body_indent,
-    };
-
-    doctree = match doctree.push_data_and_focus(only_node) {
+    doctree = match doctree.push_data_and_focus(TreeNodeType::SphinxSeeAlso { body_indent: body_indent }) {
         Ok(tree) => tree,
         Err(tree) => {
             return TransitionResult::Failure {
@@ -1609,123 +1611,71 @@ pub fn parse_sphinx_only(
         }
     };
 
+    let (doctree, nested_state_stack) = match Parser::new(
+        &arg_lines,
+        doctree,
+        body_indent,
+        line_cursor.sum_total(),
+        State::Body,
+        section_level,
+    ).parse() {
+        ParsingResult::EOF {
+            doctree,
+            state_stack,
+        } => (doctree, state_stack),
+        ParsingResult::EmptyStateStack {
+            doctree,
+            state_stack,
+        } => (doctree, state_stack),
+        ParsingResult::Failure { message, doctree, .. } => {
+            return TransitionResult::Failure {
+                message: format!(
+                    "Error when parsing a Sphinx \"seealso\" directive on line {}: {}",
+                    line_cursor.sum_total(),
+                    message
+                ),
+                doctree: doctree,
+            }
+        }
+    };
+
     TransitionResult::Success {
         doctree: doctree,
-        push_or_pop: PushOrPop::Push(vec![State::Body]),
-        line_advance: LineAdvance::Some(1),
+        push_or_pop: PushOrPop::Push(nested_state_stack),
+        line_advance: LineAdvance::Some(offset),
     }
 }
 
-pub fn parse_sphinx_tabularcolumns() {
-    todo!()
-}
-
-pub fn parse_sphinx_math_block() {
-    todo!()
-}
-
-pub fn parse_sphinx_productionlist() {
-    todo!()
-}
-
-// ========================
-//  A+-specific directives
-// ========================
-
-pub fn parse_aplus_questionnaire(
+/// A parser for the Sphinx `centered` directive.
+/// The directive argument is parsed as inline text and no content is allowed.
+pub fn parse_sphinx_centered(
     src_lines: &[String],
     mut doctree: DocTree,
     line_cursor: &mut LineCursor,
-    base_indent: usize,
     empty_after_marker: bool,
     first_indent: usize,
     body_indent: usize,
 ) -> TransitionResult {
-    let (key, difficulty, max_points): (String, String, String) = match scan_directive_arguments(
-            src_lines,
-            line_cursor,
-            body_indent,
-            Some(first_indent),
-            empty_after_marker,
+
+    let text = if let Some(arg) = scan_directive_arguments(
+        src_lines,
+        line_cursor,
+        body_indent,
+        Some(first_indent),
+        empty_after_marker,
     ) {
-        Some(lines) => aplus_key_difficulty_and_max_points(lines.join(" ").as_str(), line_cursor),
-        None => return TransitionResult::Failure {
+        arg.join(" ")
+    } else {
+        return TransitionResult::Failure {
             message: format!(
-                "A+ questionnaire on line {} was not given arguments. Computer says no...",
+                "Sphinx \"centered\" directive on line {} has no text to center. Computer says no...",
                 line_cursor.sum_total()
             ),
             doctree: doctree,
-        }
-    };
-
-    let (
-        submissions,
-        points_to_pass,
-        feedback,
-        title,
-        no_override,
-        pick_randomly,
-        preserve_questions_between_attempts,
-        category,
-        status,
-        reveal_model_at_max_submissions,
-        show_model,
-        allow_assistant_viewing,
-        allow_assistant_grading,
-    ) = if let Some(mut options) = scan_directive_options(src_lines, line_cursor, body_indent) {
-        (
-            options.remove("submissions"),
-            options.remove("points-to-pass"),
-            options.remove("feedback"),
-            options.remove("title"),
-            options.remove("no_override"),
-            options.remove("pick_randomly"),
-            options.remove("preserve-questions-between-attempts"),
-            options.remove("category"),
-            options.remove("status"),
-            options.remove("reveal-model-at-max-submissions"),
-            options.remove("show-model"),
-            options.remove("allow-assistant-viewing"),
-            options.remove("allow-assistant-grading"),
-        )
-    } else {
-        (
-            None, None, None, None, None, None, None, None, None, None, None, None, None,
-        )
-    };
-
-    use crate::common::QuizPoints;
-
-    let questionnaire_node = TreeNodeType::AplusQuestionnaire {
-        body_indent: body_indent,
-        key: key,
-        difficulty: if difficulty.is_empty() {
-            None
-        } else {
-            Some(difficulty)
-        },
-        max_points: if let Ok(result) = max_points.parse::<QuizPoints>() {
-            Some(result)
-        } else {
-            None
-        },
-        points_from_children: 0,
-        submissions: submissions,
-        points_to_pass: points_to_pass,
-        feedback: feedback,
-        title: title,
-        no_override: no_override,
-        pick_randomly: pick_randomly,
-        preserve_questions_between_attempts: preserve_questions_between_attempts,
-        category: category,
-        status: status,
-        reveal_model_at_max_submissions: reveal_model_at_max_submissions,
-        show_model: show_model,
-        allow_assistant_viewing: allow_assistant_viewing,
-        allow_assistant_grading: allow_assistant_grading,
+        };
     };
 
-    doctree = match doctree.push_data_and_focus(questionnaire_node) {
+    doctree = match doctree.push_data_and_focus(TreeNodeType::SphinxCentered) {
         Ok(tree) => tree,
         Err(tree) => {
             return TransitionResult::Failure {
@@ -1738,200 +1688,167 @@ pub fn parse_aplus_questionnaire(
         }
     };
 
+    doctree = match Parser::inline_parse(text, Some(&mut doctree), line_cursor) {
+        InlineParsingResult::Nodes(nodes_data) => {
+            for data in nodes_data {
+                doctree = match doctree.push_data(data) {
+                    Ok(tree) => tree,
+                    Err(tree) => {
+                        return TransitionResult::Failure {
+                            message: format!(
+                                "Node insertion error on line {}. Computer says no...",
+                                line_cursor.sum_total()
+                            ),
+                            doctree: tree,
+                        }
+                    }
+                };
+            }
+            doctree.focus_on_parent()
+        }
+        InlineParsingResult::NoNodes => doctree.focus_on_parent(),
+    };
+
     TransitionResult::Success {
         doctree: doctree,
-        push_or_pop: PushOrPop::Push(vec![State::AplusQuestionnaire]),
+        push_or_pop: PushOrPop::Neither,
         line_advance: LineAdvance::None,
     }
 }
 
-/// A `pick-one` type questionnaire question parser.
-pub fn parse_aplus_pick_one(
+/// A parser for the Sphinx `hlist` directive. The contained bullet list items are
+/// distributed into columns the same way Sphinx does it: each column receives
+/// `items / columns` items and the first `items % columns` columns receive one extra item.
+pub fn parse_sphinx_hlist(
     src_lines: &[String],
     mut doctree: DocTree,
     line_cursor: &mut LineCursor,
+    section_level: usize,
+    empty_after_marker: bool,
     first_indent: usize,
     body_indent: usize,
-    empty_after_marker: bool,
 ) -> TransitionResult {
 
-
-    /// Correct answers in `pick-one` and `pick-any` directives are marked with `*`.
-    /// A `pick-any` question may have neutral options, which are marked with `?`.
-    /// Neutral options are always counted as correct, whether the student selected them or not.
-    /// Initially selected options may be set with `+`.
-    /// The initially selected options are pre-selected when the exercise is loaded.
-    /// The `+` character is written before `*` or `?` if they are combined.
-    const APLUS_PICK_ONE_CHOICE_PATTERN: &'static str =
-        r"^(\s*)(?P<pre_selected>\+)?(?P<correct>\*)?(?P<label>\S+)\.[ ]+(?P<answer>.+)";
-    const APLUS_PICK_HINT_PATTERN: &'static str =
-        r"^(\s*)(?P<show_not_answered>!)?(?P<label>\S+)[ ]*§[ ]*(?P<hint>.+)";
-
-    use regex::{Captures, Regex};
-
-    lazy_static::lazy_static! {
-        static ref CHOICE_RE: Regex = Regex::new(APLUS_PICK_ONE_CHOICE_PATTERN).unwrap();
-        static ref HINT_RE: Regex = Regex::new(APLUS_PICK_HINT_PATTERN).unwrap();
-    }
-
-    // Parsing the directive arguments
-
-    use crate::common::QuizPoints;
-
-    let points: QuizPoints = match scan_directive_arguments(
+    if let Some(_) = scan_directive_arguments(
         src_lines,
         line_cursor,
         body_indent,
         Some(first_indent),
         empty_after_marker,
     ) {
-        Some(lines) => match lines.join(" ").as_str().parse() {
-            Ok(points) => points,
-            Err(e) => return TransitionResult::Failure {
-                message: format!("Quiz question points preceding line {} could not be parsed into an integer. Computer says no...", line_cursor.sum_total()),
-                doctree: doctree
-            }
-        }
-        None => return TransitionResult::Failure {
+        return TransitionResult::Failure {
             message: format!(
-                "No points provided for pick-one question on line {}. Computer says no...",
+                "Sphinx \"hlist\" directive on line {} does not take arguments. Computer says no...",
                 line_cursor.sum_total()
             ),
             doctree: doctree,
-        }
-    };
-
-    if let TreeNodeType::AplusQuestionnaire {
-        points_from_children,
-        ..
-    } = doctree.mut_node_data() {
-        *points_from_children += points;
+        };
     }
 
-    // Parsing directive options
-
-    let (class, required, key, dropdown) = if let Some(mut options) =
-        scan_directive_options
-        (src_lines, line_cursor, body_indent)
-    {
-        (
-            options.remove("class"),
-            options.remove("required"),
-            options.remove("key"),
-            options.remove("dropdown"),
-        )
+    let columns = if let Some(mut options) = scan_directive_options(
+        src_lines, line_cursor, body_indent
+    ) {
+        if let Some(columns) = options.remove("columns") {
+            match columns.trim().parse::<usize>() {
+                Ok(n) if n > 0 => n,
+                _ => return TransitionResult::Failure {
+                    message: format!(
+                        "Invalid number of columns \"{}\" in a Sphinx \"hlist\" directive before line {}. Computer says no...",
+                        columns,
+                        line_cursor.sum_total()
+                    ),
+                    doctree: doctree,
+                }
+            }
+        } else {
+            2
+        }
     } else {
-        (None, None, None, None)
+        2
     };
 
-    Parser::skip_empty_lines(src_lines, line_cursor);
+    let (content_lines, offset) = if line_cursor.relative_offset() < src_lines.len() {
+        match Parser::read_indented_block(
+            src_lines,
+            line_cursor.relative_offset(),
+            false,
+            true,
+            Some(body_indent),
+            Some(body_indent),
+            false,
+        ) {
+            IndentedBlockResult::Ok { lines, offset, .. } => (lines, offset),
+            _ => return TransitionResult::Failure {
+                message: format!(
+                    "Error when reading in the contents of a Sphinx \"hlist\" directive around line {}. Computer says no...",
+                    line_cursor.sum_total()
+                ),
+                doctree: doctree,
+            },
+        }
+    } else {
+        (Vec::new(), 0)
+    };
 
-    // Generating and focusing on node
+    // Split the contained bullet list into items, each of which is a vector of source lines.
+    // The line offset of each item is stored for the nested parsers.
+    let is_bullet_line = |line: &str, bullet: char| {
+        let mut chars = line.chars();
+        chars.next() == Some(bullet)
+            && match chars.next() {
+                Some(c) => c.is_whitespace(),
+                None => true,
+            }
+    };
 
-    let pick_one_node = TreeNodeType::AplusPickOne {
-        body_indent: body_indent,
-        class: class,
-        points: points,
-        required: if required.is_some() { true } else { false },
-        key: key,
-        dropdown: if dropdown.is_some() { true } else { false },
-    };
-
-    doctree = match doctree.push_data_and_focus(pick_one_node) {
-        Ok(tree) => tree,
-        Err(tree) => {
+    let mut bullet: Option<char> = None;
+    let mut items: Vec<(usize, Vec<String>)> = Vec::new();
+    for (index, line) in content_lines.iter().enumerate() {
+        if line.trim().is_empty() || line.starts_with(char::is_whitespace) {
+            if let Some((_, item_lines)) = items.last_mut() {
+                item_lines.push(line.clone());
+            }
+            continue;
+        }
+        let detected_bullet = match bullet {
+            Some(bullet) => bullet,
+            None => match line.chars().next() {
+                Some(c) if "*+-•‣⁃".contains(c) => {
+                    bullet = Some(c);
+                    c
+                }
+                _ => '*',
+            },
+        };
+        if is_bullet_line(line, detected_bullet) {
+            items.push((index, vec![line.clone()]));
+        } else {
             return TransitionResult::Failure {
                 message: format!(
-                    "Node insertion error on line {}. Computer says no...",
-                    line_cursor.sum_total()
+                    "Sphinx \"hlist\" directive before line {} may only contain a single bullet list. Computer says no...",
+                    line_cursor.sum_total() + index + 1
                 ),
-                doctree: tree,
-            }
+                doctree: doctree,
+            };
         }
-    };
-
-    // Check for assignment
-
-    Parser::skip_empty_lines(src_lines, line_cursor);
+    }
 
-    let start_line = match src_lines.get(line_cursor.relative_offset()) {
-        Some(line) => line,
-        None => return TransitionResult::Failure {
+    if items.is_empty() {
+        return TransitionResult::Failure {
             message: format!(
-                "Input overflow on line {} when parsing pick-one assignment. Computer says no...",
+                "Sphinx \"hlist\" directive before line {} contains no list items. Computer says no...",
                 line_cursor.sum_total()
             ),
-            doctree: doctree
-        }
-    };
-
-    let assignment_inline_nodes: Vec<TreeNodeType> = if !CHOICE_RE.is_match(start_line) {
-        let (block_lines, offset) = match Parser::read_text_block(src_lines, line_cursor.relative_offset(),  true, true, Some(body_indent),true) {
-            TextBlockResult::Ok {lines, offset } => (lines, offset),
-            TextBlockResult::Err { lines, offset } => return TransitionResult::Failure {
-                message: format!("Could not read pick-one assignment lines starting on line {}. Computer says no...", line_cursor.sum_total()),
-                doctree: doctree
-            }
-        };
-        let inline_nodes = match Parser::inline_parse(block_lines.join("\n"), None, line_cursor) {
-            InlineParsingResult::Nodes(nodes) => nodes,
-            _ => return TransitionResult::Failure {
-                message: format!("Could not parse pick-one assignment for inline nodes on line {}. Computer says no...", line_cursor.sum_total()),
-                doctree: doctree
-            }
-        };
-
-        line_cursor.increment_by(1);
-
-        inline_nodes
-    } else {
-        Vec::new()
-    };
-
-    // Add assignment node (paragraph) to tree
-
-    Parser::skip_empty_lines(src_lines, line_cursor);
-
-    if !assignment_inline_nodes.is_empty() {
-        let assignment_node = TreeNodeType::Paragraph {
-            indent: body_indent,
-        };
-        doctree = match doctree.push_data_and_focus(assignment_node) {
-            Ok(tree) => tree,
-            Err(tree) => {
-                return TransitionResult::Failure {
-                    message: format!(
-                        "Node insertion error on line {}. Computer says no...",
-                        line_cursor.sum_total()
-                    ),
-                    doctree: tree,
-                }
-            }
+            doctree: doctree,
         };
-        for node in assignment_inline_nodes {
-            doctree = match doctree.push_data(node) {
-                Ok(tree) => tree,
-                Err(tree) => {
-                    return TransitionResult::Failure {
-                        message: format!(
-                            "Node insertion error on line {}. Computer says no...",
-                            line_cursor.sum_total()
-                        ),
-                        doctree: tree,
-                    }
-                }
-            };
-        }
-        doctree = doctree.focus_on_parent()
     }
 
-    Parser::skip_empty_lines(src_lines, line_cursor);
-
-    // Read question choices
-
-    doctree = match doctree.push_data_and_focus(TreeNodeType::AplusPickChoices {
-        body_indent: body_indent,
-    }) {
+    doctree = match doctree.push_data_and_focus(
+        TreeNodeType::SphinxHList {
+            columns: columns,
+            body_indent: body_indent,
+        }
+    ) {
         Ok(tree) => tree,
         Err(tree) => {
             return TransitionResult::Failure {
@@ -1944,54 +1861,22 @@ pub fn parse_aplus_pick_one(
         }
     };
 
-    while let Some(current_line) = src_lines.get(line_cursor.relative_offset()) {
-        let indent = current_line
-            .chars()
-            .take_while(|c| c.is_whitespace())
-            .count();
-
-        if indent != body_indent {
-            break;
-        }
+    let n_of_items = items.len();
+    let items_per_column = n_of_items / columns;
+    let n_of_longer_columns = n_of_items % columns;
+    let mut items = items.into_iter();
 
-        let captures: Captures = if let Some(capts) = CHOICE_RE.captures(current_line) {
-            capts
-        } else {
-            break;
-        };
+    for column in 0..columns {
 
-        let label = captures.name("label").unwrap().as_str().to_string();
-        let pre_selected = captures.name("pre_selected");
-        let correct = captures.name("correct");
-        let answer = if let Some(capture) = captures.name("answer") {
-            capture.as_str()
+        let n_of_column_items = if column < n_of_longer_columns {
+            items_per_column + 1
         } else {
-            ""
-        };
-
-        if answer.trim().is_empty() {
-            return TransitionResult::Failure {
-                message: format!("Discovered a pick-one answer without content on line {}. Computer says no...", line_cursor.sum_total()),
-                doctree: doctree
-            };
-        }
-
-        let answer_nodes: Vec<TreeNodeType> = match Parser::inline_parse(answer.to_string(), None, line_cursor) {
-            InlineParsingResult::Nodes(nodes) => nodes,
-            _ => return TransitionResult::Failure {
-                message: format!("Could not parse pick-one answer on line {} for inline nodes. Computer says no...", line_cursor.sum_total()),
-                doctree: doctree
-            }
-        };
-
-        let choice_node = TreeNodeType::AplusPickChoice {
-            label: label,
-            is_pre_selected: pre_selected.is_some(),
-            is_correct: correct.is_some(),
-            is_neutral: false, // pick-one nodes don't have this set
+            items_per_column
         };
 
-        doctree = match doctree.push_data_and_focus(choice_node) {
+        doctree = match doctree.push_data_and_focus(
+            TreeNodeType::SphinxHListColumn { body_indent: body_indent }
+        ) {
             Ok(tree) => tree,
             Err(tree) => {
                 return TransitionResult::Failure {
@@ -2003,384 +1888,515 @@ pub fn parse_aplus_pick_one(
                 }
             }
         };
-        for node in answer_nodes {
-            doctree = match doctree.push_data(node) {
-                Ok(tree) => tree,
-                Err(tree) => {
+        let column_id = doctree.current_node_id();
+
+        let mut column_lines: Vec<String> = Vec::new();
+        let mut column_line_offset: Option<usize> = None;
+        for (item_offset, mut item_lines) in items.by_ref().take(n_of_column_items) {
+            if column_line_offset.is_none() {
+                column_line_offset = Some(item_offset);
+            }
+            column_lines.append(&mut item_lines);
+        }
+
+        if let Some(column_line_offset) = column_line_offset {
+            doctree = match Parser::new(
+                &column_lines,
+                doctree,
+                body_indent,
+                line_cursor.sum_total() + column_line_offset,
+                State::Body,
+                section_level,
+            ).parse() {
+                ParsingResult::EOF { doctree, .. } | ParsingResult::EmptyStateStack { doctree, .. } => doctree,
+                ParsingResult::Failure { message, doctree, .. } => {
                     return TransitionResult::Failure {
                         message: format!(
-                            "Node insertion error on line {}. Computer says no...",
-                            line_cursor.sum_total()
+                            "Error when parsing a Sphinx \"hlist\" column on line {}: {}",
+                            line_cursor.sum_total() + column_line_offset,
+                            message
                         ),
-                        doctree: tree,
+                        doctree: doctree,
                     }
                 }
             };
+            // Return from the depths of the nested list to the column...
+            while doctree.current_node_id() != column_id {
+                doctree = doctree.focus_on_parent();
+            }
         }
+
         doctree = doctree.focus_on_parent();
+    }
 
-        line_cursor.increment_by(1);
+    doctree = doctree.focus_on_parent();
+
+    TransitionResult::Success {
+        doctree: doctree,
+        push_or_pop: PushOrPop::Neither,
+        line_advance: LineAdvance::Some(offset),
     }
+}
 
-    if doctree.n_of_children() == 0 {
+/// A parser for the Sphinx `highlight` directive. Generates no nodes, but sets the default language
+/// and line number threshold of the literal and code blocks that follow it in the document.
+pub fn parse_sphinx_highlight(
+    src_lines: &[String],
+    mut doctree: DocTree,
+    line_cursor: &mut LineCursor,
+    empty_after_marker: bool,
+    first_indent: usize,
+    body_indent: usize,
+) -> TransitionResult {
+
+    let directive_line = line_cursor.sum_total() + 1;
+
+    let language = if let Some(arg) = scan_directive_arguments(
+        src_lines,
+        line_cursor,
+        body_indent,
+        Some(first_indent),
+        empty_after_marker,
+    ) {
+        arg.join(" ").trim().to_string()
+    } else {
         return TransitionResult::Failure {
             message: format!(
-                "Found no choices for pick-one question on line {}. Computer says no...",
+                "Sphinx \"highlight\" directive on line {} is missing a language argument. Computer says no...",
                 line_cursor.sum_total()
             ),
             doctree: doctree,
         };
-    }
-
-    doctree = doctree.focus_on_parent();
-
-    // Read possible hints inside the answers environment
-
-    Parser::skip_empty_lines(src_lines, line_cursor);
+    };
 
-    doctree = match doctree.push_data_and_focus(TreeNodeType::AplusQuestionnaireHints {
-        body_indent: body_indent,
-    }) {
-        Ok(tree) => tree,
-        Err(tree) => {
-            return TransitionResult::Failure {
-                message: format!(
-                    "Node insertion error on line {}. Computer says no...",
-                    line_cursor.sum_total()
-                ),
-                doctree: tree,
+    let linenothreshold = if let Some(mut options) = scan_directive_options(
+        src_lines, line_cursor, body_indent
+    ) {
+        if let Some(threshold) = options.remove("linenothreshold") {
+            if let Ok(threshold) = threshold.trim().parse::<usize>() {
+                Some(threshold)
+            } else {
+                doctree.add_system_message(
+                    SystemMessageLevel::Warning,
+                    Some(directive_line),
+                    format!(
+                        "Invalid line number threshold \"{}\" in a Sphinx \"highlight\" directive. Ignoring.",
+                        threshold
+                    ),
+                );
+                None
             }
+        } else {
+            None
         }
+    } else {
+        None
     };
 
-    while let Some(current_line) = src_lines.get(line_cursor.relative_offset()) {
-        let indent = if !current_line.is_empty() {
-            current_line
-                .chars()
-                .take_while(|c| c.is_whitespace())
-                .count()
-        } else {
-            body_indent
-        };
-
-        if indent != body_indent {
-            break;
-        }
+    doctree.set_highlight_language(language.as_str(), linenothreshold);
 
-        let captures = if let Some(capts) = HINT_RE.captures(current_line) {
-            capts
-        } else {
-            break;
-        };
+    TransitionResult::Success {
+        doctree: doctree,
+        push_or_pop: PushOrPop::Neither,
+        line_advance: LineAdvance::None,
+    }
+}
 
-        let show_not_answered = captures.name("show_not_answered");
-        let label = match captures.name("label") {
-            Some(label) => label.as_str().to_string(),
-            None => {
-                return TransitionResult::Failure {
-                    message: format!(
-                        "No enumerator for pick-one hint on line {}. Computer says no...",
-                        line_cursor.sum_total()
-                    ),
-                    doctree: doctree,
-                }
-            }
-        };
-        let hint: &str = if let Some(hint) = captures.name("hint") {
-            hint.as_str().trim()
-        } else {
-            return TransitionResult::Failure {
-                message: format!(
-                    "No hint text for pick-one hint on line {}. Computer says no...",
-                    line_cursor.sum_total()
-                ),
-                doctree: doctree,
+/// A parser for the Sphinx-specific `code-block` directive. See https://www.sphinx-doc.org/en/master/usage/restructuredtext/directives.html#directive-code-block
+/// for explanations of different settings and arguments.
+pub fn parse_sphinx_code_block(
+    src_lines: &[String],
+    mut doctree: DocTree,
+    line_cursor: &mut LineCursor,
+    base_indent: usize,
+    empty_after_marker: bool,
+    body_indent: usize,
+    first_indent: Option<usize>,
+) -> TransitionResult {
+    // Read directive argument: the formal language (should be recognized by Pygments)
+    let formal_language = if let Some(arg) = scan_directive_arguments(
+        src_lines,
+        line_cursor,
+        body_indent,
+        first_indent,
+        empty_after_marker,
+    ) {
+        arg.join("")
+    } else if let Some(language) = doctree.shared_highlight_language() {
+        language.clone() // set by a preceding "highlight" directive
+    } else {
+        String::from("python") // the Sphinx "highlight_language" setting default
+    };
+
+    // Read the settings...
+    let (linenos, lineno_start, emphasize_lines, caption, name, dedent, force) =
+        if let Some(mut settings) = scan_directive_options(
+            src_lines, line_cursor, body_indent
+        ){
+            let mut linenos = if let Some(linenos) = settings.remove("linenos") {
+                true
+            } else {
+                false
+            };
+            let lineno_start = if let Some(start_line) = settings.remove("lineno-start") {
+                if let Ok(number) = start_line.parse::<usize>() {
+                    linenos = true;
+                    Some(number)
+                } else {
+                    None
+                }
+            } else {
+                None
+            };
+            let emphasize_lines = if let Some(line_numbers) = settings.remove("emphasize-lines")
+            {
+                let emph_lines = line_numbers
+                    .split(",")
+                    .filter(|s| !s.trim().is_empty())
+                    .map(|s| s.trim())
+                    .filter_map(|s| s.parse::<usize>().ok())
+                    .collect::<Vec<usize>>();
+
+                Some(emph_lines)
+            } else {
+                None
+            };
+            let caption = settings.remove("caption");
+            let name = if let Some(refname) = settings.remove("name") {
+                Some(crate::common::normalize_refname(&refname))
+            } else {
+                None
+            };
+            let dedent = if let Some(dedent) = settings.remove("dedent") {
+                if let Ok(dedent) = dedent.parse::<usize>() {
+                    Some(dedent)
+                } else {
+                    None
+                }
+            } else {
+                None
+            };
+            let force = if let Some(force) = settings.remove("force") {
+                true
+            } else {
+                false
             };
+
+            (
+                linenos,
+                lineno_start,
+                emphasize_lines,
+                caption,
+                name,
+                dedent,
+                force,
+            )
+        } else {
+            (false, None, None, None, None, None, false)
         };
 
-        if hint.is_empty() {
+    // Construct node from settings and read content...
+
+    let (code_text, offset) = match Parser::read_indented_block(
+        src_lines,
+        line_cursor.relative_offset(),
+        false,
+        true,
+        Some(body_indent),
+        None,
+        false,
+    ) {
+        IndentedBlockResult::Ok {mut lines, minimum_indent, offset, blank_finish } => {
+            // Remove empty lines from front
+            lines = lines
+                .iter()
+                .skip_while(|line| line.is_empty())
+                .map(|s| s.to_string())
+                .collect();
+
+            // Remove empty lines from back
+            while let Some(line) = lines.last_mut() {
+                if line.is_empty() {
+                    lines.pop();
+                } else {
+                    break;
+                }
+            }
+
+            (lines.join("\n") + "\n", offset)
+        }
+        _ => {
             return TransitionResult::Failure {
                 message: format!(
-                    "Empty  hint text for hint on line {}. Computer says no...",
-                    line_cursor.sum_total()
+                    "Error when parsing a Sphinx code block on line {}.",
+                    line_cursor.sum_total(),
                 ),
                 doctree: doctree,
-            };
+            }
         }
+    };
 
-        let hint_nodes: Vec<TreeNodeType> = match Parser::inline_parse(hint.to_string(), None, line_cursor) {
-            InlineParsingResult::Nodes(nodes) => nodes,
-            _ => return TransitionResult::Failure {
-                message: format!("Could not parse pick-one answer on line {} for inline nodes. Computer says no...", line_cursor.sum_total()),
-                doctree: doctree
-            }
-        };
+    let linenos = linenos || doctree.highlight_linenos(code_text.lines().count());
 
-        if hint_nodes.is_empty() {
+    let code_block_data = TreeNodeType::SphinxCodeBlock {
+        language: formal_language,
+        linenos: linenos,
+        lineno_start: lineno_start,
+        emphasize_lines: emphasize_lines,
+        caption: caption,
+        name: name,
+        dedent: dedent,
+        force: force,
+        code_text: code_text,
+    };
+
+    doctree = match doctree.push_data(code_block_data) {
+        Ok(tree) => tree,
+        Err(tree) => {
             return TransitionResult::Failure {
                 message: format!(
-                    "No inline nodes found for pick-one hint on line {}. Computer says no...",
+                    "Erro when parsing Sphinx code block on line {}. Computer says no...",
                     line_cursor.sum_total()
                 ),
-                doctree: doctree,
-            };
-        }
-
-        let hint_node = TreeNodeType::AplusQuestionnaireHint {
-            label: label,
-            show_when_not_selected: show_not_answered.is_some(),
-            question_type: crate::common::AplusQuestionnaireType::PickOne,
-        };
-
-        doctree = match doctree.push_data_and_focus(hint_node) {
-            Ok(tree) => tree,
-            Err(tree) => {
-                return TransitionResult::Failure {
-                    message: format!(
-                        "Node insertion error on line {}. Computer says no...",
-                        line_cursor.sum_total()
-                    ),
-                    doctree: tree,
-                }
+                doctree: tree,
             }
-        };
-        for node in hint_nodes {
-            doctree = match doctree.push_data(node) {
-                Ok(tree) => tree,
-                Err(tree) => {
-                    return TransitionResult::Failure {
-                        message: format!(
-                            "Node insertion error on line {}. Computer says no...",
-                            line_cursor.sum_total()
-                        ),
-                        doctree: tree,
-                    }
-                }
-            };
         }
-        doctree = doctree.focus_on_parent();
-
-        line_cursor.increment_by(1);
-    }
-
-    Parser::skip_empty_lines(src_lines, line_cursor);
-
-    doctree = doctree.focus_on_parent(); // Focus on pick-one
-    doctree = doctree.focus_on_parent(); // Focus on questionnaire
-
-    // Return with modified doctree
+    };
 
     TransitionResult::Success {
         doctree: doctree,
         push_or_pop: PushOrPop::Neither,
-        line_advance: LineAdvance::None,
+        line_advance: LineAdvance::Some(offset),
     }
 }
 
-/// A `pick-any` type questionnaire question parser.
-pub fn parse_aplus_pick_any(
+/// A parser for the Sphinx `literalinclude` directive. Reads the file given as an argument
+/// relative to the folder of the document being parsed and generates a `SphinxCodeBlock`
+/// out of its (filtered) contents. See https://www.sphinx-doc.org/en/master/usage/restructuredtext/directives.html#directive-literalinclude
+/// for explanations of different settings.
+///
+/// If the file cannot be read or the given filters do not match its contents,
+/// an error is reported and the directive is skipped.
+pub fn parse_sphinx_literalinclude(
     src_lines: &[String],
     mut doctree: DocTree,
     line_cursor: &mut LineCursor,
+    empty_after_marker: bool,
     first_indent: usize,
     body_indent: usize,
-    empty_after_marker: bool,
 ) -> TransitionResult {
 
-    const APLUS_PICK_ANY_CHOICE_PATTERN: &'static str = r"^(\s*)(?P<pre_selected>\+)?(?:(?P<neutral>\?)|(?P<correct>\*))?(?P<label>\S+)\.[ ]+(?P<answer>.+)";
-    const APLUS_PICK_HINT_PATTERN: &'static str =
-        r"^(\s*)(?P<show_not_answered>!)?(?P<label>\S+)[ ]*§[ ]*(?P<hint>.+)";
-
-    lazy_static::lazy_static! {
-        static ref CHOICE_RE: regex::Regex = regex::Regex::new(APLUS_PICK_ANY_CHOICE_PATTERN).unwrap();
-        static ref HINT_RE: regex::Regex = regex::Regex::new(APLUS_PICK_HINT_PATTERN).unwrap();
-    }
+    let directive_line = line_cursor.sum_total() + 1;
 
-    let points: crate::common::QuizPoints = match scan_directive_arguments(
+    let file_name = if let Some(arg) = scan_directive_arguments(
         src_lines,
         line_cursor,
         body_indent,
         Some(first_indent),
         empty_after_marker,
     ) {
-        Some(lines) => match lines.join(" ").as_str().parse() {
-            Ok(points) => points,
-            Err(e) => return TransitionResult::Failure {
-                message: format!(
-                    "Quiz question points preceding line {} could not be parsed into an integer. Computer says no...",
-                    line_cursor.sum_total()
-                ),
-                doctree: doctree
-            }
-        },
-        None => return TransitionResult::Failure {
+        arg.join("").trim().to_string()
+    } else {
+        return TransitionResult::Failure {
             message: format!(
-                "No points provided for pick-any question on line {}. Computer says no...",
-                line_cursor.sum_total()
+                "Sphinx \"literalinclude\" directive on line {} is missing a file name. Computer says no...",
+                directive_line
             ),
             doctree: doctree,
+        };
+    };
+
+    let mut options = if let Some(options) = scan_directive_options(
+        src_lines, line_cursor, body_indent
+    ) {
+        options
+    } else {
+        HashMap::new()
+    };
+
+    let code_text = match read_literalinclude_text(doctree.shared_file_folder(), &file_name, &mut options) {
+        Ok(text) => text,
+        Err(message) => {
+            doctree.add_system_message(
+                SystemMessageLevel::Error,
+                Some(directive_line),
+                format!(
+                    "Could not include \"{}\" in a Sphinx \"literalinclude\" directive: {}. Skipping.",
+                    file_name, message
+                ),
+            );
+            return TransitionResult::Success {
+                doctree: doctree,
+                push_or_pop: PushOrPop::Neither,
+                line_advance: LineAdvance::None,
+            };
         }
     };
 
-    if let TreeNodeType::AplusQuestionnaire {
-        points_from_children,
-        ..
-    } = doctree.mut_node_data() {
-        *points_from_children += points;
-    }
+    let language = if let Some(language) = options.remove("language") {
+        language
+    } else if options.contains_key("diff") {
+        String::from("udiff")
+    } else if let Some(language) = doctree.shared_highlight_language() {
+        language.clone()
+    } else {
+        String::from("python")
+    };
 
-    let (
-        class,
-        required,
-        key,
-        partial_points,
-        randomized,
-        correct_count,
-        preserve_questions_between_attempts,
-    ) = if let Some(mut options) = scan_directive_options
-    (src_lines, line_cursor, body_indent) {
-        (
-            options.remove("class"),
-            options.remove("required"),
-            options.remove("key"),
-            options.remove("partial-points"),
-            options.remove("randomized"),
-            options.remove("correct-count"),
-            options.remove("preserve-questions-between-attempts"),
-        )
-    } else {
-        (None, None, None, None, None, None, None)
-    };
+    let n_of_lines = code_text.lines().count();
 
-    let pick_any_node = TreeNodeType::AplusPickAny {
-        body_indent: body_indent,
-        points: points,
-        class: class,
-        required: if required.is_some() { true } else { false },
-        key: key,
-        partial_points: if partial_points.is_some() {
-            true
-        } else {
-            false
-        },
-        randomized: if randomized.is_some() && correct_count.is_some() {
-            true
-        } else {
-            false
-        },
-        correct_count: if randomized.is_some() && correct_count.is_some() {
-            if let Ok(result) = correct_count.unwrap().parse() {
-                Some(result)
-            } else {
-                return TransitionResult::Failure {
-                    message: format!("No correct count provided for pick-any on line {} with randomization activated. Computer says no...", line_cursor.sum_total()),
-                    doctree: doctree
-                };
-            }
+    let mut linenos = options.remove("linenos").is_some();
+    let lineno_start = if let Some(start_line) = options.remove("lineno-start") {
+        if let Ok(number) = start_line.trim().parse::<usize>() {
+            linenos = true;
+            Some(number)
         } else {
             None
-        },
-        preserve_questions_between_attempts: if preserve_questions_between_attempts.is_some() {
-            true
-        } else {
-            false
-        },
+        }
+    } else {
+        None
+    };
+    let linenos = linenos || doctree.highlight_linenos(n_of_lines);
+
+    let emphasize_lines = if let Some(line_numbers) = options.remove("emphasize-lines") {
+        match parse_line_numbers(&line_numbers, n_of_lines) {
+            Ok(indices) => Some(indices.iter().map(|index| index + 1).collect::<Vec<usize>>()),
+            Err(message) => {
+                doctree.add_system_message(
+                    SystemMessageLevel::Warning,
+                    Some(directive_line),
+                    format!(
+                        "Invalid emphasized lines in a Sphinx \"literalinclude\" directive: {}. Ignoring.",
+                        message
+                    ),
+                );
+                None
+            }
+        }
+    } else {
+        None
+    };
+    let caption = options.remove("caption");
+    let name = if let Some(refname) = options.remove("name") {
+        Some(crate::common::normalize_refname(&refname))
+    } else {
+        None
+    };
+    let dedent = if let Some(dedent) = options.remove("dedent") {
+        dedent.trim().parse::<usize>().ok()
+    } else {
+        None
     };
+    let force = options.remove("force").is_some();
 
-    doctree = match doctree.push_data_and_focus(pick_any_node) {
+    let code_block_data = TreeNodeType::SphinxCodeBlock {
+        language: language,
+        linenos: linenos,
+        lineno_start: lineno_start,
+        emphasize_lines: emphasize_lines,
+        caption: caption,
+        name: name,
+        dedent: dedent,
+        force: force,
+        code_text: code_text,
+    };
+
+    doctree = match doctree.push_data(code_block_data) {
         Ok(tree) => tree,
         Err(tree) => {
             return TransitionResult::Failure {
                 message: format!(
                     "Node insertion error on line {}. Computer says no...",
-                    line_cursor.sum_total()
+                    directive_line
                 ),
                 doctree: tree,
             }
         }
     };
 
-    // Check for assignment
+    TransitionResult::Success {
+        doctree: doctree,
+        push_or_pop: PushOrPop::Neither,
+        line_advance: LineAdvance::None,
+    }
+}
 
-    Parser::skip_empty_lines(src_lines, line_cursor);
+/// A parser for the Sphinx `glossary` directive. The contents are parsed as a definition list,
+/// the terms of which are turned into reference targets by the doctree.
+/// If the `sorted` option is given, the terms are sorted alphabetically.
+pub fn parse_sphinx_glossary(
+    src_lines: &[String],
+    mut doctree: DocTree,
+    line_cursor: &mut LineCursor,
+    section_level: usize,
+    empty_after_marker: bool,
+    first_indent: usize,
+    body_indent: usize,
+) -> TransitionResult {
 
-    let start_line = match src_lines.get(line_cursor.relative_offset()) {
-        Some(line) => line,
-        None => return TransitionResult::Failure {
+    if let Some(_) = scan_directive_arguments(
+        src_lines,
+        line_cursor,
+        body_indent,
+        Some(first_indent),
+        empty_after_marker,
+    ) {
+        return TransitionResult::Failure {
             message: format!(
-                "Input overflow on line {} when parsing pick-any assignment. Computer says no...",
+                "Sphinx \"glossary\" directive on line {} does not take arguments. Computer says no...",
                 line_cursor.sum_total()
             ),
-            doctree: doctree
-        }
-    };
-
-    let assignment_inline_nodes: Vec<TreeNodeType> = if !CHOICE_RE.is_match(start_line) {
-        let (block_lines, offset) = match Parser::read_text_block(src_lines, line_cursor.relative_offset(),  true, true, Some(body_indent), true) {
-            TextBlockResult::Ok {lines, offset } => (lines, offset),
-            TextBlockResult::Err {lines, offset } => return TransitionResult::Failure {
-                message: format!("Could not read pick-any assignment lines starting on line {}. Computer says no...", line_cursor.sum_total()),
-                doctree: doctree
-            }
-        };
-
-        let inline_nodes = match Parser::inline_parse(block_lines.join("\n"), None, line_cursor) {
-            InlineParsingResult::Nodes(nodes) => nodes,
-            _ => return TransitionResult::Failure {
-                message: format!("Could not parse pick-any assignment for inline nodes on line {}. Computer says no...", line_cursor.sum_total()),
-                doctree: doctree
-            }
+            doctree: doctree,
         };
+    }
 
-        line_cursor.increment_by(1);
-
-        inline_nodes
+    let sorted = if let Some(mut options) = scan_directive_options(
+        src_lines, line_cursor, body_indent
+    ) {
+        options.remove("sorted").is_some()
     } else {
-        Vec::new()
+        false
     };
 
-    // Add assignment node (paragraph) to tree
+    let (content_lines, offset) = if line_cursor.relative_offset() < src_lines.len() {
+        match Parser::read_indented_block(
+            src_lines,
+            line_cursor.relative_offset(),
+            false,
+            true,
+            Some(body_indent),
+            Some(body_indent),
+            false,
+        ) {
+            IndentedBlockResult::Ok { lines, offset, .. } => (lines, offset),
+            _ => return TransitionResult::Failure {
+                message: format!(
+                    "Error when reading in the contents of a Sphinx \"glossary\" directive around line {}. Computer says no...",
+                    line_cursor.sum_total()
+                ),
+                doctree: doctree,
+            },
+        }
+    } else {
+        (Vec::new(), 0)
+    };
 
-    if !assignment_inline_nodes.is_empty() {
-        let assignment_node = TreeNodeType::Paragraph {
-            indent: body_indent,
-        };
-        doctree = match doctree.push_data_and_focus(assignment_node) {
-            Ok(tree) => tree,
-            Err(tree) => {
-                return TransitionResult::Failure {
-                    message: format!(
-                        "Node insertion error on line {}. Computer says no...",
-                        line_cursor.sum_total()
-                    ),
-                    doctree: tree,
-                }
-            }
+    if content_lines.iter().all(|line| line.trim().is_empty()) {
+        return TransitionResult::Failure {
+            message: format!(
+                "Sphinx \"glossary\" directive before line {} has no content. Computer says no...",
+                line_cursor.sum_total()
+            ),
+            doctree: doctree,
         };
-        for node in assignment_inline_nodes {
-            doctree = match doctree.push_data(node) {
-                Ok(tree) => tree,
-                Err(tree) => {
-                    return TransitionResult::Failure {
-                        message: format!(
-                            "Node insertion error on line {}. Computer says no...",
-                            line_cursor.sum_total()
-                        ),
-                        doctree: tree,
-                    }
-                }
-            };
-        }
-        doctree = doctree.focus_on_parent()
     }
 
-    Parser::skip_empty_lines(src_lines, line_cursor);
-
-    // Read question choices
-
-    doctree = match doctree.push_data_and_focus(TreeNodeType::AplusPickChoices {
-        body_indent: body_indent,
-    }) {
+    doctree = match doctree.push_data_and_focus(
+        TreeNodeType::SphinxGlossary {
+            sorted: sorted,
+            body_indent: body_indent,
+        }
+    ) {
         Ok(tree) => tree,
         Err(tree) => {
             return TransitionResult::Failure {
@@ -2392,106 +2408,154 @@ pub fn parse_aplus_pick_any(
             }
         }
     };
+    let glossary_id = doctree.current_node_id();
 
-    while let Some(current_line) = src_lines.get(line_cursor.relative_offset()) {
-        let indent = current_line
-            .chars()
-            .take_while(|c| c.is_whitespace())
-            .count();
-
-        if indent != body_indent {
-            break;
+    doctree = match Parser::new(
+        &content_lines,
+        doctree,
+        body_indent,
+        line_cursor.sum_total(),
+        State::Body,
+        section_level,
+    ).parse() {
+        ParsingResult::EOF { doctree, .. } | ParsingResult::EmptyStateStack { doctree, .. } => doctree,
+        ParsingResult::Failure { message, doctree, .. } => {
+            return TransitionResult::Failure {
+                message: format!(
+                    "Error when parsing a Sphinx \"glossary\" on line {}: {}",
+                    line_cursor.sum_total(),
+                    message
+                ),
+                doctree: doctree,
+            }
         }
+    };
 
-        let captures: regex::Captures = if let Some(capts) = CHOICE_RE.captures(current_line) {
-            capts
-        } else {
-            break;
-        };
-
-        let pre_selected = captures.name("pre_selected");
-        let correct = captures.name("correct");
-        let neutral = captures.name("neutral");
-
-        let label = captures.name("label").unwrap().as_str().to_string();
-        let answer = if let Some(capture) = captures.name("answer") {
-            capture.as_str()
-        } else {
-            ""
-        };
+    // Return from the depths of the definition list to the glossary...
+    while doctree.current_node_id() != glossary_id {
+        doctree = doctree.focus_on_parent();
+    }
 
-        if answer.trim().is_empty() {
-            return TransitionResult::Failure {
-                message: format!("Discovered a pick-any answer without content on line {}. Computer says no...", line_cursor.sum_total()),
-                doctree: doctree
-            };
+    if sorted {
+        if let Some(children) = doctree.mut_children() {
+            for child in children.iter_mut() {
+                if let TreeNodeType::DefinitionList { .. } = child.shared_data() {
+                    if let Some(items) = child.mut_children() {
+                        items.retain(|item| if let TreeNodeType::EmptyLine = item.shared_data() { false } else { true });
+                        items.sort_by_key(|item| match item.shared_data() {
+                            TreeNodeType::DefinitionListItem { term, .. } => crate::common::normalize_refname(term),
+                            _ => String::new()
+                        });
+                    }
+                }
+            }
         }
+    }
 
-        let answer_nodes: Vec<TreeNodeType> = match Parser::inline_parse(answer.to_string(), None, line_cursor) {
-            InlineParsingResult::Nodes(nodes) => nodes,
-            _ => return TransitionResult::Failure {
-                message: format!("Could not parse pick-any answer on line {} for inline nodes. Computer says no...", line_cursor.sum_total()),
-                doctree: doctree
-            }
-        };
+    doctree = doctree.focus_on_parent();
 
-        let choice_node = TreeNodeType::AplusPickChoice {
-            label: label,
-            is_pre_selected: pre_selected.is_some(),
-            is_correct: correct.is_some(),
-            is_neutral: neutral.is_some(),
-        };
+    TransitionResult::Success {
+        doctree: doctree,
+        push_or_pop: PushOrPop::Neither,
+        line_advance: LineAdvance::Some(offset),
+    }
+}
 
-        doctree = match doctree.push_data_and_focus(choice_node) {
-            Ok(tree) => tree,
-            Err(tree) => {
-                return TransitionResult::Failure {
-                    message: format!(
-                        "Node insertion error on line {}. Computer says no...",
-                        line_cursor.sum_total()
-                    ),
-                    doctree: tree,
-                }
-            }
-        };
-        for node in answer_nodes {
-            doctree = match doctree.push_data(node) {
-                Ok(tree) => tree,
-                Err(tree) => {
-                    return TransitionResult::Failure {
-                        message: format!(
-                            "Node insertion error on line {}. Computer says no...",
-                            line_cursor.sum_total()
-                        ),
-                        doctree: tree,
-                    }
-                }
-            };
-        }
-        doctree = doctree.focus_on_parent();
+/// A parser for the Sphinx `sectionauthor` directive. The author, given in the form `Name <email>`,
+/// is recorded in the enclosing section.
+pub fn parse_sphinx_sectionauthor(
+    src_lines: &[String],
+    doctree: DocTree,
+    line_cursor: &mut LineCursor,
+    empty_after_marker: bool,
+    first_indent: usize,
+    body_indent: usize,
+) -> TransitionResult {
+    parse_sphinx_author(
+        src_lines,
+        doctree,
+        line_cursor,
+        empty_after_marker,
+        first_indent,
+        body_indent,
+        crate::common::SphinxAuthorKind::Section,
+    )
+}
 
-        line_cursor.increment_by(1);
-    }
+/// A parser for the Sphinx `codeauthor` directive. The author, given in the form `Name <email>`,
+/// is recorded in the enclosing section.
+pub fn parse_sphinx_codeauthor(
+    src_lines: &[String],
+    doctree: DocTree,
+    line_cursor: &mut LineCursor,
+    empty_after_marker: bool,
+    first_indent: usize,
+    body_indent: usize,
+) -> TransitionResult {
+    parse_sphinx_author(
+        src_lines,
+        doctree,
+        line_cursor,
+        empty_after_marker,
+        first_indent,
+        body_indent,
+        crate::common::SphinxAuthorKind::Code,
+    )
+}
 
-    if doctree.n_of_children() == 0 {
+/// A parser for the Sphinx `index` directive. Each line of the argument holds one or more index entries,
+/// such as `single: loop; while`. The `name` option turns the location of the entries into a reference target.
+pub fn parse_sphinx_index(
+    src_lines: &[String],
+    mut doctree: DocTree,
+    line_cursor: &mut LineCursor,
+    empty_after_marker: bool,
+    first_indent: usize,
+    body_indent: usize,
+) -> TransitionResult {
+
+    let lines = if let Some(lines) = scan_directive_arguments(
+        src_lines,
+        line_cursor,
+        body_indent,
+        Some(first_indent),
+        empty_after_marker,
+    ) {
+        lines
+    } else {
         return TransitionResult::Failure {
             message: format!(
-                "Found no choices for pick-any question on line {}. Computer says no...",
+                "Sphinx \"index\" directive on line {} has no entries. Computer says no...",
                 line_cursor.sum_total()
             ),
             doctree: doctree,
         };
-    }
-
-    doctree = doctree.focus_on_parent();
+    };
 
-    // Read possible hints inside the answers environment
+    let mut entries = Vec::new();
+    for line in lines.iter() {
+        match crate::common::parse_index_entries(line) {
+            Ok(line_entries) => entries.extend(line_entries),
+            Err(message) => {
+                return TransitionResult::Failure {
+                    message: format!(
+                        "Sphinx \"index\" directive before line {}: {}. Computer says no...",
+                        line_cursor.sum_total(),
+                        message
+                    ),
+                    doctree: doctree,
+                }
+            }
+        }
+    }
 
-    Parser::skip_empty_lines(src_lines, line_cursor);
+    if let Some(mut options) = scan_directive_options(src_lines, line_cursor, body_indent) {
+        if let Some(name) = options.remove("name") {
+            doctree.push_to_internal_target_stack(crate::common::normalize_refname(name.as_str()));
+        }
+    }
 
-    doctree = match doctree.push_data_and_focus(TreeNodeType::AplusQuestionnaireHints {
-        body_indent: body_indent,
-    }) {
+    doctree = match doctree.push_data(TreeNodeType::SphinxIndex { entries: entries }) {
         Ok(tree) => tree,
         Err(tree) => {
             return TransitionResult::Failure {
@@ -2504,264 +2568,259 @@ pub fn parse_aplus_pick_any(
         }
     };
 
-    while let Some(current_line) = src_lines.get(line_cursor.relative_offset()) {
-        let indent = current_line
-            .chars()
-            .take_while(|c| c.is_whitespace())
-            .count();
+    TransitionResult::Success {
+        doctree: doctree,
+        push_or_pop: PushOrPop::Neither,
+        line_advance: LineAdvance::None,
+    }
+}
 
-        if indent != body_indent {
-            break;
-        }
+/// Parses the Sphinx `only` directive. The expression is evaluated with the tags defined in the doctree,
+/// and the contents are parsed only if it evaluates to `true`.
+pub fn parse_sphinx_only(
+    src_lines: &[String],
+    mut doctree: DocTree,
+    line_cursor: &mut LineCursor,
+    empty_after_marker: bool,
+    first_indent: usize,
+    body_indent: usize,
+) -> TransitionResult {
 
-        let captures = if let Some(capts) = HINT_RE.captures(current_line) {
-            capts
-        } else {
-            break;
-        };
+    let directive_line = line_cursor.sum_total() + 1;
 
-        let show_not_answered = captures.name("show_not_answered");
-        let label = match captures.name("label") {
-            Some(enumerator) => enumerator.as_str().to_string(),
-            None => {
-                return TransitionResult::Failure {
-                    message: format!(
-                        "No label for pick-any hint on line {}. Computer says no...",
-                        line_cursor.sum_total()
+    let expression = match scan_directive_arguments(src_lines, line_cursor, body_indent, Some(first_indent), empty_after_marker) {
+        Some(lines) => lines.join(" "),
+        None => String::new()
+    };
+
+    // Expressions that cannot be evaluated are reported and their contents are kept.
+    let include_contents = if expression.is_empty() {
+        doctree.add_system_message(
+            SystemMessageLevel::Error,
+            Some(directive_line),
+            String::from(r#"The expression of an "only" directive should not be empty. Keeping contents."#),
+        );
+        true
+    } else {
+        match crate::common::evaluate_only_expression(expression.as_str(), doctree.shared_sphinx_only_tags()) {
+            Ok(value) => value,
+            Err(message) => {
+                doctree.add_system_message(
+                    SystemMessageLevel::Error,
+                    Some(directive_line),
+                    format!(
+                        r#"Could not evaluate the "only" expression "{}": {}. Keeping contents."#,
+                        expression, message
                     ),
-                    doctree: doctree,
-                }
+                );
+                true
             }
-        };
-        let hint: &str = if let Some(hint) = captures.name("hint") {
-            hint.as_str().trim()
-        } else {
-            return TransitionResult::Failure {
-                message: format!(
-                    "No hint text for pick-any hint on line {}. Computer says no...",
-                    line_cursor.sum_total()
-                ),
-                doctree: doctree,
-            };
-        };
-
-        if hint.is_empty() {
-            return TransitionResult::Failure {
-                message: format!(
-                    "Empty hint text for hint on line {}. Computer says no...",
-                    line_cursor.sum_total()
-                ),
-                doctree: doctree,
-            };
         }
+    };
 
-        let hint_nodes: Vec<TreeNodeType> = match Parser::inline_parse(hint.to_string(), None, line_cursor) {
-    InlineParsingResult::Nodes(nodes) => nodes,
-    _ => return TransitionResult::Failure {
-        message: format!("Could not parse pick-any answer on line {} for inline nodes. Computer says no...", line_cursor.sum_total()),
-        doctree: doctree
+    // The contents of a false branch are skipped without parsing them,
+    // so that the targets and other names defined in them are not registered.
+    if !include_contents {
+        let offset = match Parser::read_indented_block(
+            src_lines,
+            line_cursor.relative_offset(),
+            false,
+            true,
+            Some(body_indent),
+            Some(body_indent),
+            false,
+        ) {
+            IndentedBlockResult::Ok { offset, .. } => offset,
+            _ => 0,
+        };
+        return TransitionResult::Success {
+            doctree: doctree,
+            push_or_pop: PushOrPop::Neither,
+            line_advance: LineAdvance::Some(offset),
+        };
     }
+
+    let only_node = TreeNodeType::SphinxOnly {
+        expression: expression,
+        body_indent: body_indent,
     };
 
-        if hint_nodes.is_empty() {
+    doctree = match doctree.push_data_and_focus(only_node) {
+        Ok(tree) => tree,
+        Err(tree) => {
             return TransitionResult::Failure {
                 message: format!(
-                    "No inline nodes found for pick-any hint on line {}. Computer says no...",
+                    "Node insertion error on line {}. Computer says no...",
                     line_cursor.sum_total()
                 ),
-                doctree: doctree,
-            };
-        }
-
-        let hint_node = TreeNodeType::AplusQuestionnaireHint {
-            label: label,
-            show_when_not_selected: show_not_answered.is_some(),
-            question_type: crate::common::AplusQuestionnaireType::PickAny,
-        };
-
-        doctree = match doctree.push_data_and_focus(hint_node) {
-            Ok(tree) => tree,
-            Err(tree) => {
-                return TransitionResult::Failure {
-                    message: format!(
-                        "Node insertion error on line {}. Computer says no...",
-                        line_cursor.sum_total()
-                    ),
-                    doctree: tree,
-                }
+                doctree: tree,
             }
-        };
-        for node in hint_nodes {
-            doctree = match doctree.push_data(node) {
-                Ok(tree) => tree,
-                Err(tree) => {
-                    return TransitionResult::Failure {
-                        message: format!(
-                            "Node insertion error on line {}. Computer says no...",
-                            line_cursor.sum_total()
-                        ),
-                        doctree: tree,
-                    }
-                }
-            };
         }
-        doctree = doctree.focus_on_parent();
-
-        line_cursor.increment_by(1);
-    }
-
-    Parser::skip_empty_lines(src_lines, line_cursor);
-
-    doctree = doctree.focus_on_parent(); // Focus on pick-one
-    doctree = doctree.focus_on_parent(); // Focus on questionnaire
-
-    // Return with modified doctree
+    };
 
     TransitionResult::Success {
         doctree: doctree,
-        push_or_pop: PushOrPop::Neither,
-        line_advance: LineAdvance::None,
+        push_or_pop: PushOrPop::Push(vec![State::Body]),
+        line_advance: LineAdvance::Some(1),
     }
 }
 
-/// A `freetext` type questionnaire question parser.
-pub fn parse_aplus_freetext(
+/// A parser for the Sphinx `tabularcolumns` directive. The LaTeX column specification given as the argument
+/// is stored in the doctree, and given to the next table encountered in the document.
+pub fn parse_sphinx_tabularcolumns(
     src_lines: &[String],
     mut doctree: DocTree,
     line_cursor: &mut LineCursor,
+    empty_after_marker: bool,
     first_indent: usize,
     body_indent: usize,
-    empty_after_marker: bool,
 ) -> TransitionResult {
 
-    use crate::common::QuizPoints;
-
-    let (points, method_string) = if let Some(arg) = scan_directive_arguments(
+    let spec = if let Some(arg) = scan_directive_arguments(
         src_lines,
         line_cursor,
         body_indent,
         Some(first_indent),
         empty_after_marker,
     ) {
-        let arg_string = arg.join(" ");
+        arg.join("").trim().to_string()
+    } else {
+        return TransitionResult::Failure {
+            message: format!(
+                "Sphinx \"tabularcolumns\" directive on line {} is missing a column specification. Computer says no...",
+                line_cursor.sum_total()
+            ),
+            doctree: doctree,
+        };
+    };
 
-        let mut arg_iter = arg_string.split_whitespace();
-        let points_string: Option<&str> = arg_iter.next();
-        let method_str = arg_iter.next();
+    doctree.set_incoming_column_spec(spec.as_str());
 
-        let points: QuizPoints = if let Some(string) = points_string {
-            if let Ok(result) = string.parse() {
-                result
-            } else {
-                return TransitionResult::Failure {
-                    message: format!("Quiz freetext question points preceding line {} could not be parsed into an integer. Computer says no...", line_cursor.sum_total()),
-                    doctree: doctree
-                };
-            }
-        } else {
-            return TransitionResult::Failure {
-                message: format!(
-                    "No points found for freetext on line {}. Computer says no...",
-                    line_cursor.sum_total()
-                ),
-                doctree: doctree,
-            };
-        };
-        let method_string = if let Some(string) = method_str {
-            string.to_string()
-        } else {
-            String::new()
-        };
+    TransitionResult::Success {
+        doctree: doctree,
+        push_or_pop: PushOrPop::Neither,
+        line_advance: LineAdvance::None,
+    }
+}
 
-        (points, method_string)
-    } else {
-        return TransitionResult::Failure {
-            message: format!(
-                "No points provided for freetext question on line {}. Computer says no...",
-                line_cursor.sum_total()
-            ),
-            doctree: doctree,
-        };
-    };
+pub fn parse_sphinx_math_block() {
+    todo!()
+}
 
-    if let TreeNodeType::AplusQuestionnaire {
-        points_from_children,
-        ..
-    } = doctree.mut_node_data()
-    {
-        *points_from_children += points;
-    }
+/// A parser for the Sphinx `productionlist` directive. Each line of the directive holds a production `name: definition`,
+/// and lines with an empty name continue the definition of the previous production.
+/// If the first line does not contain a colon, it is taken to be the name of the production group.
+/// Production names written inside backticks in definitions are turned into references to the productions.
+pub fn parse_sphinx_productionlist(
+    src_lines: &[String],
+    mut doctree: DocTree,
+    line_cursor: &mut LineCursor,
+    empty_after_marker: bool,
+    first_indent: usize,
+    body_indent: usize,
+) -> TransitionResult {
 
-    let (class, required, key, length, height) = if let Some(mut options) = scan_directive_options
-    (src_lines, line_cursor, body_indent) {
-        (
-            options.remove("class"),
-            options.remove("required"),
-            options.remove("key"),
-            options.remove("length"),
-            options.remove("height"),
-        )
-    } else {
-        (None, None, None, None, None)
-    };
+    use crate::common::{production_label, SphinxProductionSegment};
+    use crate::utf8_to_latex::unicode_text_to_latex;
 
-    let freetext_node = TreeNodeType::AplusFreeText {
-        body_indent: body_indent,
-        points: points,
-        compare_method: method_string,
-        model_answer: String::new(),
-        class: class,
-        required: required,
-        key: key,
-        length: length,
-        height: height,
+    // The production lines, possibly starting from the marker line
+    let mut lines = Vec::<String>::new();
+    if ! empty_after_marker {
+        if let Some(line) = src_lines.get(line_cursor.relative_offset()) {
+            lines.push(line.chars().skip(first_indent).collect::<String>().trim().to_string());
+        }
+    }
+    line_cursor.increment_by(1);
+    while let Some(line) = src_lines.get(line_cursor.relative_offset()) {
+        let indent = line.chars().take_while(|c| c.is_whitespace()).count();
+        if line.trim().is_empty() || indent < body_indent {
+            break
+        }
+        lines.push(line.trim().to_string());
+        line_cursor.increment_by(1);
+    }
+
+    let group = match lines.first() {
+        Some(line) if ! line.contains(":") => Some(lines.remove(0)),
+        _ => None
     };
 
-    doctree = match doctree.push_data_and_focus(freetext_node) {
-        Ok(tree) => tree,
-        Err(tree) => {
-            return TransitionResult::Failure {
-                message: format!(
-                    "Node insertion error on line {}. Computer says no...",
-                    line_cursor.sum_total()
-                ),
-                doctree: tree,
+    // Splits a production definition into text and references to other productions
+    let definition_segments = |definition: &str| -> Vec<SphinxProductionSegment> {
+        let mut segments = Vec::new();
+        for (index, part) in definition.split("`").enumerate() {
+            if part.is_empty() {
+                continue
+            }
+            if index % 2 == 0 {
+                segments.push(SphinxProductionSegment::Text(unicode_text_to_latex(part)));
+            } else {
+                // A token may be prefixed with a production group, which is hidden with a "~"
+                let (hide_group, token) = match part.strip_prefix("~") {
+                    Some(token) => (true, token),
+                    None => (false, part)
+                };
+                let (token_group, name) = match token.rfind(":") {
+                    Some(colon) => (Some(&token[..colon]), &token[colon + 1..]),
+                    None => (group.as_deref(), token)
+                };
+                let text = if hide_group { name } else { token };
+                segments.push(SphinxProductionSegment::Token {
+                    text: unicode_text_to_latex(text),
+                    target_label: production_label(token_group, name),
+                });
             }
         }
+        segments
     };
 
-    Parser::skip_empty_lines(src_lines, line_cursor);
-
-    // Read in assignment
-
-    let assignment_inline_nodes: Vec<TreeNodeType> = {
-        let (block_lines, offset) = match Parser::read_text_block(src_lines, line_cursor.relative_offset(),  true, true, Some(body_indent), true) {
-            TextBlockResult::Ok { lines, offset } => (lines, offset),
-            TextBlockResult::Err { lines, offset } => return TransitionResult::Failure {
-                message: format!("Could not read pick-any assignment lines starting on line {}. Computer says no...", line_cursor.sum_total()),
-                doctree: doctree
+    let mut productions = Vec::<TreeNodeType>::new();
+    for line in lines.iter() {
+        let (name, definition) = match line.find(":") {
+            Some(colon) => (line[..colon].trim(), line[colon + 1..].trim()),
+            None => {
+                return TransitionResult::Failure {
+                    message: format!(
+                        "Sphinx production list before line {} contains a line \"{}\" without a colon. Computer says no...",
+                        line_cursor.sum_total(),
+                        line
+                    ),
+                    doctree: doctree,
+                }
             }
         };
-
-        let inline_nodes = match Parser::inline_parse(block_lines.join("\n"), None, line_cursor) {
-            InlineParsingResult::Nodes(nodes) => nodes,
-            _ => return TransitionResult::Failure {
-                message: format!("Could not parse pick-any assignment for inline nodes on line {}. Computer says no...", line_cursor.sum_total()),
-                doctree: doctree
+        if name.is_empty() {
+            if let Some(TreeNodeType::SphinxProduction { definition: lines, .. }) = productions.last_mut() {
+                lines.push(definition_segments(definition));
+            } else {
+                return TransitionResult::Failure {
+                    message: format!(
+                        "Sphinx production list before line {} starts with a continuation line. Computer says no...",
+                        line_cursor.sum_total()
+                    ),
+                    doctree: doctree,
+                }
             }
-        };
-
-        line_cursor.increment_by(1);
-
-        inline_nodes
-    };
+        } else {
+            productions.push(TreeNodeType::SphinxProduction {
+                name: unicode_text_to_latex(name),
+                target_label: production_label(group.as_deref(), name),
+                definition: vec![definition_segments(definition)],
+            });
+        }
+    }
 
-    // Add assignment node (paragraph) to tree
+    if productions.is_empty() {
+        return TransitionResult::Failure {
+            message: format!(
+                "Sphinx production list before line {} has no productions. Computer says no...",
+                line_cursor.sum_total()
+            ),
+            doctree: doctree,
+        }
+    }
 
-    let assignment_node = TreeNodeType::Paragraph {
-        indent: body_indent,
-    };
-    doctree = match doctree.push_data_and_focus(assignment_node) {
+    doctree = match doctree.push_data_and_focus(TreeNodeType::SphinxProductionList { group: group }) {
         Ok(tree) => tree,
         Err(tree) => {
             return TransitionResult::Failure {
@@ -2773,8 +2832,8 @@ pub fn parse_aplus_freetext(
             }
         }
     };
-    for node in assignment_inline_nodes {
-        doctree = match doctree.push_data(node) {
+    for production in productions {
+        doctree = match doctree.push_data(production) {
             Ok(tree) => tree,
             Err(tree) => {
                 return TransitionResult::Failure {
@@ -2789,138 +2848,312 @@ pub fn parse_aplus_freetext(
     }
     doctree = doctree.focus_on_parent();
 
-    Parser::skip_empty_lines(src_lines, line_cursor);
+    TransitionResult::Success {
+        doctree: doctree,
+        push_or_pop: PushOrPop::Neither,
+        line_advance: LineAdvance::None,
+    }
+}
 
-    // Read in model answer
+// ========================
+//  A+-specific directives
+// ========================
 
-    if let Some(answer) = src_lines.get(line_cursor.relative_offset()) {
-        let indent = answer.chars().take_while(|c| c.is_whitespace()).count();
-        if indent != body_indent {
-            return TransitionResult::Failure {
-                message: format!("A+ freetext answer has incorrect indentation on line {}. Computer says no...", line_cursor.sum_total()),
-                doctree: doctree
-            };
-        }
+pub fn parse_aplus_questionnaire(
+    src_lines: &[String],
+    mut doctree: DocTree,
+    line_cursor: &mut LineCursor,
+    base_indent: usize,
+    empty_after_marker: bool,
+    first_indent: usize,
+    body_indent: usize,
+) -> TransitionResult {
+    let directive_line = line_cursor.sum_total() + 1;
 
-        if let TreeNodeType::AplusFreeText { model_answer, .. } = doctree.mut_node_data() {
-            model_answer.push_str(answer.trim());
-        } else {
-            return TransitionResult::Failure {
-                message: format!("Not focused on A+ freetext node when reading its model answer on line {}? Computer says no...", line_cursor.sum_total()),
-                doctree: doctree
-            };
-        }
+    let arguments = scan_directive_arguments(
+        src_lines,
+        line_cursor,
+        body_indent,
+        Some(first_indent),
+        empty_after_marker,
+    );
 
-        line_cursor.increment_by(1);
+    let (
+        submissions,
+        points_to_pass,
+        feedback,
+        title,
+        no_override,
+        pick_randomly,
+        preserve_questions_between_attempts,
+        category,
+        status,
+        reveal_model_at_max_submissions,
+        show_model,
+        allow_assistant_viewing,
+        allow_assistant_grading,
+    ) = if let Some(mut options) = scan_directive_options(src_lines, line_cursor, body_indent) {
+        (
+            options.remove("submissions"),
+            options.remove("points-to-pass"),
+            aplus_feedback_kind(&mut options),
+            options.remove("title"),
+            options.remove("no_override"),
+            options.remove("pick_randomly"),
+            options.remove("preserve-questions-between-attempts"),
+            options.remove("category"),
+            options.remove("status"),
+            options.remove("reveal-model-at-max-submissions"),
+            options.remove("show-model"),
+            options.remove("allow-assistant-viewing"),
+            options.remove("allow-assistant-grading"),
+        )
     } else {
-        return TransitionResult::Failure {
-            message: format!("Tried scanning freetext question for correct answer but encountered end of input on line {}. Computer says no...", line_cursor.sum_total()),
-            doctree: doctree
-        };
+        (
+            None, None, None, None, None, None, None, None, None, None, None, None, None,
+        )
     };
 
-    // Read possible hints
-    const APLUS_PICK_HINT_PATTERN: &'static str =
-        r"^(\s*)(?P<show_not_answered>!)?(?P<label>.+)[ ]*§[ ]*(?P<hint>.+)";
-    lazy_static::lazy_static! {
-        static ref HINT_RE: regex::Regex = regex::Regex::new(APLUS_PICK_HINT_PATTERN).unwrap();
-    }
+    // Feedback questionnaires do not need arguments, as their key is always the same
+    let (key, difficulty, max_points): (String, String, String) = match (arguments, feedback) {
+        (Some(lines), _) => aplus_key_difficulty_and_max_points(lines.join(" ").as_str(), &mut doctree, directive_line),
+        (None, Some(_)) => (String::new(), String::new(), String::new()),
+        (None, None) => return TransitionResult::Failure {
+            message: format!(
+                "A+ questionnaire on line {} was not given arguments. Computer says no...",
+                directive_line
+            ),
+            doctree: doctree,
+        }
+    };
 
-    Parser::skip_empty_lines(src_lines, line_cursor);
+    use crate::common::QuizPoints;
 
-    doctree = match doctree.push_data_and_focus(
-        TreeNodeType::AplusQuestionnaireHints {
-            body_indent: body_indent,
-        }
-    ) {
-        Ok(tree) => tree,
-        Err(tree) => {
-            return TransitionResult::Failure {
-                message: format!(
-                    "Node insertion error on line {}. Computer says no...",
-                    line_cursor.sum_total()
+    let (key, max_points, category) = if feedback.is_some() {
+        if let Some(previous_line) = doctree.register_aplus_exercise_key(AplusFeedbackKind::KEY, directive_line) {
+            doctree.add_system_message(
+                SystemMessageLevel::Error,
+                Some(directive_line),
+                format!(
+                    "Only one A+ feedback questionnaire is allowed per file. The previous one is on line {}.",
+                    previous_line
                 ),
-                doctree: tree,
-            }
-        }
-    };
-
-    while let Some(current_line) = src_lines.get(line_cursor.relative_offset()) {
-        let indent = current_line
-            .chars()
-            .take_while(|c| c.is_whitespace())
-            .count();
-
-        if indent != body_indent {
-            break;
+            );
         }
+        (
+            AplusFeedbackKind::KEY.to_string(),
+            // Feedback is worth zero points, unless stated otherwise
+            Some(max_points.parse::<QuizPoints>().unwrap_or(0)),
+            category.or(Some(AplusFeedbackKind::CATEGORY.to_string())),
+        )
+    } else {
+        register_aplus_exercise_key(&mut doctree, &key, directive_line);
+        (key, max_points.parse::<QuizPoints>().ok(), category)
+    };
 
-        let captures = if let Some(capts) = HINT_RE.captures(current_line) {
-            capts
+    let questionnaire_node = TreeNodeType::AplusQuestionnaire {
+        body_indent: body_indent,
+        key: key,
+        difficulty: if difficulty.is_empty() {
+            None
         } else {
-            break;
-        };
+            Some(difficulty)
+        },
+        max_points: max_points,
+        points_from_children: 0,
+        submissions: submissions,
+        points_to_pass: points_to_pass,
+        feedback: feedback,
+        title: title,
+        no_override: no_override,
+        pick_randomly: pick_randomly,
+        preserve_questions_between_attempts: preserve_questions_between_attempts,
+        category: category,
+        status: status,
+        reveal_model_at_max_submissions: reveal_model_at_max_submissions,
+        show_model: show_model,
+        allow_assistant_viewing: allow_assistant_viewing,
+        allow_assistant_grading: allow_assistant_grading,
+        instructions: Vec::new(),
+    };
 
-        let show_not_answered = captures.name("show_not_answered");
-        let label = match captures.name("label") {
-            Some(label) => label.as_str().trim().to_string(),
-            None => {
-                return TransitionResult::Failure {
-                    message: format!(
-                        "No text for freetext hint on line {}. Computer says no...",
-                        line_cursor.sum_total()
-                    ),
-                    doctree: doctree,
-                }
-            }
-        };
-        let hint: &str = if let Some(hint) = captures.name("hint") {
-            hint.as_str().trim()
-        } else {
+    doctree = match doctree.push_data_and_focus(questionnaire_node) {
+        Ok(tree) => tree,
+        Err(tree) => {
             return TransitionResult::Failure {
                 message: format!(
-                    "No hint text for freetext hint on line {}. Computer says no...",
+                    "Node insertion error on line {}. Computer says no...",
                     line_cursor.sum_total()
                 ),
-                doctree: doctree,
-            };
-        };
+                doctree: tree,
+            }
+        }
+    };
 
-        if hint.is_empty() {
+    TransitionResult::Success {
+        doctree: doctree,
+        push_or_pop: PushOrPop::Push(vec![State::AplusQuestionnaire]),
+        line_advance: LineAdvance::None,
+    }
+}
+
+/// A `pick-one` type questionnaire question parser.
+pub fn parse_aplus_pick_one(
+    src_lines: &[String],
+    mut doctree: DocTree,
+    line_cursor: &mut LineCursor,
+    first_indent: usize,
+    body_indent: usize,
+    empty_after_marker: bool,
+) -> TransitionResult {
+
+
+    /// Correct answers in `pick-one` and `pick-any` directives are marked with `*`.
+    /// A `pick-any` question may have neutral options, which are marked with `?`.
+    /// Neutral options are always counted as correct, whether the student selected them or not.
+    /// Initially selected options may be set with `+`.
+    /// The initially selected options are pre-selected when the exercise is loaded.
+    /// The `+` character is written before `*` or `?` if they are combined.
+    const APLUS_PICK_ONE_CHOICE_PATTERN: &'static str =
+        r"^(\s*)(?P<pre_selected>\+)?(?P<correct>\*)?(?P<label>\S+)\.[ ]+(?P<answer>.+)";
+    const APLUS_PICK_HINT_PATTERN: &'static str =
+        r"^(\s*)(?P<show_not_answered>!)?(?P<label>\S+)[ ]*§[ ]*(?P<hint>.+)";
+
+    use regex::{Captures, Regex};
+
+    lazy_static::lazy_static! {
+        static ref CHOICE_RE: Regex = Regex::new(APLUS_PICK_ONE_CHOICE_PATTERN).unwrap();
+        static ref HINT_RE: Regex = Regex::new(APLUS_PICK_HINT_PATTERN).unwrap();
+    }
+
+    // Parsing the directive arguments
+
+    use crate::common::QuizPoints;
+
+    let points: QuizPoints = match scan_directive_arguments(
+        src_lines,
+        line_cursor,
+        body_indent,
+        Some(first_indent),
+        empty_after_marker,
+    ) {
+        Some(lines) => match lines.join(" ").as_str().parse() {
+            Ok(points) => points,
+            Err(e) => return TransitionResult::Failure {
+                message: format!("Quiz question points preceding line {} could not be parsed into an integer. Computer says no...", line_cursor.sum_total()),
+                doctree: doctree
+            }
+        }
+        // Questions of feedback questionnaires are worth zero points by default
+        None if in_aplus_feedback_questionnaire(&doctree) => 0,
+        None => return TransitionResult::Failure {
+            message: format!(
+                "No points provided for pick-one question on line {}. Computer says no...",
+                line_cursor.sum_total()
+            ),
+            doctree: doctree,
+        }
+    };
+
+    if let TreeNodeType::AplusQuestionnaire {
+        points_from_children,
+        ..
+    } = doctree.mut_node_data() {
+        *points_from_children += points;
+    }
+
+    // Parsing directive options
+
+    let (class, required, key, dropdown) = if let Some(mut options) =
+        scan_directive_options
+        (src_lines, line_cursor, body_indent)
+    {
+        (
+            options.remove("class"),
+            options.remove("required"),
+            options.remove("key"),
+            options.remove("dropdown"),
+        )
+    } else {
+        (None, None, None, None)
+    };
+
+    Parser::skip_empty_lines(src_lines, line_cursor);
+
+    // Generating and focusing on node
+
+    let pick_one_node = TreeNodeType::AplusPickOne {
+        body_indent: body_indent,
+        class: class,
+        points: points,
+        required: if required.is_some() { true } else { false },
+        key: key,
+        dropdown: if dropdown.is_some() { true } else { false },
+        assignment: String::new(),
+    };
+
+    doctree = match doctree.push_data_and_focus(pick_one_node) {
+        Ok(tree) => tree,
+        Err(tree) => {
             return TransitionResult::Failure {
                 message: format!(
-                    "Empty hint text for hint on line {}. Computer says no...",
+                    "Node insertion error on line {}. Computer says no...",
                     line_cursor.sum_total()
                 ),
-                doctree: doctree,
-            };
+                doctree: tree,
+            }
+        }
+    };
+
+    // Check for assignment
+
+    Parser::skip_empty_lines(src_lines, line_cursor);
+
+    let start_line = match src_lines.get(line_cursor.relative_offset()) {
+        Some(line) => line,
+        None => return TransitionResult::Failure {
+            message: format!(
+                "Input overflow on line {} when parsing pick-one assignment. Computer says no...",
+                line_cursor.sum_total()
+            ),
+            doctree: doctree
         }
+    };
+
+    let assignment_inline_nodes: Vec<TreeNodeType> = if !CHOICE_RE.is_match(start_line) {
+        let (block_lines, offset) = match Parser::read_text_block(src_lines, line_cursor.relative_offset(),  true, true, Some(body_indent),true) {
+            TextBlockResult::Ok {lines, offset } => (lines, offset),
+            TextBlockResult::Err { lines, offset } => return TransitionResult::Failure {
+                message: format!("Could not read pick-one assignment lines starting on line {}. Computer says no...", line_cursor.sum_total()),
+                doctree: doctree
+            }
+        };
+        set_aplus_question_assignment(&mut doctree, block_lines.join("\n"));
 
-        let hint_nodes: Vec<TreeNodeType> = match Parser::inline_parse(hint.to_string(), None, line_cursor) {
+        let inline_nodes = match Parser::inline_parse(block_lines.join("\n"), Some(&mut doctree), line_cursor) {
             InlineParsingResult::Nodes(nodes) => nodes,
             _ => return TransitionResult::Failure {
-                message: format!("Could not parse freetext hint on line {} for inline nodes. Computer says no...", line_cursor.sum_total()),
+                message: format!("Could not parse pick-one assignment for inline nodes on line {}. Computer says no...", line_cursor.sum_total()),
                 doctree: doctree
             }
         };
 
-        if hint_nodes.is_empty() {
-            return TransitionResult::Failure {
-                message: format!(
-                    "No inline nodes found for freetext hint on line {}. Computer says no...",
-                    line_cursor.sum_total()
-                ),
-                doctree: doctree,
-            };
-        }
+        line_cursor.increment_by(1);
 
-        let hint_node = TreeNodeType::AplusQuestionnaireHint {
-            label: label,
-            show_when_not_selected: show_not_answered.is_some(),
-            question_type: crate::common::AplusQuestionnaireType::FreeText,
-        };
+        inline_nodes
+    } else {
+        Vec::new()
+    };
 
-        doctree = match doctree.push_data_and_focus(hint_node) {
+    // Add assignment node (paragraph) to tree
+
+    Parser::skip_empty_lines(src_lines, line_cursor);
+
+    if !assignment_inline_nodes.is_empty() {
+        let assignment_node = TreeNodeType::Paragraph {
+            indent: body_indent,
+        };
+        doctree = match doctree.push_data_and_focus(assignment_node) {
             Ok(tree) => tree,
             Err(tree) => {
                 return TransitionResult::Failure {
@@ -2932,7 +3165,7 @@ pub fn parse_aplus_freetext(
                 }
             }
         };
-        for node in hint_nodes {
+        for node in assignment_inline_nodes {
             doctree = match doctree.push_data(node) {
                 Ok(tree) => tree,
                 Err(tree) => {
@@ -2946,235 +3179,2298 @@ pub fn parse_aplus_freetext(
                 }
             };
         }
-        doctree = doctree.focus_on_parent();
-
-        line_cursor.increment_by(1);
+        doctree = doctree.focus_on_parent()
     }
 
     Parser::skip_empty_lines(src_lines, line_cursor);
 
-    doctree = doctree.focus_on_parent(); // Focus on pick-one
-    doctree = doctree.focus_on_parent(); // Focus on questionnaire
-
-    TransitionResult::Success {
-        doctree: doctree,
-        push_or_pop: PushOrPop::Neither,
-        line_advance: LineAdvance::None,
-    }
-}
+    // Read question choices
 
-pub fn parse_aplus_submit(
-    src_lines: &[String],
-    mut doctree: DocTree,
-    line_cursor: &mut LineCursor,
-    first_indent: usize,
-    body_indent: usize,
-    empty_after_marker: bool,
-) -> TransitionResult {
+    doctree = match doctree.push_data_and_focus(TreeNodeType::AplusPickChoices {
+        body_indent: body_indent,
+    }) {
+        Ok(tree) => tree,
+        Err(tree) => {
+            return TransitionResult::Failure {
+                message: format!(
+                    "Node insertion error on line {}. Computer says no...",
+                    line_cursor.sum_total()
+                ),
+                doctree: tree,
+            }
+        }
+    };
 
-    let (key, difficulty, max_points): (String, String, String) = if let Some(arg) =
-        scan_directive_arguments(
-            src_lines,
-            line_cursor,
-            body_indent,
-            Some(first_indent),
-            empty_after_marker,
-        ) {
-        aplus_key_difficulty_and_max_points(arg.join(" ").as_str(), line_cursor)
-    } else {
+    while let Some(current_line) = src_lines.get(line_cursor.relative_offset()) {
+        let indent = current_line
+            .chars()
+            .take_while(|c| c.is_whitespace())
+            .count();
+
+        if indent != body_indent {
+            break;
+        }
+
+        let captures: Captures = if let Some(capts) = CHOICE_RE.captures(current_line) {
+            capts
+        } else {
+            break;
+        };
+
+        let label = captures.name("label").unwrap().as_str().to_string();
+        let pre_selected = captures.name("pre_selected");
+        let correct = captures.name("correct");
+        let answer = if let Some(capture) = captures.name("answer") {
+            capture.as_str()
+        } else {
+            ""
+        };
+
+        if answer.trim().is_empty() {
+            return TransitionResult::Failure {
+                message: format!("Discovered a pick-one answer without content on line {}. Computer says no...", line_cursor.sum_total()),
+                doctree: doctree
+            };
+        }
+
+        let answer_nodes: Vec<TreeNodeType> = match Parser::inline_parse(answer.to_string(), Some(&mut doctree), line_cursor) {
+            InlineParsingResult::Nodes(nodes) => nodes,
+            _ => return TransitionResult::Failure {
+                message: format!("Could not parse pick-one answer on line {} for inline nodes. Computer says no...", line_cursor.sum_total()),
+                doctree: doctree
+            }
+        };
+
+        let choice_node = TreeNodeType::AplusPickChoice {
+            label: label,
+            is_pre_selected: pre_selected.is_some(),
+            is_correct: correct.is_some(),
+            is_neutral: false, // pick-one nodes don't have this set
+            answer: answer.trim().to_string(),
+        };
+
+        doctree = match doctree.push_data_and_focus(choice_node) {
+            Ok(tree) => tree,
+            Err(tree) => {
+                return TransitionResult::Failure {
+                    message: format!(
+                        "Node insertion error on line {}. Computer says no...",
+                        line_cursor.sum_total()
+                    ),
+                    doctree: tree,
+                }
+            }
+        };
+        for node in answer_nodes {
+            doctree = match doctree.push_data(node) {
+                Ok(tree) => tree,
+                Err(tree) => {
+                    return TransitionResult::Failure {
+                        message: format!(
+                            "Node insertion error on line {}. Computer says no...",
+                            line_cursor.sum_total()
+                        ),
+                        doctree: tree,
+                    }
+                }
+            };
+        }
+        doctree = doctree.focus_on_parent();
+
+        line_cursor.increment_by(1);
+    }
+
+    if doctree.n_of_children() == 0 {
         return TransitionResult::Failure {
             message: format!(
-                "A+ submit exercise on line {} was not given arguments. Computer says no...",
+                "Found no choices for pick-one question on line {}. Computer says no...",
                 line_cursor.sum_total()
             ),
             doctree: doctree,
         };
+    }
+
+    doctree = doctree.focus_on_parent();
+
+    // Read possible hints inside the answers environment
+
+    Parser::skip_empty_lines(src_lines, line_cursor);
+
+    doctree = match doctree.push_data_and_focus(TreeNodeType::AplusQuestionnaireHints {
+        body_indent: body_indent,
+    }) {
+        Ok(tree) => tree,
+        Err(tree) => {
+            return TransitionResult::Failure {
+                message: format!(
+                    "Node insertion error on line {}. Computer says no...",
+                    line_cursor.sum_total()
+                ),
+                doctree: tree,
+            }
+        }
+    };
+
+    while let Some(current_line) = src_lines.get(line_cursor.relative_offset()) {
+        let indent = if !current_line.is_empty() {
+            current_line
+                .chars()
+                .take_while(|c| c.is_whitespace())
+                .count()
+        } else {
+            body_indent
+        };
+
+        if indent != body_indent {
+            break;
+        }
+
+        let captures = if let Some(capts) = HINT_RE.captures(current_line) {
+            capts
+        } else {
+            break;
+        };
+
+        let show_not_answered = captures.name("show_not_answered");
+        let label = match captures.name("label") {
+            Some(label) => label.as_str().to_string(),
+            None => {
+                return TransitionResult::Failure {
+                    message: format!(
+                        "No enumerator for pick-one hint on line {}. Computer says no...",
+                        line_cursor.sum_total()
+                    ),
+                    doctree: doctree,
+                }
+            }
+        };
+        let hint: &str = if let Some(hint) = captures.name("hint") {
+            hint.as_str().trim()
+        } else {
+            return TransitionResult::Failure {
+                message: format!(
+                    "No hint text for pick-one hint on line {}. Computer says no...",
+                    line_cursor.sum_total()
+                ),
+                doctree: doctree,
+            };
+        };
+
+        if hint.is_empty() {
+            return TransitionResult::Failure {
+                message: format!(
+                    "Empty  hint text for hint on line {}. Computer says no...",
+                    line_cursor.sum_total()
+                ),
+                doctree: doctree,
+            };
+        }
+
+        let hint_nodes: Vec<TreeNodeType> = match Parser::inline_parse(hint.to_string(), Some(&mut doctree), line_cursor) {
+            InlineParsingResult::Nodes(nodes) => nodes,
+            _ => return TransitionResult::Failure {
+                message: format!("Could not parse pick-one answer on line {} for inline nodes. Computer says no...", line_cursor.sum_total()),
+                doctree: doctree
+            }
+        };
+
+        if hint_nodes.is_empty() {
+            return TransitionResult::Failure {
+                message: format!(
+                    "No inline nodes found for pick-one hint on line {}. Computer says no...",
+                    line_cursor.sum_total()
+                ),
+                doctree: doctree,
+            };
+        }
+
+        let hint_node = TreeNodeType::AplusQuestionnaireHint {
+            label: label,
+            show_when_not_selected: show_not_answered.is_some(),
+            question_type: crate::common::AplusQuestionnaireType::PickOne,
+            hint: hint.to_string(),
+        };
+
+        doctree = match doctree.push_data_and_focus(hint_node) {
+            Ok(tree) => tree,
+            Err(tree) => {
+                return TransitionResult::Failure {
+                    message: format!(
+                        "Node insertion error on line {}. Computer says no...",
+                        line_cursor.sum_total()
+                    ),
+                    doctree: tree,
+                }
+            }
+        };
+        for node in hint_nodes {
+            doctree = match doctree.push_data(node) {
+                Ok(tree) => tree,
+                Err(tree) => {
+                    return TransitionResult::Failure {
+                        message: format!(
+                            "Node insertion error on line {}. Computer says no...",
+                            line_cursor.sum_total()
+                        ),
+                        doctree: tree,
+                    }
+                }
+            };
+        }
+        doctree = doctree.focus_on_parent();
+
+        line_cursor.increment_by(1);
+    }
+
+    Parser::skip_empty_lines(src_lines, line_cursor);
+
+    doctree = doctree.focus_on_parent(); // Focus on pick-one
+    doctree = doctree.focus_on_parent(); // Focus on questionnaire
+
+    // Return with modified doctree
+
+    TransitionResult::Success {
+        doctree: doctree,
+        push_or_pop: PushOrPop::Neither,
+        line_advance: LineAdvance::None,
+    }
+}
+
+/// A `pick-any` type questionnaire question parser.
+pub fn parse_aplus_pick_any(
+    src_lines: &[String],
+    mut doctree: DocTree,
+    line_cursor: &mut LineCursor,
+    first_indent: usize,
+    body_indent: usize,
+    empty_after_marker: bool,
+) -> TransitionResult {
+
+    const APLUS_PICK_ANY_CHOICE_PATTERN: &'static str = r"^(\s*)(?P<pre_selected>\+)?(?:(?P<neutral>\?)|(?P<correct>\*))?(?P<label>\S+)\.[ ]+(?P<answer>.+)";
+    const APLUS_PICK_HINT_PATTERN: &'static str =
+        r"^(\s*)(?P<show_not_answered>!)?(?P<label>\S+)[ ]*§[ ]*(?P<hint>.+)";
+
+    lazy_static::lazy_static! {
+        static ref CHOICE_RE: regex::Regex = regex::Regex::new(APLUS_PICK_ANY_CHOICE_PATTERN).unwrap();
+        static ref HINT_RE: regex::Regex = regex::Regex::new(APLUS_PICK_HINT_PATTERN).unwrap();
+    }
+
+    let points: crate::common::QuizPoints = match scan_directive_arguments(
+        src_lines,
+        line_cursor,
+        body_indent,
+        Some(first_indent),
+        empty_after_marker,
+    ) {
+        Some(lines) => match lines.join(" ").as_str().parse() {
+            Ok(points) => points,
+            Err(e) => return TransitionResult::Failure {
+                message: format!(
+                    "Quiz question points preceding line {} could not be parsed into an integer. Computer says no...",
+                    line_cursor.sum_total()
+                ),
+                doctree: doctree
+            }
+        },
+        // Questions of feedback questionnaires are worth zero points by default
+        None if in_aplus_feedback_questionnaire(&doctree) => 0,
+        None => return TransitionResult::Failure {
+            message: format!(
+                "No points provided for pick-any question on line {}. Computer says no...",
+                line_cursor.sum_total()
+            ),
+            doctree: doctree,
+        }
+    };
+
+    if let TreeNodeType::AplusQuestionnaire {
+        points_from_children,
+        ..
+    } = doctree.mut_node_data() {
+        *points_from_children += points;
+    }
+
+    let (
+        class,
+        required,
+        key,
+        partial_points,
+        randomized,
+        correct_count,
+        preserve_questions_between_attempts,
+    ) = if let Some(mut options) = scan_directive_options
+    (src_lines, line_cursor, body_indent) {
+        (
+            options.remove("class"),
+            options.remove("required"),
+            options.remove("key"),
+            options.remove("partial-points"),
+            options.remove("randomized"),
+            options.remove("correct-count"),
+            options.remove("preserve-questions-between-attempts"),
+        )
+    } else {
+        (None, None, None, None, None, None, None)
+    };
+
+    let pick_any_node = TreeNodeType::AplusPickAny {
+        body_indent: body_indent,
+        points: points,
+        class: class,
+        required: if required.is_some() { true } else { false },
+        key: key,
+        partial_points: if partial_points.is_some() {
+            true
+        } else {
+            false
+        },
+        randomized: if randomized.is_some() && correct_count.is_some() {
+            true
+        } else {
+            false
+        },
+        correct_count: if randomized.is_some() && correct_count.is_some() {
+            if let Ok(result) = correct_count.unwrap().parse() {
+                Some(result)
+            } else {
+                return TransitionResult::Failure {
+                    message: format!("No correct count provided for pick-any on line {} with randomization activated. Computer says no...", line_cursor.sum_total()),
+                    doctree: doctree
+                };
+            }
+        } else {
+            None
+        },
+        preserve_questions_between_attempts: if preserve_questions_between_attempts.is_some() {
+            true
+        } else {
+            false
+        },
+        assignment: String::new(),
+    };
+
+    doctree = match doctree.push_data_and_focus(pick_any_node) {
+        Ok(tree) => tree,
+        Err(tree) => {
+            return TransitionResult::Failure {
+                message: format!(
+                    "Node insertion error on line {}. Computer says no...",
+                    line_cursor.sum_total()
+                ),
+                doctree: tree,
+            }
+        }
+    };
+
+    // Check for assignment
+
+    Parser::skip_empty_lines(src_lines, line_cursor);
+
+    let start_line = match src_lines.get(line_cursor.relative_offset()) {
+        Some(line) => line,
+        None => return TransitionResult::Failure {
+            message: format!(
+                "Input overflow on line {} when parsing pick-any assignment. Computer says no...",
+                line_cursor.sum_total()
+            ),
+            doctree: doctree
+        }
+    };
+
+    let assignment_inline_nodes: Vec<TreeNodeType> = if !CHOICE_RE.is_match(start_line) {
+        let (block_lines, offset) = match Parser::read_text_block(src_lines, line_cursor.relative_offset(),  true, true, Some(body_indent), true) {
+            TextBlockResult::Ok {lines, offset } => (lines, offset),
+            TextBlockResult::Err {lines, offset } => return TransitionResult::Failure {
+                message: format!("Could not read pick-any assignment lines starting on line {}. Computer says no...", line_cursor.sum_total()),
+                doctree: doctree
+            }
+        };
+
+        set_aplus_question_assignment(&mut doctree, block_lines.join("\n"));
+
+        let inline_nodes = match Parser::inline_parse(block_lines.join("\n"), Some(&mut doctree), line_cursor) {
+            InlineParsingResult::Nodes(nodes) => nodes,
+            _ => return TransitionResult::Failure {
+                message: format!("Could not parse pick-any assignment for inline nodes on line {}. Computer says no...", line_cursor.sum_total()),
+                doctree: doctree
+            }
+        };
+
+        line_cursor.increment_by(1);
+
+        inline_nodes
+    } else {
+        Vec::new()
+    };
+
+    // Add assignment node (paragraph) to tree
+
+    if !assignment_inline_nodes.is_empty() {
+        let assignment_node = TreeNodeType::Paragraph {
+            indent: body_indent,
+        };
+        doctree = match doctree.push_data_and_focus(assignment_node) {
+            Ok(tree) => tree,
+            Err(tree) => {
+                return TransitionResult::Failure {
+                    message: format!(
+                        "Node insertion error on line {}. Computer says no...",
+                        line_cursor.sum_total()
+                    ),
+                    doctree: tree,
+                }
+            }
+        };
+        for node in assignment_inline_nodes {
+            doctree = match doctree.push_data(node) {
+                Ok(tree) => tree,
+                Err(tree) => {
+                    return TransitionResult::Failure {
+                        message: format!(
+                            "Node insertion error on line {}. Computer says no...",
+                            line_cursor.sum_total()
+                        ),
+                        doctree: tree,
+                    }
+                }
+            };
+        }
+        doctree = doctree.focus_on_parent()
+    }
+
+    Parser::skip_empty_lines(src_lines, line_cursor);
+
+    // Read question choices
+
+    doctree = match doctree.push_data_and_focus(TreeNodeType::AplusPickChoices {
+        body_indent: body_indent,
+    }) {
+        Ok(tree) => tree,
+        Err(tree) => {
+            return TransitionResult::Failure {
+                message: format!(
+                    "Node insertion error on line {}. Computer says no...",
+                    line_cursor.sum_total()
+                ),
+                doctree: tree,
+            }
+        }
+    };
+
+    while let Some(current_line) = src_lines.get(line_cursor.relative_offset()) {
+        let indent = current_line
+            .chars()
+            .take_while(|c| c.is_whitespace())
+            .count();
+
+        if indent != body_indent {
+            break;
+        }
+
+        let captures: regex::Captures = if let Some(capts) = CHOICE_RE.captures(current_line) {
+            capts
+        } else {
+            break;
+        };
+
+        let pre_selected = captures.name("pre_selected");
+        let correct = captures.name("correct");
+        let neutral = captures.name("neutral");
+
+        let label = captures.name("label").unwrap().as_str().to_string();
+        let answer = if let Some(capture) = captures.name("answer") {
+            capture.as_str()
+        } else {
+            ""
+        };
+
+        if answer.trim().is_empty() {
+            return TransitionResult::Failure {
+                message: format!("Discovered a pick-any answer without content on line {}. Computer says no...", line_cursor.sum_total()),
+                doctree: doctree
+            };
+        }
+
+        let answer_nodes: Vec<TreeNodeType> = match Parser::inline_parse(answer.to_string(), Some(&mut doctree), line_cursor) {
+            InlineParsingResult::Nodes(nodes) => nodes,
+            _ => return TransitionResult::Failure {
+                message: format!("Could not parse pick-any answer on line {} for inline nodes. Computer says no...", line_cursor.sum_total()),
+                doctree: doctree
+            }
+        };
+
+        let choice_node = TreeNodeType::AplusPickChoice {
+            label: label,
+            is_pre_selected: pre_selected.is_some(),
+            is_correct: correct.is_some(),
+            is_neutral: neutral.is_some(),
+            answer: answer.trim().to_string(),
+        };
+
+        doctree = match doctree.push_data_and_focus(choice_node) {
+            Ok(tree) => tree,
+            Err(tree) => {
+                return TransitionResult::Failure {
+                    message: format!(
+                        "Node insertion error on line {}. Computer says no...",
+                        line_cursor.sum_total()
+                    ),
+                    doctree: tree,
+                }
+            }
+        };
+        for node in answer_nodes {
+            doctree = match doctree.push_data(node) {
+                Ok(tree) => tree,
+                Err(tree) => {
+                    return TransitionResult::Failure {
+                        message: format!(
+                            "Node insertion error on line {}. Computer says no...",
+                            line_cursor.sum_total()
+                        ),
+                        doctree: tree,
+                    }
+                }
+            };
+        }
+        doctree = doctree.focus_on_parent();
+
+        line_cursor.increment_by(1);
+    }
+
+    if doctree.n_of_children() == 0 {
+        return TransitionResult::Failure {
+            message: format!(
+                "Found no choices for pick-any question on line {}. Computer says no...",
+                line_cursor.sum_total()
+            ),
+            doctree: doctree,
+        };
+    }
+
+    doctree = doctree.focus_on_parent();
+
+    // Read possible hints inside the answers environment
+
+    Parser::skip_empty_lines(src_lines, line_cursor);
+
+    doctree = match doctree.push_data_and_focus(TreeNodeType::AplusQuestionnaireHints {
+        body_indent: body_indent,
+    }) {
+        Ok(tree) => tree,
+        Err(tree) => {
+            return TransitionResult::Failure {
+                message: format!(
+                    "Node insertion error on line {}. Computer says no...",
+                    line_cursor.sum_total()
+                ),
+                doctree: tree,
+            }
+        }
+    };
+
+    while let Some(current_line) = src_lines.get(line_cursor.relative_offset()) {
+        let indent = current_line
+            .chars()
+            .take_while(|c| c.is_whitespace())
+            .count();
+
+        if indent != body_indent {
+            break;
+        }
+
+        let captures = if let Some(capts) = HINT_RE.captures(current_line) {
+            capts
+        } else {
+            break;
+        };
+
+        let show_not_answered = captures.name("show_not_answered");
+        let label = match captures.name("label") {
+            Some(enumerator) => enumerator.as_str().to_string(),
+            None => {
+                return TransitionResult::Failure {
+                    message: format!(
+                        "No label for pick-any hint on line {}. Computer says no...",
+                        line_cursor.sum_total()
+                    ),
+                    doctree: doctree,
+                }
+            }
+        };
+        let hint: &str = if let Some(hint) = captures.name("hint") {
+            hint.as_str().trim()
+        } else {
+            return TransitionResult::Failure {
+                message: format!(
+                    "No hint text for pick-any hint on line {}. Computer says no...",
+                    line_cursor.sum_total()
+                ),
+                doctree: doctree,
+            };
+        };
+
+        if hint.is_empty() {
+            return TransitionResult::Failure {
+                message: format!(
+                    "Empty hint text for hint on line {}. Computer says no...",
+                    line_cursor.sum_total()
+                ),
+                doctree: doctree,
+            };
+        }
+
+        let hint_nodes: Vec<TreeNodeType> = match Parser::inline_parse(hint.to_string(), Some(&mut doctree), line_cursor) {
+    InlineParsingResult::Nodes(nodes) => nodes,
+    _ => return TransitionResult::Failure {
+        message: format!("Could not parse pick-any answer on line {} for inline nodes. Computer says no...", line_cursor.sum_total()),
+        doctree: doctree
+    }
+    };
+
+        if hint_nodes.is_empty() {
+            return TransitionResult::Failure {
+                message: format!(
+                    "No inline nodes found for pick-any hint on line {}. Computer says no...",
+                    line_cursor.sum_total()
+                ),
+                doctree: doctree,
+            };
+        }
+
+        let hint_node = TreeNodeType::AplusQuestionnaireHint {
+            label: label,
+            show_when_not_selected: show_not_answered.is_some(),
+            question_type: crate::common::AplusQuestionnaireType::PickAny,
+            hint: hint.to_string(),
+        };
+
+        doctree = match doctree.push_data_and_focus(hint_node) {
+            Ok(tree) => tree,
+            Err(tree) => {
+                return TransitionResult::Failure {
+                    message: format!(
+                        "Node insertion error on line {}. Computer says no...",
+                        line_cursor.sum_total()
+                    ),
+                    doctree: tree,
+                }
+            }
+        };
+        for node in hint_nodes {
+            doctree = match doctree.push_data(node) {
+                Ok(tree) => tree,
+                Err(tree) => {
+                    return TransitionResult::Failure {
+                        message: format!(
+                            "Node insertion error on line {}. Computer says no...",
+                            line_cursor.sum_total()
+                        ),
+                        doctree: tree,
+                    }
+                }
+            };
+        }
+        doctree = doctree.focus_on_parent();
+
+        line_cursor.increment_by(1);
+    }
+
+    Parser::skip_empty_lines(src_lines, line_cursor);
+
+    doctree = doctree.focus_on_parent(); // Focus on pick-one
+    doctree = doctree.focus_on_parent(); // Focus on questionnaire
+
+    // Return with modified doctree
+
+    TransitionResult::Success {
+        doctree: doctree,
+        push_or_pop: PushOrPop::Neither,
+        line_advance: LineAdvance::None,
+    }
+}
+
+/// A `freetext` type questionnaire question parser.
+pub fn parse_aplus_freetext(
+    src_lines: &[String],
+    mut doctree: DocTree,
+    line_cursor: &mut LineCursor,
+    first_indent: usize,
+    body_indent: usize,
+    empty_after_marker: bool,
+) -> TransitionResult {
+
+    let in_feedback = in_aplus_feedback_questionnaire(&doctree);
+
+    use crate::common::QuizPoints;
+
+    let (points, method_string) = if let Some(arg) = scan_directive_arguments(
+        src_lines,
+        line_cursor,
+        body_indent,
+        Some(first_indent),
+        empty_after_marker,
+    ) {
+        let arg_string = arg.join(" ");
+
+        let mut arg_iter = arg_string.split_whitespace();
+        let points_string: Option<&str> = arg_iter.next();
+        let method_str = arg_iter.next();
+
+        let points: QuizPoints = if let Some(string) = points_string {
+            if let Ok(result) = string.parse() {
+                result
+            } else {
+                return TransitionResult::Failure {
+                    message: format!("Quiz freetext question points preceding line {} could not be parsed into an integer. Computer says no...", line_cursor.sum_total()),
+                    doctree: doctree
+                };
+            }
+        } else {
+            return TransitionResult::Failure {
+                message: format!(
+                    "No points found for freetext on line {}. Computer says no...",
+                    line_cursor.sum_total()
+                ),
+                doctree: doctree,
+            };
+        };
+        let method_string = if let Some(string) = method_str {
+            string.to_string()
+        } else {
+            String::new()
+        };
+
+        (points, method_string)
+    } else if in_feedback {
+        // Questions of feedback questionnaires are worth zero points by default
+        (0, String::new())
+    } else {
+        return TransitionResult::Failure {
+            message: format!(
+                "No points provided for freetext question on line {}. Computer says no...",
+                line_cursor.sum_total()
+            ),
+            doctree: doctree,
+        };
+    };
+
+    if let TreeNodeType::AplusQuestionnaire {
+        points_from_children,
+        ..
+    } = doctree.mut_node_data()
+    {
+        *points_from_children += points;
+    }
+
+    let (class, required, key, length, height) = if let Some(mut options) = scan_directive_options
+    (src_lines, line_cursor, body_indent) {
+        (
+            options.remove("class"),
+            options.remove("required"),
+            options.remove("key"),
+            options.remove("length"),
+            options.remove("height"),
+        )
+    } else {
+        (None, None, None, None, None)
+    };
+
+    let freetext_node = TreeNodeType::AplusFreeText {
+        body_indent: body_indent,
+        points: points,
+        compare_method: method_string,
+        model_answer: String::new(),
+        class: class,
+        required: required,
+        key: key,
+        length: length,
+        height: height,
+        assignment: String::new(),
+    };
+
+    doctree = match doctree.push_data_and_focus(freetext_node) {
+        Ok(tree) => tree,
+        Err(tree) => {
+            return TransitionResult::Failure {
+                message: format!(
+                    "Node insertion error on line {}. Computer says no...",
+                    line_cursor.sum_total()
+                ),
+                doctree: tree,
+            }
+        }
+    };
+
+    Parser::skip_empty_lines(src_lines, line_cursor);
+
+    // Read in assignment
+
+    let assignment_inline_nodes: Vec<TreeNodeType> = {
+        let (block_lines, offset) = match Parser::read_text_block(src_lines, line_cursor.relative_offset(),  true, true, Some(body_indent), true) {
+            TextBlockResult::Ok { lines, offset } => (lines, offset),
+            TextBlockResult::Err { lines, offset } => return TransitionResult::Failure {
+                message: format!("Could not read pick-any assignment lines starting on line {}. Computer says no...", line_cursor.sum_total()),
+                doctree: doctree
+            }
+        };
+
+        set_aplus_question_assignment(&mut doctree, block_lines.join("\n"));
+
+        let inline_nodes = match Parser::inline_parse(block_lines.join("\n"), Some(&mut doctree), line_cursor) {
+            InlineParsingResult::Nodes(nodes) => nodes,
+            _ => return TransitionResult::Failure {
+                message: format!("Could not parse pick-any assignment for inline nodes on line {}. Computer says no...", line_cursor.sum_total()),
+                doctree: doctree
+            }
+        };
+
+        line_cursor.increment_by(1);
+
+        inline_nodes
+    };
+
+    // Add assignment node (paragraph) to tree
+
+    let assignment_node = TreeNodeType::Paragraph {
+        indent: body_indent,
+    };
+    doctree = match doctree.push_data_and_focus(assignment_node) {
+        Ok(tree) => tree,
+        Err(tree) => {
+            return TransitionResult::Failure {
+                message: format!(
+                    "Node insertion error on line {}. Computer says no...",
+                    line_cursor.sum_total()
+                ),
+                doctree: tree,
+            }
+        }
+    };
+    for node in assignment_inline_nodes {
+        doctree = match doctree.push_data(node) {
+            Ok(tree) => tree,
+            Err(tree) => {
+                return TransitionResult::Failure {
+                    message: format!(
+                        "Node insertion error on line {}. Computer says no...",
+                        line_cursor.sum_total()
+                    ),
+                    doctree: tree,
+                }
+            }
+        };
+    }
+    doctree = doctree.focus_on_parent();
+
+    Parser::skip_empty_lines(src_lines, line_cursor);
+
+    // Read in model answer. Feedback questions do not need one.
+
+    let model_answer_line = src_lines.get(line_cursor.relative_offset()).filter(|line| {
+        !in_feedback || line.chars().take_while(|c| c.is_whitespace()).count() == body_indent
+    });
+
+    if let Some(answer) = model_answer_line {
+        let indent = answer.chars().take_while(|c| c.is_whitespace()).count();
+        if indent != body_indent {
+            return TransitionResult::Failure {
+                message: format!("A+ freetext answer has incorrect indentation on line {}. Computer says no...", line_cursor.sum_total()),
+                doctree: doctree
+            };
+        }
+
+        if let TreeNodeType::AplusFreeText { model_answer, .. } = doctree.mut_node_data() {
+            model_answer.push_str(answer.trim());
+        } else {
+            return TransitionResult::Failure {
+                message: format!("Not focused on A+ freetext node when reading its model answer on line {}? Computer says no...", line_cursor.sum_total()),
+                doctree: doctree
+            };
+        }
+
+        line_cursor.increment_by(1);
+    } else if !in_feedback {
+        return TransitionResult::Failure {
+            message: format!("Tried scanning freetext question for correct answer but encountered end of input on line {}. Computer says no...", line_cursor.sum_total()),
+            doctree: doctree
+        };
+    };
+
+    // Read possible hints
+    const APLUS_PICK_HINT_PATTERN: &'static str =
+        r"^(\s*)(?P<show_not_answered>!)?(?P<label>.+)[ ]*§[ ]*(?P<hint>.+)";
+    lazy_static::lazy_static! {
+        static ref HINT_RE: regex::Regex = regex::Regex::new(APLUS_PICK_HINT_PATTERN).unwrap();
+    }
+
+    Parser::skip_empty_lines(src_lines, line_cursor);
+
+    doctree = match doctree.push_data_and_focus(
+        TreeNodeType::AplusQuestionnaireHints {
+            body_indent: body_indent,
+        }
+    ) {
+        Ok(tree) => tree,
+        Err(tree) => {
+            return TransitionResult::Failure {
+                message: format!(
+                    "Node insertion error on line {}. Computer says no...",
+                    line_cursor.sum_total()
+                ),
+                doctree: tree,
+            }
+        }
+    };
+
+    while let Some(current_line) = src_lines.get(line_cursor.relative_offset()) {
+        let indent = current_line
+            .chars()
+            .take_while(|c| c.is_whitespace())
+            .count();
+
+        if indent != body_indent {
+            break;
+        }
+
+        let captures = if let Some(capts) = HINT_RE.captures(current_line) {
+            capts
+        } else {
+            break;
+        };
+
+        let show_not_answered = captures.name("show_not_answered");
+        let label = match captures.name("label") {
+            Some(label) => label.as_str().trim().to_string(),
+            None => {
+                return TransitionResult::Failure {
+                    message: format!(
+                        "No text for freetext hint on line {}. Computer says no...",
+                        line_cursor.sum_total()
+                    ),
+                    doctree: doctree,
+                }
+            }
+        };
+        let hint: &str = if let Some(hint) = captures.name("hint") {
+            hint.as_str().trim()
+        } else {
+            return TransitionResult::Failure {
+                message: format!(
+                    "No hint text for freetext hint on line {}. Computer says no...",
+                    line_cursor.sum_total()
+                ),
+                doctree: doctree,
+            };
+        };
+
+        if hint.is_empty() {
+            return TransitionResult::Failure {
+                message: format!(
+                    "Empty hint text for hint on line {}. Computer says no...",
+                    line_cursor.sum_total()
+                ),
+                doctree: doctree,
+            };
+        }
+
+        let hint_nodes: Vec<TreeNodeType> = match Parser::inline_parse(hint.to_string(), Some(&mut doctree), line_cursor) {
+            InlineParsingResult::Nodes(nodes) => nodes,
+            _ => return TransitionResult::Failure {
+                message: format!("Could not parse freetext hint on line {} for inline nodes. Computer says no...", line_cursor.sum_total()),
+                doctree: doctree
+            }
+        };
+
+        if hint_nodes.is_empty() {
+            return TransitionResult::Failure {
+                message: format!(
+                    "No inline nodes found for freetext hint on line {}. Computer says no...",
+                    line_cursor.sum_total()
+                ),
+                doctree: doctree,
+            };
+        }
+
+        let hint_node = TreeNodeType::AplusQuestionnaireHint {
+            label: label,
+            show_when_not_selected: show_not_answered.is_some(),
+            question_type: crate::common::AplusQuestionnaireType::FreeText,
+            hint: hint.to_string(),
+        };
+
+        doctree = match doctree.push_data_and_focus(hint_node) {
+            Ok(tree) => tree,
+            Err(tree) => {
+                return TransitionResult::Failure {
+                    message: format!(
+                        "Node insertion error on line {}. Computer says no...",
+                        line_cursor.sum_total()
+                    ),
+                    doctree: tree,
+                }
+            }
+        };
+        for node in hint_nodes {
+            doctree = match doctree.push_data(node) {
+                Ok(tree) => tree,
+                Err(tree) => {
+                    return TransitionResult::Failure {
+                        message: format!(
+                            "Node insertion error on line {}. Computer says no...",
+                            line_cursor.sum_total()
+                        ),
+                        doctree: tree,
+                    }
+                }
+            };
+        }
+        doctree = doctree.focus_on_parent();
+
+        line_cursor.increment_by(1);
+    }
+
+    Parser::skip_empty_lines(src_lines, line_cursor);
+
+    doctree = doctree.focus_on_parent(); // Focus on pick-one
+    doctree = doctree.focus_on_parent(); // Focus on questionnaire
+
+    TransitionResult::Success {
+        doctree: doctree,
+        push_or_pop: PushOrPop::Neither,
+        line_advance: LineAdvance::None,
+    }
+}
+
+/// Parses an A+ submit exercise. Exercises that open an external tool via LTI
+/// are given with the `lti` option and do not need a configuration file.
+pub fn parse_aplus_submit(
+    src_lines: &[String],
+    doctree: DocTree,
+    line_cursor: &mut LineCursor,
+    first_indent: usize,
+    body_indent: usize,
+    empty_after_marker: bool,
+) -> TransitionResult {
+    parse_submit_exercise(
+        src_lines,
+        doctree,
+        line_cursor,
+        first_indent,
+        body_indent,
+        empty_after_marker,
+        false,
+    )
+}
+
+/// Parses an A+ `acos-submit` exercise. It behaves like a submit exercise,
+/// except that the exercise is hosted on the ACOS server at the given `url`
+/// and the `ajax` flag is always set.
+pub fn parse_aplus_acos_submit(
+    src_lines: &[String],
+    doctree: DocTree,
+    line_cursor: &mut LineCursor,
+    first_indent: usize,
+    body_indent: usize,
+    empty_after_marker: bool,
+) -> TransitionResult {
+    parse_submit_exercise(
+        src_lines,
+        doctree,
+        line_cursor,
+        first_indent,
+        body_indent,
+        empty_after_marker,
+        true,
+    )
+}
+
+/// The shared parser of the A+ submit and acos-submit exercises.
+fn parse_submit_exercise(
+    src_lines: &[String],
+    mut doctree: DocTree,
+    line_cursor: &mut LineCursor,
+    first_indent: usize,
+    body_indent: usize,
+    empty_after_marker: bool,
+    is_acos: bool,
+) -> TransitionResult {
+
+    let directive_line = line_cursor.sum_total() + 1;
+
+    let (key, difficulty, max_points): (String, String, String) = if let Some(arg) =
+        scan_directive_arguments(
+            src_lines,
+            line_cursor,
+            body_indent,
+            Some(first_indent),
+            empty_after_marker,
+        ) {
+        aplus_key_difficulty_and_max_points(arg.join(" ").as_str(), &mut doctree, directive_line)
+    } else {
+        return TransitionResult::Failure {
+            message: format!(
+                "A+ submit exercise on line {} was not given arguments. Computer says no...",
+                line_cursor.sum_total()
+            ),
+            doctree: doctree,
+        };
+    };
+
+    register_aplus_exercise_key(&mut doctree, &key, directive_line);
+
+    Parser::skip_empty_lines(src_lines, line_cursor);
+
+    let (
+        config,
+        submissions,
+        points_to_pass,
+        class,
+        title,
+        category,
+        status,
+        ajax,
+        allow_assistant_viewing,
+        allow_assistant_grading,
+        quiz,
+        url,
+        radar_tokenizer,
+        radar_minimum_match_tokens,
+        lti,
+        lti_resource_link_id,
+        lti_open_in_iframe,
+        lti_aplus_get_and_post,
+    ) = if let Some(mut options) = scan_directive_options(
+        src_lines, line_cursor, body_indent
+    ) {
+        (
+            options.remove("config"),
+            options.remove("submissions"),
+            options.remove("points-to-pass"),
+            options.remove("class"),
+            options.remove("title"),
+            options.remove("category"),
+            options.remove("status"),
+            options.remove("ajax"),
+            options.remove("allow-assistant-viewing"),
+            options.remove("allow-assistant-grading"),
+            options.remove("quiz"),
+            options.remove("url"),
+            options.remove("radar-tokenizer"),
+            options.remove("radar_minimum_match_tokens"),
+            options.remove("lti"),
+            options.remove("lti_resource_link_id"),
+            options.remove("lti_open_in_iframe"),
+            options.remove("lti_aplus_get_and_post"),
+        )
+    } else {
+        (
+            None, None, None, None, None, None, None, None, None, None, None, None, None, None,
+            None, None, None, None,
+        )
+    };
+
+    if is_acos {
+        if url.is_none() {
+            return TransitionResult::Failure {
+                message: format!(
+                    "A+ ACOS exercise on line {} has to specify the exercise location via the :url: option. Computer says no...",
+                    directive_line
+                ),
+                doctree: doctree,
+            };
+        }
+    } else if config.is_none() && lti.is_none() {
+        return TransitionResult::Failure {
+    message: format!("A+ submit exercise on line {} has to specify a configuration file location via the :config: option. Computer says no...", line_cursor.sum_total()),
+    doctree: doctree
+    };
+    }
+
+    // Unpacking some options
+    let max_points = if let Ok(result) = max_points.parse() {
+        result
+    } else {
+        10
+    };
+    let points_to_pass = if let Some(ptp) = points_to_pass {
+        if let Ok(result) = ptp.parse() {
+            result
+        } else {
+            0
+        }
+    } else {
+        0
+    };
+
+    use crate::common::AplusExerciseStatus;
+    let status = if let Some(status) = status {
+        match status.as_str().trim() {
+            "ready" => AplusExerciseStatus::Ready,
+            "unlisted" => AplusExerciseStatus::Unlisted,
+            "hidden" => AplusExerciseStatus::Hidden,
+            "enrollment" => AplusExerciseStatus::Enrollment,
+            "enrollment_ext" => AplusExerciseStatus::EnrollmentExt,
+            "maintenance" => AplusExerciseStatus::Maintenance,
+            _ => AplusExerciseStatus::Unlisted,
+        }
+    } else {
+        AplusExerciseStatus::Unlisted // Default
+    };
+
+    use crate::common::AplusRadarTokenizer;
+    let tokenizer =
+        if let Some(tokenizer) = radar_tokenizer {
+            match tokenizer.as_str().trim() {
+                "python" => AplusRadarTokenizer::Python3,
+                "scala" => AplusRadarTokenizer::Scala,
+                "javascript" => AplusRadarTokenizer::JavaScript,
+                "css" => AplusRadarTokenizer::CSS,
+                "html" => AplusRadarTokenizer::HTML,
+                _ => return TransitionResult::Failure {
+                    message: format!(
+                        "No such tokenizer A+ submit exerciose on line {}. Computer says no...",
+                        line_cursor.sum_total()
+                    ),
+                    doctree: doctree,
+                },
+            }
+        } else {
+            AplusRadarTokenizer::None // Default
+        };
+
+    let lti = if let Some(lti) = lti {
+        lti
+    } else {
+        String::new()
+    };
+
+    // Crate submit node
+
+    let submit_node = TreeNodeType::AplusSubmit {
+        body_indent: body_indent,
+        key: key,
+        difficulty: difficulty,
+        max_points: max_points,
+        config: if let Some(config) = config {
+            config
+        } else {
+            String::new()
+        },
+        submissions: if let Some(submissions) = submissions {
+            if let Ok(result) = submissions.parse() {
+                result
+            } else {
+                10
+            }
+        } else {
+            10
+        },
+        points_to_pass: points_to_pass,
+        class: if let Some(class) = class {
+            class
+        } else {
+            String::new()
+        },
+        title: if let Some(title) = title {
+            title
+        } else {
+            String::new()
+        },
+        category: if let Some(category) = category {
+            category
+        } else {
+            String::from("submit")
+        },
+        status: status,
+        ajax: is_acos || ajax.is_some(),
+        allow_assistant_viewing: allow_assistant_viewing.is_some(),
+        allow_assistant_grading: allow_assistant_grading.is_some(),
+        quiz: quiz.is_some(),
+        url: if let Some(url) = url {
+            url
+        } else {
+            String::new()
+        },
+        radar_tokenizer: tokenizer, // implements Copy, so can be used below
+        radar_minimum_match_tokens: if let Some(min) = radar_minimum_match_tokens {
+            if let AplusRadarTokenizer::None = tokenizer {
+                None
+            } else {
+                if let Ok(result) = min.parse() {
+                    Some(result)
+                } else {
+                    None
+                }
+            }
+        } else {
+            None
+        },
+        lti: lti,
+        lti_resource_link_id: if let Some(id) = lti_resource_link_id {
+            id
+        } else {
+            String::new()
+        },
+        lti_open_in_iframe: lti_open_in_iframe.is_some(),
+        lti_aplus_get_and_post: lti_aplus_get_and_post.is_some(),
+    };
+
+    doctree = match doctree.push_data_and_focus(submit_node) {
+        Ok(tree) => tree,
+        Err(tree) => {
+            return TransitionResult::Failure {
+                message: format!(
+                    "Node insertion error on line {}. Computer says no...",
+                    line_cursor.sum_total()
+                ),
+                doctree: tree,
+            }
+        }
+    };
+
+    TransitionResult::Success {
+        doctree: doctree,
+        push_or_pop: PushOrPop::Push(vec![State::Body]),
+        line_advance: LineAdvance::None,
+    }
+}
+
+/// Parses the A+ `meta` directive, that sets the options of a module (exercise round)
+/// or a chapter. The options of the docutils `meta` directive, that only concern HTML output,
+/// are ignored. Invalid values are reported as errors and left out of the settings.
+pub fn parse_aplus_meta(
+    src_lines: &[String],
+    mut doctree: DocTree,
+    line_cursor: &mut LineCursor,
+    empty_after_marker: bool,
+    first_indent: usize,
+    body_indent: usize,
+) -> TransitionResult {
+    use crate::common::{AplusDateTime, TraversalType};
+    use crate::utf8_to_latex::unicode_text_to_latex;
+
+    let directive_line = line_cursor.sum_total() + 1;
+
+    scan_directive_arguments(
+        src_lines,
+        line_cursor,
+        body_indent,
+        Some(first_indent),
+        empty_after_marker,
+    );
+
+    let mut options = if let Some(options) = scan_directive_options(src_lines, line_cursor, body_indent) {
+        options
+    } else {
+        HashMap::new()
+    };
+
+    const SETTINGS: &[&str] = &[
+        "open-time", "close-time", "late-time", "late-penalty", "audience", "hidden",
+        "points-to-pass", "introduction",
+    ];
+    if !SETTINGS.iter().any(|setting| options.contains_key(*setting)) {
+        return TransitionResult::Success {
+            doctree: doctree,
+            push_or_pop: PushOrPop::Neither,
+            line_advance: LineAdvance::None,
+        };
+    }
+
+    // A round has a single set of settings, which the first meta directive defines
+    let current_id = doctree.current_node_id();
+    doctree = doctree.walk_to_root();
+    let is_duplicate = if let Some(child) = doctree.shared_child(0) {
+        if let TreeNodeType::AplusRoundSettings { .. } = child.shared_data() { true } else { false }
+    } else {
+        false
+    };
+    doctree = doctree.walk(TraversalType::ID(current_id));
+    if is_duplicate {
+        doctree.add_system_message(
+            SystemMessageLevel::Error,
+            Some(directive_line),
+            String::from("Duplicate A+ meta directive. The settings of the round are already given. Ignoring."),
+        );
+        return TransitionResult::Success {
+            doctree: doctree,
+            push_or_pop: PushOrPop::Neither,
+            line_advance: LineAdvance::None,
+        };
+    }
+
+    let mut invalid_values = Vec::<String>::new();
+
+    let mut datetime = |options: &mut HashMap<String, String>, key: &str| -> Option<AplusDateTime> {
+        let value = options.remove(key)?;
+        let datetime = converters::str_to_aplus_datetime(&value);
+        if datetime.is_none() {
+            invalid_values.push(format!("Invalid {} \"{}\" in A+ meta directive.", key, value.trim()));
+        }
+        datetime
+    };
+    let open_time = datetime(&mut options, "open-time");
+    let close_time = datetime(&mut options, "close-time");
+    let late_time = datetime(&mut options, "late-time");
+
+    let late_penalty = match options.remove("late-penalty") {
+        Some(penalty) => match penalty.trim().parse::<f64>() {
+            Ok(penalty) if (0.0..=1.0).contains(&penalty) => Some(penalty),
+            _ => {
+                invalid_values.push(format!(
+                    "Invalid late-penalty \"{}\" in A+ meta directive. The penalty should be a number between 0 and 1.",
+                    penalty.trim()
+                ));
+                None
+            }
+        },
+        None => None,
+    };
+    let audience = match options.remove("audience") {
+        Some(audience) => {
+            let converted = converters::str_to_aplus_audience(&audience);
+            if converted.is_none() {
+                invalid_values.push(format!("Invalid audience \"{}\" in A+ meta directive.", audience.trim()));
+            }
+            converted
+        }
+        None => None,
+    };
+    let points_to_pass = match options.remove("points-to-pass") {
+        Some(points) => match points.trim().parse() {
+            Ok(points) => Some(points),
+            Err(_) => {
+                invalid_values.push(format!("Invalid points-to-pass \"{}\" in A+ meta directive.", points.trim()));
+                None
+            }
+        },
+        None => None,
+    };
+
+    for message in invalid_values {
+        doctree.add_system_message(SystemMessageLevel::Error, Some(directive_line), message);
+    }
+
+    // The deadlines have to follow each other
+    match (open_time, close_time) {
+        (Some(open), Some(close)) if close < open => {
+            doctree.add_system_message(
+                SystemMessageLevel::Warning,
+                Some(directive_line),
+                String::from("The close-time of the A+ meta directive is before its open-time."),
+            );
+        }
+        _ => {}
+    };
+    match (close_time, late_time) {
+        (Some(close), Some(late)) if late < close => {
+            doctree.add_system_message(
+                SystemMessageLevel::Warning,
+                Some(directive_line),
+                String::from("The late-time of the A+ meta directive is before its close-time."),
+            );
+        }
+        _ => {}
+    };
+
+    let settings_node = TreeNodeType::AplusRoundSettings {
+        open_time: open_time,
+        close_time: close_time,
+        late_time: late_time,
+        late_penalty: late_penalty,
+        audience: audience,
+        hidden: options.remove("hidden").is_some(),
+        points_to_pass: points_to_pass,
+        introduction: options
+            .remove("introduction")
+            .map(|text| unicode_text_to_latex(text.trim())),
+    };
+
+    // The settings concern the whole round, so they are attached to the document root
+    // in front of its other children, no matter where the directive was placed.
+    doctree = doctree.walk_to_root();
+    doctree = match doctree.push_data(settings_node) {
+        Ok(tree) => tree,
+        Err(tree) => {
+            return TransitionResult::Failure {
+                message: format!(
+                    "Node insertion error on line {}. Computer says no...",
+                    directive_line
+                ),
+                doctree: tree.walk(TraversalType::ID(current_id)),
+            }
+        }
+    };
+    if let Some(children) = doctree.mut_children() {
+        if let Some(settings) = children.pop() {
+            children.insert(0, settings);
+        }
+    }
+    doctree = doctree.walk(TraversalType::ID(current_id));
+
+    TransitionResult::Success {
+        doctree: doctree,
+        push_or_pop: PushOrPop::Neither,
+        line_advance: LineAdvance::None,
+    }
+}
+
+pub fn parse_aplus_toctree() {
+    todo!()
+}
+
+pub fn parse_aplus_active_element_input(
+    src_lines: &[String],
+    mut doctree: DocTree,
+    line_cursor: &mut LineCursor,
+    base_indent: usize,
+    empty_after_marker: bool,
+    first_indent: usize,
+    body_indent: usize,
+) -> TransitionResult {
+
+    let key_for_input = if let Some(args) = scan_directive_arguments(
+        src_lines,
+        line_cursor,
+        body_indent,
+        Some(first_indent),
+        empty_after_marker,
+    ) {
+        args.join(" ")
+    } else {
+        return TransitionResult::Failure {
+    message: format!("A+ active element input before line {} has no key for output. Computer says no...", line_cursor.sum_total()),
+    doctree: doctree
+    };
+    };
+
+    let (title, default, class, width, height, clear, input_type, file) =
+        if let Some(mut options) = scan_directive_options
+        (src_lines, line_cursor, body_indent) {
+            (
+                options.remove("title"),
+                options.remove("default"),
+                options.remove("class"),
+                options.remove("width"),
+                options.remove("height"),
+                options.remove("clear"),
+                options.remove("type"),
+                options.remove("file"),
+            )
+        } else {
+            (None, None, None, None, None, None, None, None)
+        };
+
+    use crate::common::{AplusActiveElementClear, AplusActiveElementInputType};
+
+    let ae_input_node = TreeNodeType::AplusActiveElementInput {
+        key_for_input: key_for_input,
+        title: title,
+        default: default,
+        class: class,
+        width: if let Some(w) = &width {
+            converters::str_to_length(w)
+        } else {
+            None
+        },
+        height: if let Some(h) = &height {
+            converters::str_to_length(h)
+        } else {
+            None
+        },
+        clear: if let Some(clear) = clear {
+            match clear.as_str() {
+                "both" => Some(AplusActiveElementClear::Both),
+                "left" => Some(AplusActiveElementClear::Left),
+                "right" => Some(AplusActiveElementClear::Right),
+                _ => return TransitionResult::Failure {
+                    message: format!("No such clear type for A+ active element input before line {}. Computer says no...", line_cursor.sum_total()),
+                    doctree: doctree
+                }
+            }
+        } else {
+            None
+        },
+        input_type: if let Some(input_type) = &input_type {
+            if input_type == "file" {
+                Some(AplusActiveElementInputType::File)
+            } else if input_type == "clickable" {
+                Some(AplusActiveElementInputType::Clickable)
+            } else if input_type.starts_with("dropdown:") {
+                let options = if let Some(options) = input_type.split(":").last() {
+                    options
+                } else {
+                    return TransitionResult::Failure {
+                        message: format!("No options for dropdown input for A+ activ element input before line {}. Computer says no...", line_cursor.sum_total()),
+                        doctree: doctree
+                    };
+                };
+                Some(crate::common::AplusActiveElementInputType::Dropdown(
+                    options.to_string(),
+                ))
+            } else {
+                return TransitionResult::Failure {
+                    message: format!("No such input type for A+ active element input before line {}. Ignoring...", line_cursor.sum_total()),
+                    doctree: doctree
+                };
+            }
+        } else {
+            None
+        },
+        file: if let (Some(input_type), Some(file)) = (input_type, file) {
+            if input_type == "clickable" {
+                Some(file)
+            } else {
+                None
+            }
+        } else {
+            None
+        },
+    };
+
+    doctree = match doctree.push_data(ae_input_node) {
+        Ok(tree) => tree,
+        Err(tree) => {
+            return TransitionResult::Failure {
+                message: format!(
+                    "Node insertion error on line {}. Computer says no...",
+                    line_cursor.sum_total()
+                ),
+                doctree: tree,
+            }
+        }
+    };
+
+    TransitionResult::Success {
+        doctree: doctree,
+        push_or_pop: PushOrPop::Neither,
+        line_advance: LineAdvance::None,
+    }
+}
+
+/// Parses an A+ active element output directive into the respective node
+pub fn parse_aplus_active_element_output(
+    src_lines: &[String],
+    mut doctree: DocTree,
+    line_cursor: &mut LineCursor,
+    base_indent: usize,
+    empty_after_marker: bool,
+    first_indent: usize,
+    body_indent: usize,
+) -> TransitionResult {
+
+    let key_for_output = if let Some(args) = scan_directive_arguments(
+        src_lines,
+        line_cursor,
+        body_indent,
+        Some(first_indent),
+        empty_after_marker,
+    ) {
+        args.join(" ")
+    } else {
+        return TransitionResult::Failure {
+            message: format!("A+ active element output before line {} has no key for output. Computer says no...", line_cursor.sum_total()),
+            doctree: doctree
+        };
+    };
+
+    let (
+        config,
+        inputs,
+        title,
+        class,
+        width,
+        height,
+        clear,
+        output_type,
+        file,
+        submissions,
+        scale_size,
+        status,
+    ) = if let Some(mut options) = scan_directive_options(
+        src_lines, line_cursor, body_indent
+    ) {
+        (
+            options.remove("config"),
+            options.remove("inputs"),
+            options.remove("title"),
+            options.remove("class"),
+            options.remove("width"),
+            options.remove("height"),
+            options.remove("clear"),
+            options.remove("type"),
+            options.remove("file"),
+            options.remove("submissions"),
+            options.remove("scale-size"),
+            options.remove("status")
+        )
+    } else {
+        (
+            None, None, None, None, None, None, None, None, None, None, None, None
+        )
+    };
+
+    use crate::common::{
+        AplusActiveElementClear, AplusActiveElementOutputType, AplusExerciseStatus,
+    };
+
+    let ae_output_node = TreeNodeType::AplusActiveElementOutput {
+        key_for_output: key_for_output,
+        config: if let Some(config) = config {
+            config
+        } else {
+            return TransitionResult::Failure {
+                message: format!("A+ active element output before line {} must have a set config file via the \"config\" option. Computer says no...", line_cursor.sum_total()),
+                doctree: doctree
+            };
+        },
+        inputs: if let Some(inputs) = inputs {
+            inputs
+        } else {
+            return TransitionResult::Failure {
+                message: format!("A+ active element output before line {} must have a set of inputs set via the \"inputs\" setting. Computer says no...", line_cursor.sum_total()),
+                doctree: doctree
+            };
+        },
+        title: title,
+        class: class,
+        width: if let Some(w) = &width {
+            converters::str_to_length(w)
+        } else {
+            None
+        },
+        height: if let Some(h) = &height {
+            converters::str_to_length(h)
+        } else {
+            None
+        },
+        clear: if let Some(clear) = clear {
+            match clear.as_str() {
+                "both"  => Some(AplusActiveElementClear::Both),
+                "left"  => Some(AplusActiveElementClear::Left),
+                "right" => Some(AplusActiveElementClear::Right),
+                _       => None
+            }
+        } else {
+            None
+        },
+        output_type: if let Some(output_type) = output_type {
+            match output_type.as_str() {
+                "text"  => AplusActiveElementOutputType::Text,
+                "image" => AplusActiveElementOutputType::Image,
+                _       => AplusActiveElementOutputType::Text
+            }
+        } else {
+            AplusActiveElementOutputType::Text
+        },
+        submissions: if let Some(submissions) = submissions {
+            if let Ok(result) = submissions.parse::<u32>() {
+                Some(result)
+            } else {
+                None
+            }
+        } else {
+            None
+        },
+        scale_size: if let Some(_) = scale_size {
+            true
+        } else {
+            false
+        },
+        status: if let Some(status) = status {
+            match status.as_str().trim() {
+                "ready"     => AplusExerciseStatus::Ready,
+                "unlisted"  => AplusExerciseStatus::Unlisted,
+                "hidden"    => AplusExerciseStatus::Hidden,
+                "enrollment"        => AplusExerciseStatus::Enrollment,
+                "enrollment_ext"    => AplusExerciseStatus::EnrollmentExt,
+                "maintenance"       => AplusExerciseStatus::Maintenance,
+                _                   => AplusExerciseStatus::Unlisted
+            }
+        } else {
+            AplusExerciseStatus::Unlisted
+        },
+    };
+
+    doctree = match doctree.push_data(ae_output_node) {
+        Ok(tree) => tree,
+        Err(tree) => {
+            return TransitionResult::Failure {
+                message: format!(
+                    "Node insertion error on line {}. Computer says no...",
+                    line_cursor.sum_total()
+                ),
+                doctree: tree,
+            }
+        }
+    };
+
+    TransitionResult::Success {
+        doctree: doctree,
+        push_or_pop: PushOrPop::Neither,
+        line_advance: LineAdvance::None,
+    }
+}
+
+/// Parses an A+ hidden block into the respective node.
+/// The argument of the directive names the block, the option `label` gives the text
+/// of the link that shows or hides the contents and the flag `visible` makes the contents shown by default.
+/// The contents of the block are parsed as regular body elements.
+pub fn parse_aplus_hidden_block(
+    src_lines: &[String],
+    mut doctree: DocTree,
+    line_cursor: &mut LineCursor,
+    empty_after_marker: bool,
+    first_indent: usize,
+    body_indent: usize,
+) -> TransitionResult {
+    use crate::utf8_to_latex::unicode_text_to_latex;
+
+    let directive_line = line_cursor.sum_total() + 1;
+
+    let name = if let Some(arg) = scan_directive_arguments(
+        src_lines,
+        line_cursor,
+        body_indent,
+        Some(first_indent),
+        empty_after_marker,
+    ) {
+        arg.join(" ").trim().to_string()
+    } else {
+        return skip_invalid_directive(
+            src_lines,
+            doctree,
+            line_cursor,
+            body_indent,
+            directive_line,
+            String::from("The A+ hidden block is missing a name argument. Ignoring."),
+        );
+    };
+
+    let (link_text, visible) = if let Some(mut options) = scan_directive_options(
+        src_lines, line_cursor, body_indent
+    ) {
+        (options.remove("label"), options.remove("visible"))
+    } else {
+        (None, None)
+    };
+
+    let hidden_block_node = TreeNodeType::AplusHiddenBlock {
+        name: unicode_text_to_latex(name.as_str()),
+        link_text: link_text.map(|text| unicode_text_to_latex(text.trim())),
+        visible: visible.is_some(),
+        body_indent: body_indent,
+    };
+
+    doctree = match doctree.push_data_and_focus(hidden_block_node) {
+        Ok(tree) => tree,
+        Err(tree) => {
+            return TransitionResult::Failure {
+                message: format!(
+                    "Node insertion error on line {}. Computer says no...",
+                    line_cursor.sum_total()
+                ),
+                doctree: tree,
+            }
+        }
     };
 
-    Parser::skip_empty_lines(src_lines, line_cursor);
+    TransitionResult::Success {
+        doctree: doctree,
+        push_or_pop: PushOrPop::Push(vec![State::Body]),
+        line_advance: LineAdvance::None,
+    }
+}
+
+/// Parses an A+ point of interest directive into the respective node.
+
+
+/// Add support for the row and column directives introduced in November 2020.
+pub fn parse_aplus_point_of_interest(
+    src_lines: &[String],
+    mut doctree: DocTree,
+    line_cursor: &mut LineCursor,
+    base_indent: usize,
+    empty_after_marker: bool,
+    first_indent: usize,
+    body_indent: usize,
+    section_level: usize,
+) -> TransitionResult {
+
+    let title = scan_directive_arguments(
+        src_lines,
+        line_cursor,
+        body_indent,
+        Some(first_indent),
+        empty_after_marker,
+    );
 
+    // Read recognized options
     let (
-        config,
-        submissions,
-        points_to_pass,
+        id,
+        previous,
+        next,
+        hidden,
         class,
-        title,
-        category,
-        status,
-        ajax,
-        allow_assistant_viewing,
-        allow_assistant_grading,
-        quiz,
-        url,
-        radar_tokenizer,
-        radar_minimum_match_tokens,
-        lti,
-        lti_resource_link_id,
-        lti_open_in_iframe,
-        lti_aplus_get_and_post,
+        height,
+        columns,
+        bgimg,
+        not_in_slides,
+        not_in_book,
+        no_poi_box,
     ) = if let Some(mut options) = scan_directive_options(
         src_lines, line_cursor, body_indent
     ) {
+
         (
-            options.remove("config"),
-            options.remove("submissions"),
-            options.remove("points-to-pass"),
+            options.remove("id"),
+            options.remove("previous"),
+            options.remove("next"),
+            options.remove("hidden"),
             options.remove("class"),
-            options.remove("title"),
-            options.remove("category"),
-            options.remove("status"),
-            options.remove("ajax"),
-            options.remove("allow-assistant-viewing"),
-            options.remove("allow-assistant-grading"),
-            options.remove("quiz"),
-            options.remove("url"),
-            options.remove("radar-tokenizer"),
-            options.remove("radar_minimum_match_tokens"),
-            options.remove("lti"),
-            options.remove("lti_resource_link_id"),
-            options.remove("lti_open_in_iframe"),
-            options.remove("lti_aplus_get_and_post"),
+            options.remove("height"),
+            options.remove("columns"),
+            options.remove("bgimg"),
+            options.remove("not_in_slides"),
+            options.remove("not_in_book"),
+            options.remove("no_poi_box"),
         )
     } else {
         (
-            None, None, None, None, None, None, None, None, None, None, None, None, None, None,
-            None, None, None, None,
+            None, None, None, None, None, None, None, None, None, None, None,
         )
     };
 
-    if config.is_none() {
-        return TransitionResult::Failure {
-    message: format!("A+ submit exercise on line {} has to specify a configuration file location via the :config: option. Computer says no...", line_cursor.sum_total()),
-    doctree: doctree
+    let poi_node = TreeNodeType::AplusPOI {
+        title: if let Some(title) = title {
+            title.join(" ")
+        } else {
+            "".to_string()
+        },
+        body_indent: body_indent,
+
+        id: id,
+        previous: previous,
+        next: next,
+        hidden: hidden,
+        class: class,
+        height: if let Some(h) = &height {
+            converters::str_to_length(h)
+        } else {
+            None
+        },
+        columns: columns,
+        bgimg: bgimg,
+        not_in_slides: not_in_slides,
+        not_in_book: not_in_book,
+        no_poi_box: no_poi_box,
+    };
+
+    doctree = match doctree.push_data_and_focus(poi_node) {
+        Ok(tree) => tree,
+        Err(tree) => {
+            return TransitionResult::Failure {
+                message: format!(
+                    "Node insertion error on line {}. Computer says no...",
+                    line_cursor.sum_total()
+                ),
+                doctree: tree,
+            }
+        }
     };
+
+    TransitionResult::Success {
+        doctree: doctree,
+        push_or_pop: PushOrPop::Push(vec![State::AplusMultiCol]), // PoI contains body nodes and A+ specific column breaks
+        line_advance: LineAdvance::None,
     }
+}
 
-    // Unpacking some options
-    let max_points = if let Ok(result) = max_points.parse() {
-        result
-    } else {
-        10
+/// Parses an A+ annotated code block. The code is given either directly as the contents
+/// of the directive or as a nested `code-block` directive, and the `«N»` markers in it
+/// are turned into callouts. The `annotation` directives that follow the code
+/// become the children of the resulting node.
+pub fn parse_aplus_annotated(
+    src_lines: &[String],
+    mut doctree: DocTree,
+    line_cursor: &mut LineCursor,
+    empty_after_marker: bool,
+    first_indent: usize,
+    body_indent: usize,
+) -> TransitionResult {
+    // The directive takes no arguments or options, but they are read past nonetheless
+    scan_directive_arguments(
+        src_lines,
+        line_cursor,
+        body_indent,
+        Some(first_indent),
+        empty_after_marker,
+    );
+    scan_directive_options(src_lines, line_cursor, body_indent);
+    Parser::skip_empty_lines(src_lines, line_cursor);
+
+    // A nested code block directive determines the indentation of the code.
+    // Its language is not needed, as the annotated code is written without highlighting.
+    let code_indent = match src_lines.get(line_cursor.relative_offset()) {
+        Some(line) => {
+            let trimmed = line.trim();
+            let is_code_block = ["code-block::", "code::", "sourcecode::"]
+                .iter()
+                .any(|marker| trimmed.starts_with(&format!(".. {}", marker)));
+            if is_code_block {
+                line_cursor.increment_by(1);
+                let next_line_indent = |line_cursor: &LineCursor| match src_lines.get(line_cursor.relative_offset()) {
+                    Some(line) => line.chars().take_while(|c| c.is_whitespace()).count(),
+                    None => body_indent + 1,
+                };
+                scan_directive_options(src_lines, line_cursor, next_line_indent(line_cursor));
+                Parser::skip_empty_lines(src_lines, line_cursor);
+                next_line_indent(line_cursor).max(body_indent + 1)
+            } else {
+                body_indent
+            }
+        }
+        None => body_indent,
     };
-    let points_to_pass = if let Some(ptp) = points_to_pass {
-        if let Ok(result) = ptp.parse() {
-            result
-        } else {
-            0
+
+    // Read the code lines, until the indentation decreases or the first annotation is encountered
+    let mut code_lines = Vec::<String>::new();
+    while let Some(line) = src_lines.get(line_cursor.relative_offset()) {
+        let indent = line.chars().take_while(|c| c.is_whitespace()).count();
+        if !line.trim().is_empty() {
+            if indent < code_indent || line.trim_start().starts_with(".. annotation::") {
+                break;
+            }
         }
-    } else {
-        0
+        code_lines.push(line.chars().skip(code_indent).collect::<String>());
+        line_cursor.increment_by(1);
+    }
+    while let Some(true) = code_lines.last().map(|line| line.trim().is_empty()) {
+        code_lines.pop();
+    }
+
+    let mut callouts = Vec::<(usize, usize)>::new();
+    let code_lines = code_lines
+        .iter()
+        .enumerate()
+        .map(|(index, line)| {
+            let (code, numbers) = strip_annotation_markers(line);
+            callouts.extend(numbers.into_iter().map(|number| (index, number)));
+            code
+        })
+        .collect::<Vec<String>>();
+
+    let annotated_node = TreeNodeType::AplusAnnotated {
+        code_lines: code_lines,
+        callouts: callouts,
+        n_of_annotations: 0,
+        body_indent: body_indent,
     };
 
-    use crate::common::AplusExerciseStatus;
-    let status = if let Some(status) = status {
-        match status.as_str().trim() {
-            "ready" => AplusExerciseStatus::Ready,
-            "unlisted" => AplusExerciseStatus::Unlisted,
-            "hidden" => AplusExerciseStatus::Hidden,
-            "enrollment" => AplusExerciseStatus::Enrollment,
-            "enrollment_ext" => AplusExerciseStatus::EnrollmentExt,
-            "maintenance" => AplusExerciseStatus::Maintenance,
-            _ => AplusExerciseStatus::Unlisted,
+    doctree = match doctree.push_data_and_focus(annotated_node) {
+        Ok(tree) => tree,
+        Err(tree) => {
+            return TransitionResult::Failure {
+                message: format!(
+                    "Node insertion error on line {}. Computer says no...",
+                    line_cursor.sum_total()
+                ),
+                doctree: tree,
+            }
         }
+    };
+
+    TransitionResult::Success {
+        doctree: doctree,
+        push_or_pop: PushOrPop::Push(vec![State::Body]),
+        line_advance: LineAdvance::None,
+    }
+}
+
+/// Parses the annotation of an A+ annotated code block.
+/// Annotations are only allowed directly inside an annotated code block,
+/// and they are numbered in the order they are encountered in.
+pub fn parse_aplus_annotation(
+    src_lines: &[String],
+    mut doctree: DocTree,
+    line_cursor: &mut LineCursor,
+    empty_after_marker: bool,
+    first_indent: usize,
+    body_indent: usize,
+) -> TransitionResult {
+    let directive_line = line_cursor.sum_total() + 1;
+
+    scan_directive_arguments(
+        src_lines,
+        line_cursor,
+        body_indent,
+        Some(first_indent),
+        empty_after_marker,
+    );
+    scan_directive_options(src_lines, line_cursor, body_indent);
+
+    let number = if let TreeNodeType::AplusAnnotated { n_of_annotations, .. } = doctree.mut_node_data() {
+        *n_of_annotations += 1;
+        *n_of_annotations
     } else {
-        AplusExerciseStatus::Unlisted // Default
+        return skip_invalid_directive(
+            src_lines,
+            doctree,
+            line_cursor,
+            body_indent,
+            directive_line,
+            String::from("The A+ annotation is not inside an annotated code block. Ignoring."),
+        );
     };
 
-    use crate::common::AplusRadarTokenizer;
-    let tokenizer =
-        if let Some(tokenizer) = radar_tokenizer {
-            match tokenizer.as_str().trim() {
-                "python" => AplusRadarTokenizer::Python3,
-                "scala" => AplusRadarTokenizer::Scala,
-                "javascript" => AplusRadarTokenizer::JavaScript,
-                "css" => AplusRadarTokenizer::CSS,
-                "html" => AplusRadarTokenizer::HTML,
-                _ => return TransitionResult::Failure {
-                    message: format!(
-                        "No such tokenizer A+ submit exerciose on line {}. Computer says no...",
-                        line_cursor.sum_total()
-                    ),
-                    doctree: doctree,
-                },
+    let annotation_node = TreeNodeType::AplusAnnotation {
+        number: number,
+        body_indent: body_indent,
+    };
+
+    doctree = match doctree.push_data_and_focus(annotation_node) {
+        Ok(tree) => tree,
+        Err(tree) => {
+            return TransitionResult::Failure {
+                message: format!(
+                    "Node insertion error on line {}. Computer says no...",
+                    directive_line
+                ),
+                doctree: tree,
             }
+        }
+    };
+
+    TransitionResult::Success {
+        doctree: doctree,
+        push_or_pop: PushOrPop::Push(vec![State::Body]),
+        line_advance: LineAdvance::None,
+    }
+}
+
+/// Removes the `«N»` annotation markers from a line of code,
+/// returning the remaining code with its trailing whitespace trimmed and the marker numbers.
+/// A marker whose number does not fit into a `usize` is left in the code as is.
+fn strip_annotation_markers(line: &str) -> (String, Vec<usize>) {
+    let mut code = String::with_capacity(line.len());
+    let mut numbers = Vec::<usize>::new();
+    let mut rest = line;
+    while let Some(start) = rest.find('«') {
+        let after_marker = &rest[start + '«'.len_utf8()..];
+        let digits = after_marker.chars().take_while(|c| c.is_ascii_digit()).collect::<String>();
+        let number = if after_marker[digits.len()..].starts_with('»') {
+            digits.parse::<usize>().ok()
         } else {
-            AplusRadarTokenizer::None // Default
+            None
         };
+        if let Some(number) = number {
+            code += &rest[..start];
+            numbers.push(number);
+            rest = &after_marker[digits.len() + '»'.len_utf8()..];
+        } else {
+            code += &rest[..start + '«'.len_utf8()];
+            rest = after_marker;
+        }
+    }
+    code += rest;
+    (code.trim_end().to_string(), numbers)
+}
 
-    let lti = if let Some(lti) = lti {
-        lti
+/// Parses an A+ `lineref-code-block`. The directive is used like the Sphinx `code-block`,
+/// but the lines of code may end with a `::label::` marker. The markers are removed from the code
+/// and the labels turned into targets, that the `:lineref:` role can refer to.
+pub fn parse_aplus_lineref_codeblock(
+    src_lines: &[String],
+    mut doctree: DocTree,
+    line_cursor: &mut LineCursor,
+    empty_after_marker: bool,
+    first_indent: usize,
+    body_indent: usize,
+) -> TransitionResult {
+    lazy_static::lazy_static! {
+        static ref LINE_LABEL_RE: regex::Regex = regex::Regex::new(r"^(?P<code>.*?)\s*::(?P<label>[a-zA-Z0-9_-]+)::\s*$").unwrap();
+    }
+
+    let directive_line = line_cursor.sum_total() + 1;
+
+    let language = if let Some(arg) = scan_directive_arguments(
+        src_lines,
+        line_cursor,
+        body_indent,
+        Some(first_indent),
+        empty_after_marker,
+    ) {
+        Some(arg.join(""))
     } else {
-        String::new()
+        None
     };
 
-    // Crate submit node
+    // The code block options are accepted, but not used in LarST
+    scan_directive_options(src_lines, line_cursor, body_indent);
 
-    let submit_node = TreeNodeType::AplusSubmit {
-        body_indent: body_indent,
-        key: key,
-        difficulty: difficulty,
-        max_points: max_points,
-        config: config.unwrap(),
-        submissions: if let Some(submissions) = submissions {
-            if let Ok(result) = submissions.parse() {
-                result
-            } else {
-                10
-            }
-        } else {
-            10
-        },
-        points_to_pass: points_to_pass,
-        class: if let Some(class) = class {
-            class
-        } else {
-            String::new()
-        },
-        title: if let Some(title) = title {
-            title
-        } else {
-            String::new()
-        },
-        category: if let Some(category) = category {
-            category
-        } else {
-            String::from("submit")
-        },
-        status: status,
-        ajax: ajax.is_some(),
-        allow_assistant_viewing: allow_assistant_viewing.is_some(),
-        allow_assistant_grading: allow_assistant_grading.is_some(),
-        quiz: quiz.is_some(),
-        url: if let Some(url) = url {
-            url
-        } else {
-            String::new()
-        },
-        radar_tokenizer: tokenizer, // implements Copy, so can be used below
-        radar_minimum_match_tokens: if let Some(min) = radar_minimum_match_tokens {
-            if let AplusRadarTokenizer::None = tokenizer {
-                None
-            } else {
-                if let Ok(result) = min.parse() {
-                    Some(result)
-                } else {
-                    None
-                }
+    let (lines, offset) = if let IndentedBlockResult::Ok { lines, offset, .. } = Parser::read_indented_block(
+        src_lines,
+        line_cursor.relative_offset(),
+        false,
+        true,
+        Some(body_indent),
+        None,
+        false,
+    ) {
+        (lines, offset)
+    } else {
+        return TransitionResult::Failure {
+            message: format!(
+                "Could not read the code block on line {}. Computer says no...",
+                line_cursor.sum_total() + 1
+            ),
+            doctree: doctree,
+        };
+    };
+
+    let mut line_labels = Vec::<(usize, String)>::new();
+    let mut code_lines = lines
+        .iter()
+        .skip_while(|line| line.trim().is_empty())
+        .enumerate()
+        .map(|(index, line)| match LINE_LABEL_RE.captures(line) {
+            Some(captures) => {
+                line_labels.push((index, crate::common::lineref_label(&captures["label"])));
+                captures["code"].to_string()
             }
-        } else {
-            None
-        },
-        lti: lti,
-        lti_resource_link_id: if let Some(id) = lti_resource_link_id {
-            id
-        } else {
-            String::new()
-        },
-        lti_open_in_iframe: lti_open_in_iframe.is_some(),
-        lti_aplus_get_and_post: lti_aplus_get_and_post.is_some(),
+            None => line.trim_end().to_string(),
+        })
+        .collect::<Vec<String>>();
+    while let Some(true) = code_lines.last().map(|line| line.is_empty()) {
+        code_lines.pop();
+    }
+
+    // The labels are escaped into LaTeX with a character that does not occur in the code
+    let escape_char = crate::common::LINEREF_ESCAPE_CHARS
+        .iter()
+        .find(|c| !code_lines.iter().any(|line| line.contains(**c)))
+        .copied();
+    if escape_char.is_none() && !line_labels.is_empty() {
+        doctree.add_system_message(
+            SystemMessageLevel::Warning,
+            Some(directive_line),
+            format!(
+                "All of the escape characters {} occur in the lineref code block, so its line labels are left out.",
+                crate::common::LINEREF_ESCAPE_CHARS.iter().collect::<String>()
+            ),
+        );
+        line_labels.clear();
+    }
+
+    let code_block = TreeNodeType::AplusLineRefCodeBlock {
+        language: language,
+        code_lines: code_lines,
+        line_labels: line_labels,
+        escape_char: escape_char,
     };
 
-    doctree = match doctree.push_data_and_focus(submit_node) {
+    doctree = match doctree.push_data(code_block) {
         Ok(tree) => tree,
         Err(tree) => {
             return TransitionResult::Failure {
@@ -3189,125 +5485,106 @@ pub fn parse_aplus_submit(
 
     TransitionResult::Success {
         doctree: doctree,
-        push_or_pop: PushOrPop::Push(vec![State::Body]),
-        line_advance: LineAdvance::None,
+        push_or_pop: PushOrPop::Neither,
+        line_advance: LineAdvance::Some(offset),
     }
 }
 
-pub fn parse_aplus_toctree() {
-    todo!()
-}
-
-pub fn parse_aplus_active_element_input(
+/// Parses an A+ REPL session. Lines starting with the `scala>` prompt and their `|` continuation
+/// lines are recorded as input, and the rest as output. The results named `resN` by the REPL
+/// are renumbered, so that the numbering continues from the previous session of the document.
+pub fn parse_aplus_repl(
     src_lines: &[String],
     mut doctree: DocTree,
     line_cursor: &mut LineCursor,
-    base_indent: usize,
     empty_after_marker: bool,
     first_indent: usize,
     body_indent: usize,
 ) -> TransitionResult {
+    use crate::common::AplusREPLLine;
 
-    let key_for_input = if let Some(args) = scan_directive_arguments(
+    lazy_static::lazy_static! {
+        static ref PROMPT_RE: regex::Regex = regex::Regex::new(r"^(scala>)[ ]?(.*)$").unwrap();
+        static ref CONTINUATION_RE: regex::Regex = regex::Regex::new(r"^([ ]*\|)[ ]?(.*)$").unwrap();
+        static ref RES_DEFINITION_RE: regex::Regex = regex::Regex::new(r"^(val )?res([0-9]+)\b").unwrap();
+        static ref RES_REFERENCE_RE: regex::Regex = regex::Regex::new(r"\bres([0-9]+)\b").unwrap();
+    }
+
+    // The directive takes no arguments or options, but they are read past nonetheless
+    scan_directive_arguments(
         src_lines,
         line_cursor,
         body_indent,
         Some(first_indent),
         empty_after_marker,
+    );
+    scan_directive_options(src_lines, line_cursor, body_indent);
+
+    let (lines, offset) = if let IndentedBlockResult::Ok { lines, offset, .. } = Parser::read_indented_block(
+        src_lines,
+        line_cursor.relative_offset(),
+        false,
+        true,
+        Some(body_indent),
+        None,
+        false,
     ) {
-        args.join(" ")
+        (lines, offset)
     } else {
         return TransitionResult::Failure {
-    message: format!("A+ active element input before line {} has no key for output. Computer says no...", line_cursor.sum_total()),
-    doctree: doctree
-    };
+            message: format!(
+                "Could not read the REPL session on line {}. Computer says no...",
+                line_cursor.sum_total() + 1
+            ),
+            doctree: doctree,
+        };
     };
 
-    let (title, default, class, width, height, clear, input_type, file) =
-        if let Some(mut options) = scan_directive_options
-        (src_lines, line_cursor, body_indent) {
-            (
-                options.remove("title"),
-                options.remove("default"),
-                options.remove("class"),
-                options.remove("width"),
-                options.remove("height"),
-                options.remove("clear"),
-                options.remove("type"),
-                options.remove("file"),
-            )
+    let mut repl_lines = Vec::<AplusREPLLine>::new();
+    for line in lines
+        .iter()
+        .map(|line| line.trim_end())
+        .skip_while(|line| line.is_empty())
+    {
+        let continues_input = if let Some(AplusREPLLine::Input { .. }) = repl_lines.last() {
+            true
         } else {
-            (None, None, None, None, None, None, None, None)
+            false
         };
-
-    use crate::common::{AplusActiveElementClear, AplusActiveElementInputType};
-
-    let ae_input_node = TreeNodeType::AplusActiveElementInput {
-        key_for_input: key_for_input,
-        title: title,
-        default: default,
-        class: class,
-        width: if let Some(w) = &width {
-            converters::str_to_length(w)
-        } else {
-            None
-        },
-        height: if let Some(h) = &height {
-            converters::str_to_length(h)
-        } else {
-            None
-        },
-        clear: if let Some(clear) = clear {
-            match clear.as_str() {
-                "both" => Some(AplusActiveElementClear::Both),
-                "left" => Some(AplusActiveElementClear::Left),
-                "right" => Some(AplusActiveElementClear::Right),
-                _ => return TransitionResult::Failure {
-                    message: format!("No such clear type for A+ active element input before line {}. Computer says no...", line_cursor.sum_total()),
-                    doctree: doctree
+        let input_captures = match PROMPT_RE.captures(line) {
+            Some(captures) => Some(captures),
+            None if continues_input => CONTINUATION_RE.captures(line),
+            None => None,
+        };
+        if let Some(captures) = input_captures {
+            let text = RES_REFERENCE_RE.replace_all(&captures[2], |res: &regex::Captures| {
+                match doctree.repl_res_number(&res[1]) {
+                    Some(number) => format!("res{}", number),
+                    None => res[0].to_string(),
                 }
-            }
-        } else {
-            None
-        },
-        input_type: if let Some(input_type) = &input_type {
-            if input_type == "file" {
-                Some(AplusActiveElementInputType::File)
-            } else if input_type == "clickable" {
-                Some(AplusActiveElementInputType::Clickable)
-            } else if input_type.starts_with("dropdown:") {
-                let options = if let Some(options) = input_type.split(":").last() {
-                    options
-                } else {
-                    return TransitionResult::Failure {
-                        message: format!("No options for dropdown input for A+ activ element input before line {}. Computer says no...", line_cursor.sum_total()),
-                        doctree: doctree
-                    };
-                };
-                Some(crate::common::AplusActiveElementInputType::Dropdown(
-                    options.to_string(),
-                ))
-            } else {
-                return TransitionResult::Failure {
-                    message: format!("No such input type for A+ active element input before line {}. Ignoring...", line_cursor.sum_total()),
-                    doctree: doctree
-                };
-            }
+            });
+            repl_lines.push(AplusREPLLine::Input {
+                prompt: captures[1].to_string(),
+                text: text.to_string(),
+            });
+        } else if let Some(captures) = RES_DEFINITION_RE.captures(line) {
+            let number = doctree.next_repl_res_number(&captures[2]);
+            let prefix = captures.get(1).map(|val| val.as_str()).unwrap_or("");
+            let rest = &line[captures.get(0).unwrap().end()..];
+            repl_lines.push(AplusREPLLine::Output(format!("{}res{}{}", prefix, number, rest)));
         } else {
-            None
-        },
-        file: if let (Some(input_type), Some(file)) = (input_type, file) {
-            if input_type == "clickable" {
-                Some(file)
-            } else {
-                None
-            }
+            repl_lines.push(AplusREPLLine::Output(line.to_string()));
+        }
+    }
+    while let Some(AplusREPLLine::Output(text)) = repl_lines.last() {
+        if text.is_empty() {
+            repl_lines.pop();
         } else {
-            None
-        },
-    };
+            break;
+        }
+    }
 
-    doctree = match doctree.push_data(ae_input_node) {
+    doctree = match doctree.push_data(TreeNodeType::AplusREPLSession { lines: repl_lines }) {
         Ok(tree) => tree,
         Err(tree) => {
             return TransitionResult::Failure {
@@ -3320,6 +5597,34 @@ pub fn parse_aplus_active_element_input(
         }
     };
 
+    TransitionResult::Success {
+        doctree: doctree,
+        push_or_pop: PushOrPop::Neither,
+        line_advance: LineAdvance::Some(offset),
+    }
+}
+
+/// Parses an A+ `repl-res-count-reset` directive, which makes the numbering of the REPL results
+/// start from `res0` again in the next REPL session. The directive produces no output.
+pub fn parse_aplus_repl_res_count_reset(
+    src_lines: &[String],
+    mut doctree: DocTree,
+    line_cursor: &mut LineCursor,
+    empty_after_marker: bool,
+    first_indent: usize,
+    body_indent: usize,
+) -> TransitionResult {
+    scan_directive_arguments(
+        src_lines,
+        line_cursor,
+        body_indent,
+        Some(first_indent),
+        empty_after_marker,
+    );
+    scan_directive_options(src_lines, line_cursor, body_indent);
+
+    doctree.reset_repl_res_count();
+
     TransitionResult::Success {
         doctree: doctree,
         push_or_pop: PushOrPop::Neither,
@@ -3327,151 +5632,41 @@ pub fn parse_aplus_active_element_input(
     }
 }
 
-/// Parses an A+ active element output directive into the respective node
-pub fn parse_aplus_active_element_output(
+/// Parses an A+ `div` directive into a container, whose classes are given as the arguments of the directive.
+pub fn parse_aplus_div(
     src_lines: &[String],
     mut doctree: DocTree,
     line_cursor: &mut LineCursor,
-    base_indent: usize,
     empty_after_marker: bool,
     first_indent: usize,
     body_indent: usize,
 ) -> TransitionResult {
-
-    let key_for_output = if let Some(args) = scan_directive_arguments(
+    let classes = if let Some(arg) = scan_directive_arguments(
         src_lines,
         line_cursor,
         body_indent,
         Some(first_indent),
         empty_after_marker,
     ) {
-        args.join(" ")
+        arg.join(" ")
+            .split_whitespace()
+            .map(|class| class.to_string())
+            .collect::<Vec<String>>()
     } else {
-        return TransitionResult::Failure {
-            message: format!("A+ active element output before line {} has no key for output. Computer says no...", line_cursor.sum_total()),
-            doctree: doctree
-        };
+        Vec::new()
     };
 
-    let (
-        config,
-        inputs,
-        title,
-        class,
-        width,
-        height,
-        clear,
-        output_type,
-        file,
-        submissions,
-        scale_size,
-        status,
-    ) = if let Some(mut options) = scan_directive_options(
-        src_lines, line_cursor, body_indent
-    ) {
-        (
-            options.remove("config"),
-            options.remove("inputs"),
-            options.remove("title"),
-            options.remove("class"),
-            options.remove("width"),
-            options.remove("height"),
-            options.remove("clear"),
-            options.remove("type"),
-            options.remove("file"),
-            options.remove("submissions"),
-            options.remove("scale-size"),
-            options.remove("status")
-        )
-    } else {
-        (
-            None, None, None, None, None, None, None, None, None, None, None, None
-        )
-    };
+    scan_directive_options(src_lines, line_cursor, body_indent);
 
-    use crate::common::{
-        AplusActiveElementClear, AplusActiveElementOutputType, AplusExerciseStatus,
-    };
+    for class in classes.iter() {
+        doctree.push_class(class);
+    }
 
-    let ae_output_node = TreeNodeType::AplusActiveElementOutput {
-        key_for_output: key_for_output,
-        config: if let Some(config) = config {
-            config
-        } else {
-            return TransitionResult::Failure {
-                message: format!("A+ active element output before line {} must have a set config file via the \"config\" option. Computer says no...", line_cursor.sum_total()),
-                doctree: doctree
-            };
-        },
-        inputs: if let Some(inputs) = inputs {
-            inputs
-        } else {
-            return TransitionResult::Failure {
-                message: format!("A+ active element output before line {} must have a set of inputs set via the \"inputs\" setting. Computer says no...", line_cursor.sum_total()),
-                doctree: doctree
-            };
-        },
-        title: title,
-        class: class,
-        width: if let Some(w) = &width {
-            converters::str_to_length(w)
-        } else {
-            None
-        },
-        height: if let Some(h) = &height {
-            converters::str_to_length(h)
-        } else {
-            None
-        },
-        clear: if let Some(clear) = clear {
-            match clear.as_str() {
-                "both"  => Some(AplusActiveElementClear::Both),
-                "left"  => Some(AplusActiveElementClear::Left),
-                "right" => Some(AplusActiveElementClear::Right),
-                _       => None
-            }
-        } else {
-            None
-        },
-        output_type: if let Some(output_type) = output_type {
-            match output_type.as_str() {
-                "text"  => AplusActiveElementOutputType::Text,
-                "image" => AplusActiveElementOutputType::Image,
-                _       => AplusActiveElementOutputType::Text
-            }
-        } else {
-            AplusActiveElementOutputType::Text
-        },
-        submissions: if let Some(submissions) = submissions {
-            if let Ok(result) = submissions.parse::<u32>() {
-                Some(result)
-            } else {
-                None
-            }
-        } else {
-            None
-        },
-        scale_size: if let Some(_) = scale_size {
-            true
-        } else {
-            false
-        },
-        status: if let Some(status) = status {
-            match status.as_str().trim() {
-                "ready"     => AplusExerciseStatus::Ready,
-                "unlisted"  => AplusExerciseStatus::Unlisted,
-                "hidden"    => AplusExerciseStatus::Hidden,
-                "enrollment"        => AplusExerciseStatus::Enrollment,
-                "enrollment_ext"    => AplusExerciseStatus::EnrollmentExt,
-                "maintenance"       => AplusExerciseStatus::Maintenance,
-                _                   => AplusExerciseStatus::Unlisted
-            }
-        } else {
-            AplusExerciseStatus::Unlisted
-        },
+    let div_node = TreeNodeType::AplusDiv {
+        body_indent: body_indent,
     };
 
-    doctree = match doctree.push_data(ae_output_node) {
+    doctree = match doctree.push_data_and_focus(div_node) {
         Ok(tree) => tree,
         Err(tree) => {
             return TransitionResult::Failure {
@@ -3486,100 +5681,62 @@ pub fn parse_aplus_active_element_output(
 
     TransitionResult::Success {
         doctree: doctree,
-        push_or_pop: PushOrPop::Neither,
+        push_or_pop: PushOrPop::Push(vec![State::Body]),
         line_advance: LineAdvance::None,
     }
 }
 
-pub fn parse_aplus_hidden_block() {
-    todo!()
-}
-
-/// Parses an A+ point of interest directive into the respective node.
-
-
-/// Add support for the row and column directives introduced in November 2020.
-pub fn parse_aplus_point_of_interest(
+/// Parses an A+ `styled-topic` directive. The title of the topic is given as the argument
+/// and the classes used to style it with the `class` option.
+pub fn parse_aplus_styled_topic(
     src_lines: &[String],
     mut doctree: DocTree,
     line_cursor: &mut LineCursor,
-    base_indent: usize,
     empty_after_marker: bool,
     first_indent: usize,
     body_indent: usize,
-    section_level: usize,
 ) -> TransitionResult {
+    use crate::utf8_to_latex::unicode_text_to_latex;
 
-    let title = scan_directive_arguments(
+    let directive_line = line_cursor.sum_total() + 1;
+
+    let title = if let Some(arg) = scan_directive_arguments(
         src_lines,
         line_cursor,
         body_indent,
         Some(first_indent),
         empty_after_marker,
-    );
-
-    // Read recognized options
-    let (
-        id,
-        previous,
-        next,
-        hidden,
-        class,
-        height,
-        columns,
-        bgimg,
-        not_in_slides,
-        not_in_book,
-        no_poi_box,
-    ) = if let Some(mut options) = scan_directive_options(
-        src_lines, line_cursor, body_indent
     ) {
+        arg.join(" ").trim().to_string()
+    } else {
+        return skip_invalid_directive(
+            src_lines,
+            doctree,
+            line_cursor,
+            body_indent,
+            directive_line,
+            String::from("The A+ styled topic is missing a title. Ignoring."),
+        );
+    };
 
-        (
-            options.remove("id"),
-            options.remove("previous"),
-            options.remove("next"),
-            options.remove("hidden"),
-            options.remove("class"),
-            options.remove("height"),
-            options.remove("columns"),
-            options.remove("bgimg"),
-            options.remove("not_in_slides"),
-            options.remove("not_in_book"),
-            options.remove("no_poi_box"),
-        )
+    let class = if let Some(mut options) = scan_directive_options(src_lines, line_cursor, body_indent) {
+        options.remove("class")
     } else {
-        (
-            None, None, None, None, None, None, None, None, None, None, None,
-        )
+        None
     };
 
-    let poi_node = TreeNodeType::AplusPOI {
-        title: if let Some(title) = title {
-            title.join(" ")
-        } else {
-            "".to_string()
-        },
-        body_indent: body_indent,
+    if let Some(class) = &class {
+        for class in class.split_whitespace() {
+            doctree.push_class(class);
+        }
+    }
 
-        id: id,
-        previous: previous,
-        next: next,
-        hidden: hidden,
-        class: class,
-        height: if let Some(h) = &height {
-            converters::str_to_length(h)
-        } else {
-            None
-        },
-        columns: columns,
-        bgimg: bgimg,
-        not_in_slides: not_in_slides,
-        not_in_book: not_in_book,
-        no_poi_box: no_poi_box,
+    let topic_node = TreeNodeType::AplusStyledTopic {
+        title: unicode_text_to_latex(title.as_str()),
+        body_indent: body_indent,
     };
 
-    doctree = match doctree.push_data_and_focus(poi_node) {
+    doctree = match doctree.push_data_and_focus(topic_node) {
         Ok(tree) => tree,
         Err(tree) => {
             return TransitionResult::Failure {
@@ -3594,53 +5751,254 @@ pub fn parse_aplus_point_of_interest(
 
     TransitionResult::Success {
         doctree: doctree,
-        push_or_pop: PushOrPop::Push(vec![State::AplusMultiCol]), // PoI contains body nodes and A+ specific column breaks
+        push_or_pop: PushOrPop::Push(vec![State::Body]),
         line_advance: LineAdvance::None,
     }
 }
 
-pub fn parse_aplus_annotated() {
-    todo!()
-}
-
-pub fn parse_aplus_lineref_codeblock() {
-    todo!()
+/// Parses an A+ `story` directive. The source of the story is given as the argument.
+pub fn parse_aplus_story(
+    src_lines: &[String],
+    doctree: DocTree,
+    line_cursor: &mut LineCursor,
+    empty_after_marker: bool,
+    first_indent: usize,
+    body_indent: usize,
+) -> TransitionResult {
+    let directive_line = line_cursor.sum_total() + 1;
+    let (args, mut options) = scan_media_arguments_and_options(
+        src_lines, line_cursor, empty_after_marker, first_indent, body_indent
+    );
+    let source = match args.into_iter().next() {
+        Some(source) => source,
+        None => return skip_invalid_directive(
+            src_lines,
+            doctree,
+            line_cursor,
+            body_indent,
+            directive_line,
+            String::from("The A+ story is missing its source. Ignoring."),
+        ),
+    };
+    let story_node = TreeNodeType::AplusStory {
+        source: source,
+        title: media_title(&mut options),
+    };
+    push_media_node(doctree, story_node, line_cursor)
 }
 
-pub fn parse_aplus_repl_res_count_reset() {
-    todo!()
+/// Parses an A+ `jsvee` program visualization. The id of the visualization is given as the argument.
+pub fn parse_aplus_jsvee(
+    src_lines: &[String],
+    doctree: DocTree,
+    line_cursor: &mut LineCursor,
+    empty_after_marker: bool,
+    first_indent: usize,
+    body_indent: usize,
+) -> TransitionResult {
+    let directive_line = line_cursor.sum_total() + 1;
+    let (args, mut options) = scan_media_arguments_and_options(
+        src_lines, line_cursor, empty_after_marker, first_indent, body_indent
+    );
+    let visualization_id = match args.into_iter().next() {
+        Some(id) => id,
+        None => return skip_invalid_directive(
+            src_lines,
+            doctree,
+            line_cursor,
+            body_indent,
+            directive_line,
+            String::from("The A+ JSVee visualization is missing its id. Ignoring."),
+        ),
+    };
+    let jsvee_node = TreeNodeType::AplusJSVee {
+        visualization_id: visualization_id,
+        title: media_title(&mut options),
+    };
+    push_media_node(doctree, jsvee_node, line_cursor)
 }
 
-pub fn parse_aplus_acos_submit() {
-    todo!()
+/// Parses an A+ `youtube` directive. The arguments are the id of the video
+/// and its optional duration. A poster image is looked up from the source folder
+/// by the id of the video, unless one is given with the `poster` option.
+pub fn parse_aplus_youtube(
+    src_lines: &[String],
+    doctree: DocTree,
+    line_cursor: &mut LineCursor,
+    empty_after_marker: bool,
+    first_indent: usize,
+    body_indent: usize,
+) -> TransitionResult {
+    let directive_line = line_cursor.sum_total() + 1;
+    let (args, mut options) = scan_media_arguments_and_options(
+        src_lines, line_cursor, empty_after_marker, first_indent, body_indent
+    );
+    let mut args = args.into_iter();
+    let video_id = match args.next() {
+        Some(id) => id,
+        None => return skip_invalid_directive(
+            src_lines,
+            doctree,
+            line_cursor,
+            body_indent,
+            directive_line,
+            String::from("The A+ YouTube video is missing its id. Ignoring."),
+        ),
+    };
+    let poster = local_poster_image(doctree.shared_file_folder(), &video_id, options.remove("poster"));
+    let youtube_node = TreeNodeType::AplusYouTube {
+        title: media_title(&mut options),
+        duration: media_detail(args.next()),
+        poster: poster,
+        video_id: video_id,
+    };
+    push_media_node(doctree, youtube_node, line_cursor)
 }
 
-pub fn parse_aplus_div() {
-    todo!()
+/// Parses an A+ `local-video` directive. The arguments are the path of the video file
+/// and its optional duration. A poster image with the same name as the video is used,
+/// if one exists, unless another one is given with the `poster` option.
+pub fn parse_aplus_local_video(
+    src_lines: &[String],
+    doctree: DocTree,
+    line_cursor: &mut LineCursor,
+    empty_after_marker: bool,
+    first_indent: usize,
+    body_indent: usize,
+) -> TransitionResult {
+    let directive_line = line_cursor.sum_total() + 1;
+    let (args, mut options) = scan_media_arguments_and_options(
+        src_lines, line_cursor, empty_after_marker, first_indent, body_indent
+    );
+    let mut args = args.into_iter();
+    let video_path = match args.next() {
+        Some(path) => path,
+        None => return skip_invalid_directive(
+            src_lines,
+            doctree,
+            line_cursor,
+            body_indent,
+            directive_line,
+            String::from("The A+ local video is missing its path. Ignoring."),
+        ),
+    };
+    let poster = local_poster_image(doctree.shared_file_folder(), &video_path, options.remove("poster"));
+    let video_node = TreeNodeType::AplusLocalVideo {
+        title: media_title(&mut options),
+        duration: media_detail(args.next()),
+        poster: poster,
+        video_path: video_path,
+    };
+    push_media_node(doctree, video_node, line_cursor)
 }
 
-pub fn parse_aplus_styled_topic() {
-    todo!()
+/// Parses an A+ `embedded-page` directive. The arguments are the URL of the page
+/// and optionally its width and height, which may also be given as options.
+pub fn parse_aplus_embedded_page(
+    src_lines: &[String],
+    doctree: DocTree,
+    line_cursor: &mut LineCursor,
+    empty_after_marker: bool,
+    first_indent: usize,
+    body_indent: usize,
+) -> TransitionResult {
+    let directive_line = line_cursor.sum_total() + 1;
+    let (args, mut options) = scan_media_arguments_and_options(
+        src_lines, line_cursor, empty_after_marker, first_indent, body_indent
+    );
+    let mut args = args.into_iter();
+    let url = match args.next() {
+        Some(url) => url,
+        None => return skip_invalid_directive(
+            src_lines,
+            doctree,
+            line_cursor,
+            body_indent,
+            directive_line,
+            String::from("The A+ embedded page is missing its URL. Ignoring."),
+        ),
+    };
+    let page_node = TreeNodeType::AplusEmbeddedPage {
+        url: url,
+        title: media_title(&mut options),
+        width: media_detail(args.next().or(options.remove("width"))),
+        height: media_detail(args.next().or(options.remove("height"))),
+    };
+    push_media_node(doctree, page_node, line_cursor)
 }
 
-pub fn parse_aplus_story() {
-    todo!()
+/// Reads the whitespace-separated arguments and the options of an A+ media directive.
+fn scan_media_arguments_and_options(
+    src_lines: &[String],
+    line_cursor: &mut LineCursor,
+    empty_after_marker: bool,
+    first_indent: usize,
+    body_indent: usize,
+) -> (Vec<String>, HashMap<String, String>) {
+    let args = if let Some(arg) = scan_directive_arguments(
+        src_lines,
+        line_cursor,
+        body_indent,
+        Some(first_indent),
+        empty_after_marker,
+    ) {
+        arg.join(" ")
+            .split_whitespace()
+            .map(|arg| arg.to_string())
+            .collect()
+    } else {
+        Vec::new()
+    };
+    let options = scan_directive_options(src_lines, line_cursor, body_indent).unwrap_or(HashMap::new());
+    (args, options)
 }
 
-pub fn parse_aplus_jsvee() {
-    todo!()
+/// Removes the `title` option from the options of an A+ media directive and converts it to LaTeX.
+fn media_title(options: &mut HashMap<String, String>) -> Option<String> {
+    options
+        .remove("title")
+        .map(|title| crate::utf8_to_latex::unicode_text_to_latex(title.trim()))
 }
 
-pub fn parse_aplus_youtube() {
-    todo!()
+/// Converts a detail of an A+ media directive, such as a duration or a size, to LaTeX.
+fn media_detail(detail: Option<String>) -> Option<String> {
+    detail.map(|detail| crate::utf8_to_latex::unicode_text_to_latex(detail.trim()))
 }
 
-pub fn parse_aplus_local_video() {
-    todo!()
+/// Returns the given poster image of an A+ video, or looks for a PNG or JPEG image named after
+/// the video in the given folder. The returned path is relative to the folder.
+fn local_poster_image(folder: &str, video_name: &str, poster: Option<String>) -> Option<String> {
+    if poster.is_some() {
+        return poster;
+    }
+    let video_path = std::path::Path::new(video_name);
+    ["png", "jpg", "jpeg"]
+        .iter()
+        .map(|extension| video_path.with_extension(extension))
+        .find(|image_path| std::path::Path::new(folder).join(image_path).is_file())
+        .map(|image_path| image_path.to_string_lossy().to_string())
 }
 
-pub fn parse_aplus_embedded_page() {
-    todo!()
+/// Pushes a media node into the tree. Media nodes have no content.
+fn push_media_node(
+    doctree: DocTree,
+    media_node: TreeNodeType,
+    line_cursor: &mut LineCursor,
+) -> TransitionResult {
+    match doctree.push_data(media_node) {
+        Ok(tree) => TransitionResult::Success {
+            doctree: tree,
+            push_or_pop: PushOrPop::Neither,
+            line_advance: LineAdvance::None,
+        },
+        Err(tree) => TransitionResult::Failure {
+            message: format!(
+                "Node insertion error on line {}. Computer says no...",
+                line_cursor.sum_total()
+            ),
+            doctree: tree,
+        },
+    }
 }
 
 /// Parses unknown directive blocks as literal text.
@@ -3653,6 +6011,9 @@ pub fn parse_unknown_directive(
     body_indent: usize,
     empty_after_marker: bool,
 ) -> TransitionResult {
+    let line = line_cursor.sum_total() + 1;
+    let source_lines = unknown_directive_source_lines(src_lines, line_cursor.relative_offset());
+
     let argument = if let Some(arg) = scan_directive_arguments(
         src_lines,
         line_cursor,
@@ -3679,6 +6040,8 @@ pub fn parse_unknown_directive(
         argument: argument,
         options: options,
         body_indent: body_indent,
+        line: line,
+        source_lines: source_lines,
     };
 
     doctree = match doctree.push_data_and_focus(unknown_directive_data) {
@@ -3862,12 +6225,59 @@ fn scan_directive_options(
     }
 }
 
+/// Reports an invalid directive as an error on the given directive line
+/// and skips its indented contents, so that parsing continues after the directive.
+fn skip_invalid_directive(
+    src_lines: &[String],
+    mut doctree: DocTree,
+    line_cursor: &mut LineCursor,
+    body_indent: usize,
+    directive_line: usize,
+    message: String,
+) -> TransitionResult {
+    doctree.add_system_message(SystemMessageLevel::Error, Some(directive_line), message);
+    // The directive may have no contents left after its arguments and options
+    let has_contents = match src_lines.get(line_cursor.relative_offset()) {
+        Some(line) => {
+            line.trim().is_empty()
+                || line.chars().take_while(|c| c.is_whitespace()).count() >= body_indent
+        }
+        None => false,
+    };
+    if !has_contents {
+        return TransitionResult::Success {
+            doctree: doctree,
+            push_or_pop: PushOrPop::Neither,
+            line_advance: LineAdvance::None,
+        };
+    }
+    let offset = match Parser::read_indented_block(
+        src_lines,
+        line_cursor.relative_offset(),
+        false,
+        true,
+        Some(body_indent),
+        Some(body_indent),
+        false,
+    ) {
+        IndentedBlockResult::Ok { offset, .. } => offset,
+        _ => 0,
+    };
+    TransitionResult::Success {
+        doctree: doctree,
+        push_or_pop: PushOrPop::Neither,
+        line_advance: LineAdvance::Some(offset),
+    }
+}
+
 /// Parses the given `&str` for the directive key,
 /// difficulty and maximum points.
 /// Empty strings are returned for every missing part.
+/// Invalid arguments are reported as a warning on the given directive line.
 fn aplus_key_difficulty_and_max_points(
     arg_str: &str,
-    line_cursor: &mut LineCursor,
+    doctree: &mut DocTree,
+    directive_line: usize,
 ) -> (String, String, String) {
     use regex::Regex;
 
@@ -3895,7 +6305,389 @@ fn aplus_key_difficulty_and_max_points(
         (key, difficulty, max_points)
     } else {
         // No allocations for strings with zero size
-        eprintln!("Either no arguments or invalid argument format for questionnaire preceding line {}...", line_cursor.sum_total());
+        doctree.add_system_message(
+            SystemMessageLevel::Warning,
+            Some(directive_line),
+            format!("Invalid A+ exercise arguments \"{}\". Ignoring.", arg_str),
+        );
         (String::new(), String::new(), String::new())
     }
 }
+
+/// Records the key of an A+ exercise defined on the given directive line.
+/// A key that is already in use in the document is reported as an error.
+fn register_aplus_exercise_key(doctree: &mut DocTree, key: &str, directive_line: usize) {
+    if key.is_empty() {
+        return;
+    }
+    if let Some(previous_line) = doctree.register_aplus_exercise_key(key, directive_line) {
+        doctree.add_system_message(
+            SystemMessageLevel::Error,
+            Some(directive_line),
+            format!(
+                "Duplicate A+ exercise key \"{}\". The key is already used on line {}.",
+                key, previous_line
+            ),
+        );
+    }
+}
+
+/// Stores the source text of an assignment on the A+ question the given tree is focused on.
+fn set_aplus_question_assignment(doctree: &mut DocTree, text: String) {
+    match doctree.mut_node_data() {
+        TreeNodeType::AplusPickOne { assignment, .. }
+        | TreeNodeType::AplusPickAny { assignment, .. }
+        | TreeNodeType::AplusFreeText { assignment, .. } => *assignment = text,
+        _ => {}
+    }
+}
+
+/// Checks whether the given tree is focused on an A+ feedback questionnaire.
+fn in_aplus_feedback_questionnaire(doctree: &DocTree) -> bool {
+    if let TreeNodeType::AplusQuestionnaire { feedback: Some(_), .. } = doctree.shared_node_data() {
+        true
+    } else {
+        false
+    }
+}
+
+/// Removes the feedback options from the given A+ questionnaire options and returns
+/// the kind of feedback questionnaire they define, if any.
+/// The more specific options, such as `chapter-feedback`, take precedence over plain `feedback`.
+fn aplus_feedback_kind(options: &mut HashMap<String, String>) -> Option<AplusFeedbackKind> {
+    let mut kind = None;
+    for (option, option_kind) in AplusFeedbackKind::OPTIONS.iter() {
+        if options.remove(*option).is_some() {
+            kind = Some(*option_kind);
+        }
+    }
+    kind
+}
+
+/// Reads the file included by a Sphinx `literalinclude` directive relative to the given folder,
+/// and applies the filtering options `diff`, `start-after`, `start-at`, `end-before`, `end-at`,
+/// `lines`, `dedent`, `prepend` and `append` to its contents in the order Sphinx does.
+/// The options `dedent` and `diff` are left in the given option map.
+fn read_literalinclude_text(
+    folder: &str,
+    file_name: &str,
+    options: &mut HashMap<String, String>,
+) -> Result<String, String> {
+
+    let read_lines = |file_name: &str| -> Result<Vec<String>, String> {
+        let mut path = std::path::PathBuf::from(folder);
+        path.push(file_name.trim_start_matches('/'));
+        match std::fs::read_to_string(&path) {
+            Ok(contents) => Ok(contents.lines().map(|line| line.to_string()).collect()),
+            Err(e) => Err(format!("{} ({:?})", e, path)),
+        }
+    };
+
+    let mut lines = read_lines(file_name)?;
+
+    // A diff replaces the contents with the differences between the two files.
+    if let Some(diff_file_name) = options.get("diff") {
+        let old_lines = read_lines(diff_file_name.trim())?;
+        let diff = unified_diff(&old_lines, &lines, diff_file_name.trim(), file_name);
+        return Ok(diff.join("\n") + "\n");
+    }
+
+    if let Some(pattern) = options.remove("start-after") {
+        match lines.iter().position(|line| line.contains(pattern.as_str())) {
+            Some(index) => { lines.drain(..index + 1); },
+            None => return Err(format!("start-after pattern \"{}\" not found", pattern)),
+        }
+    } else if let Some(pattern) = options.remove("start-at") {
+        match lines.iter().position(|line| line.contains(pattern.as_str())) {
+            Some(index) => { lines.drain(..index); },
+            None => return Err(format!("start-at pattern \"{}\" not found", pattern)),
+        }
+    }
+
+    if let Some(pattern) = options.remove("end-before") {
+        match lines.iter().position(|line| line.contains(pattern.as_str())) {
+            Some(index) => lines.truncate(index),
+            None => return Err(format!("end-before pattern \"{}\" not found", pattern)),
+        }
+    } else if let Some(pattern) = options.remove("end-at") {
+        match lines.iter().position(|line| line.contains(pattern.as_str())) {
+            Some(index) => lines.truncate(index + 1),
+            None => return Err(format!("end-at pattern \"{}\" not found", pattern)),
+        }
+    }
+
+    if let Some(line_numbers) = options.remove("lines") {
+        let indices = parse_line_numbers(&line_numbers, lines.len())?;
+        if indices.iter().any(|index| *index >= lines.len()) {
+            return Err(format!("line number specification \"{}\" is out of range", line_numbers))
+        }
+        lines = indices.iter().map(|index| lines[*index].clone()).collect();
+    }
+
+    if let Some(dedent) = options.get("dedent") {
+        let dedent = if dedent.trim().is_empty() {
+            // Remove the common indentation of all non-empty lines
+            lines
+                .iter()
+                .filter(|line| ! line.trim().is_empty())
+                .map(|line| line.chars().take_while(|c| c.is_whitespace()).count())
+                .min()
+                .unwrap_or(0)
+        } else if let Ok(dedent) = dedent.trim().parse::<usize>() {
+            dedent
+        } else {
+            return Err(format!("invalid dedent value \"{}\"", dedent))
+        };
+        lines = lines
+            .iter()
+            .map(|line| {
+                let n_of_whitespace = line.chars().take(dedent).take_while(|c| c.is_whitespace()).count();
+                line.chars().skip(n_of_whitespace).collect::<String>()
+            })
+            .collect();
+    }
+
+    if let Some(prepend) = options.remove("prepend") {
+        lines.insert(0, prepend);
+    }
+    if let Some(append) = options.remove("append") {
+        lines.push(append);
+    }
+
+    Ok(lines.join("\n") + "\n")
+}
+
+/// Parses a Sphinx line number specification such as `1,3,5-10,20-`
+/// into a vector of zero-based line indices. Open ranges extend to the given number of lines.
+fn parse_line_numbers(spec: &str, n_of_lines: usize) -> Result<Vec<usize>, String> {
+
+    let invalid = || format!("invalid line number specification \"{}\"", spec);
+
+    let mut indices = Vec::new();
+    for part in spec.split(",") {
+        let bounds = part.trim().split("-").map(|s| s.trim()).collect::<Vec<&str>>();
+        match bounds.as_slice() {
+            [number] => {
+                match number.parse::<usize>() {
+                    Ok(number) if number > 0 => indices.push(number - 1),
+                    _ => return Err(invalid()),
+                }
+            }
+            [start, end] if ! (start.is_empty() && end.is_empty()) => {
+                let start = if start.is_empty() {
+                    1
+                } else {
+                    start.parse::<usize>().map_err(|_| invalid())?
+                };
+                let end = if end.is_empty() {
+                    std::cmp::max(start, n_of_lines)
+                } else {
+                    end.parse::<usize>().map_err(|_| invalid())?
+                };
+                if start == 0 || start > end {
+                    return Err(invalid());
+                }
+                indices.extend(start - 1..end);
+            }
+            _ => return Err(invalid()),
+        }
+    }
+
+    Ok(indices)
+}
+
+/// Generates the lines of a unified diff with 3 lines of context
+/// between the given old and new lines, in the format used by Sphinx.
+fn unified_diff(old: &[String], new: &[String], old_name: &str, new_name: &str) -> Vec<String> {
+
+    const CONTEXT: usize = 3;
+
+    // Longest common subsequence lengths of the suffixes of both inputs
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                std::cmp::max(lcs[i + 1][j], lcs[i][j + 1])
+            };
+        }
+    }
+
+    // Edit script as (prefix, old index, new index) triplets, where the indices
+    // point to the position of the operation in each input.
+    let mut edits: Vec<(char, usize, usize)> = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            edits.push((' ', i, j));
+            i += 1;
+            j += 1;
+        } else if i < old.len() && (j == new.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            edits.push(('-', i, j));
+            i += 1;
+        } else {
+            edits.push(('+', i, j));
+            j += 1;
+        }
+    }
+
+    let format_range = |start: usize, length: usize| {
+        if length == 1 {
+            format!("{}", start + 1)
+        } else if length == 0 {
+            format!("{},0", start)
+        } else {
+            format!("{},{}", start + 1, length)
+        }
+    };
+
+    let mut diff = vec![format!("--- {}", old_name), format!("+++ {}", new_name)];
+
+    let changes = edits
+        .iter()
+        .enumerate()
+        .filter(|(_, (prefix, _, _))| *prefix != ' ')
+        .map(|(index, _)| index)
+        .collect::<Vec<usize>>();
+
+    // Group changes separated by at most 2 * CONTEXT unchanged lines into hunks
+    let mut hunk_start = 0;
+    let mut hunks: Vec<(usize, usize)> = Vec::new();
+    for (n, change) in changes.iter().enumerate() {
+        if n == 0 || change - changes[n - 1] > 2 * CONTEXT + 1 {
+            if n > 0 {
+                hunks.push((hunk_start, changes[n - 1]));
+            }
+            hunk_start = *change;
+        }
+    }
+    if let Some(change) = changes.last() {
+        hunks.push((hunk_start, *change));
+    }
+
+    for (first_change, last_change) in hunks {
+        let start = first_change.saturating_sub(CONTEXT);
+        let end = std::cmp::min(edits.len(), last_change + CONTEXT + 1);
+        let hunk = &edits[start..end];
+        let old_start = hunk[0].1;
+        let new_start = hunk[0].2;
+        let old_length = hunk.iter().filter(|(prefix, _, _)| *prefix != '+').count();
+        let new_length = hunk.iter().filter(|(prefix, _, _)| *prefix != '-').count();
+        diff.push(format!(
+            "@@ -{} +{} @@",
+            format_range(old_start, old_length),
+            format_range(new_start, new_length)
+        ));
+        for (prefix, i, j) in hunk {
+            let line = if *prefix == '+' { &new[*j] } else { &old[*i] };
+            diff.push(format!("{}{}", prefix, line));
+        }
+    }
+
+    diff
+}
+
+
+/// The shared implementation of the parsers of the Sphinx `sectionauthor` and `codeauthor` directives.
+fn parse_sphinx_author(
+    src_lines: &[String],
+    mut doctree: DocTree,
+    line_cursor: &mut LineCursor,
+    empty_after_marker: bool,
+    first_indent: usize,
+    body_indent: usize,
+    kind: crate::common::SphinxAuthorKind,
+) -> TransitionResult {
+
+    use crate::utf8_to_latex::unicode_text_to_latex;
+
+    let author = if let Some(arg) = scan_directive_arguments(
+        src_lines,
+        line_cursor,
+        body_indent,
+        Some(first_indent),
+        empty_after_marker,
+    ) {
+        arg.join(" ").trim().to_string()
+    } else {
+        return TransitionResult::Failure {
+            message: format!(
+                "Sphinx author directive on line {} is missing the name of the author. Computer says no...",
+                line_cursor.sum_total()
+            ),
+            doctree: doctree,
+        };
+    };
+
+    // The email address is optional and given inside angle brackets after the name
+    let (name, email) = match (author.rfind("<"), author.ends_with(">")) {
+        (Some(email_start), true) if ! author[..email_start].trim().is_empty() => (
+            author[..email_start].trim().to_string(),
+            Some(author[email_start + 1..author.len() - 1].trim().to_string()),
+        ),
+        _ => (author, None),
+    };
+
+    let name = unicode_text_to_latex(name.as_str());
+
+    // Section authors are also recorded on the enclosing section
+    if kind == crate::common::SphinxAuthorKind::Section {
+        if let TreeNodeType::Section { authors, .. } = doctree.mut_node_data() {
+            if ! authors.contains(&name) {
+                authors.push(name.clone());
+            }
+        }
+    }
+
+    doctree = match doctree.push_data(TreeNodeType::SphinxAuthor {
+        kind: kind,
+        name: name,
+        email: email,
+    }) {
+        Ok(tree) => tree,
+        Err(tree) => {
+            return TransitionResult::Failure {
+                message: format!(
+                    "Node insertion error on line {}. Computer says no...",
+                    line_cursor.sum_total()
+                ),
+                doctree: tree,
+            }
+        }
+    };
+
+    TransitionResult::Success {
+        doctree: doctree,
+        push_or_pop: PushOrPop::Neither,
+        line_advance: LineAdvance::None,
+    }
+}
+
+/// Returns the lines of the directive starting at `marker_line` as they appear in the source:
+/// the marker line followed by every line indented more than the marker,
+/// with the indentation of the marker and the trailing empty lines left out.
+fn unknown_directive_source_lines(src_lines: &[String], marker_line: usize) -> Vec<String> {
+    let marker_indent = match src_lines.get(marker_line) {
+        Some(line) => line.chars().take_while(|c| c.is_whitespace()).count(),
+        None => return Vec::new(),
+    };
+
+    let dedent = |line: &String| line.trim_end().chars().skip(marker_indent).collect::<String>();
+
+    let mut source_lines = vec![dedent(&src_lines[marker_line])];
+    for line in src_lines.iter().skip(marker_line + 1) {
+        if line.trim().is_empty() {
+            source_lines.push(String::new());
+        } else if line.chars().take_while(|c| c.is_whitespace()).count() > marker_indent {
+            source_lines.push(dedent(line));
+        } else {
+            break;
+        }
+    }
+    while let Some(true) = source_lines.last().map(|line| line.is_empty()) {
+        source_lines.pop();
+    }
+
+    source_lines
+}
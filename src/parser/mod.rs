@@ -99,6 +99,7 @@ impl <'source> Parser <'source> {
             // Check that we are not stuck parsing the same line over and over again
             if ! line_changed && line_not_changed_count >= 10 {
                 return ParsingResult::Failure {
+                    line: self.line_cursor.sum_total() + 1,
                     message: format!("Line not advanced even after {} iterations of the parsing loop on line {}. Clearly something is amiss...", line_not_changed_count, self.line_cursor.sum_total()),
                     doctree: if let Some(doctree) = self.doctree.take() {
                         doctree
@@ -136,6 +137,7 @@ impl <'source> Parser <'source> {
 
                     State::Failure { .. } => {
                         return ParsingResult::Failure {
+                            line: self.line_cursor.sum_total() + 1,
                             message: String::from("Parsing ended in Failure state...\n"),
                             doctree: if let Some(doctree) = self.doctree.take() {
                                 doctree
@@ -150,6 +152,7 @@ impl <'source> Parser <'source> {
                             transitions_ref
                         } else {
                             return ParsingResult::Failure {
+                                line: self.line_cursor.sum_total() + 1,
                                 message: String::from("No transitions for this state...\n"),
                                 doctree: if let Some(doctree) = self.doctree.take() {
                                     doctree
@@ -178,6 +181,7 @@ impl <'source> Parser <'source> {
                     Some(line) => line,
                     None => {
                         return ParsingResult::Failure {
+                            line: self.line_cursor.sum_total() + 1,
                             message: String::from("Parsing ended prematurely because of an unqualified move past EOF..."),
                             doctree: if let Some(doctree) = self.doctree.take() { doctree } else {
                                 panic!(
@@ -226,6 +230,7 @@ impl <'source> Parser <'source> {
                                         Some(machine) => (),
                                         None => {
                                             return ParsingResult::Failure {
+                                                line: self.line_cursor.sum_total() + 1,
                                                 message: String::from(
                                                     "Can't pop from empty stack...\n",
                                                 ),
@@ -257,8 +262,10 @@ impl <'source> Parser <'source> {
                             Some(doctree)
                         }
                         // Transition failed ⇒ terminate.
+                        // Transitions move the line cursor, so the failure is placed on the line they started from.
                         TransitionResult::Failure { message, doctree } => {
                             return ParsingResult::Failure {
+                                line: line_before_transition + 1,
                                 message: message,
                                 doctree: doctree,
                             }
@@ -282,6 +289,7 @@ impl <'source> Parser <'source> {
                     self.doctree = Some(doctree.focus_on_parent());
                 } else {
                     return ParsingResult::Failure {
+                        line: self.line_cursor.sum_total() + 1,
                         message: format!(
                             "Doctree in possession of transition method after transition on line {}. Computer says no...",
                             self.line_cursor.sum_total()
@@ -386,6 +394,19 @@ impl <'source> Parser <'source> {
         loop {
             match Parser::match_inline_str(&mut doctree, &src_chars) {
                 Some((mut node_data, offset)) => {
+                    // Inline parsers do not know the source line of the messages and references they generate
+                    if let Some(doctree) = &mut doctree {
+                        doctree.set_missing_system_message_lines(line_cursor.sum_total() + 1);
+                    }
+                    for data in node_data.iter_mut() {
+                        match data {
+                            TreeNodeType::SphinxTermReference { line, .. }
+                            | TreeNodeType::SphinxCrossReference { line, .. } if line.is_none() => {
+                                *line = Some(line_cursor.sum_total() + 1)
+                            }
+                            _ => {}
+                        }
+                    }
                     nodes_data.append(&mut node_data);
 
                     // Move iterator to start of next possible match
@@ -470,6 +491,7 @@ impl <'source> Parser <'source> {
             IndentedBlockResult::Ok {lines, minimum_indent, offset, blank_finish } => (lines, offset),
             _ => {
                 return Err(ParsingResult::Failure {
+                    line: line_cursor.sum_total() + 1,
                     message: format!(
                         "Error when reading in a block of text for nested parse in line {}.",
                         line_cursor.sum_total()
@@ -512,8 +534,9 @@ impl <'source> Parser <'source> {
                     line_offset,
                 ))
             }
-            ParsingResult::Failure { message, doctree } => {
+            ParsingResult::Failure { message, line, doctree } => {
                 return Err(ParsingResult::Failure {
+                    line: line,
                     message: format!("Nested parse ended in failure: {}", message),
                     doctree: doctree,
                 })
@@ -38,7 +38,11 @@ pub fn parse_aplus_questionnaire_text(
                 panic!("Error when reading intermediate text in A+ questionnaire on line {}. Computer says no...", line_cursor.sum_total())
             };
 
-            let inline_nodes = match Parser::inline_parse(block_lines.join("\n"), None, line_cursor) {
+            if let TreeNodeType::AplusQuestionnaire { instructions, .. } = doctree.mut_node_data() {
+                instructions.push(block_lines.join("\n"));
+            }
+
+            let inline_nodes = match Parser::inline_parse(block_lines.join("\n"), Some(&mut doctree), line_cursor) {
                 InlineParsingResult::Nodes(nodes) => nodes,
                 _ => panic!(
                     "Could not parse intermediate questionnaire text on line {} for inline nodes. Computer says no...",
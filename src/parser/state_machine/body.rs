@@ -5,6 +5,7 @@ Copyright © 2020 Santtu Söderholm
 */
 
 use super::*;
+use crate::common::SystemMessageLevel;
 use crate::parser::types_and_aliases::IndentedBlockResult;
 
 /// The transition method for matching bullets in `Body` state.
@@ -379,7 +380,7 @@ pub fn footnote(
                         line_cursor.sum_total()
                     )
                 },
-                Err(ParsingResult::Failure { message, doctree }) => return TransitionResult::Failure {
+                Err(ParsingResult::Failure { message, doctree, .. }) => return TransitionResult::Failure {
                     message: format!("Looks like footnote on line {} has no content. Computer says no...", line_cursor.sum_total()),
                     doctree: doctree
                 },
@@ -484,7 +485,7 @@ pub fn citation(
                         line_cursor.sum_total()
                     )
                 },
-                Err(ParsingResult::Failure { message, doctree }) => return TransitionResult::Failure {
+                Err(ParsingResult::Failure { message, doctree, .. }) => return TransitionResult::Failure {
                     message: format!(
                         "Looks like citation on line {} has no content. Computer says no...",
                         line_cursor.sum_total()
@@ -674,7 +675,18 @@ pub fn hyperlink_target(
                     }
                 }
 
-                _ => panic!("Inline parser failed when parsing a hyperlink target on line {} .Computer says no...", line_cursor.sum_total())
+                _ => {
+                    doctree.add_system_message(
+                        SystemMessageLevel::Error,
+                        Some(line_cursor.sum_total() + 1),
+                        format!("Could not parse the hyperlink target \"{}\". Ignoring.", label_as_string),
+                    );
+                    return TransitionResult::Success {
+                        doctree: doctree,
+                        push_or_pop: PushOrPop::Neither,
+                        line_advance: LineAdvance::Some(offset),
+                    };
+                }
 
             };
 
@@ -767,6 +779,10 @@ pub fn directive(
 
     let (body_indent, body_offset) =
         match Parser::indent_on_subsequent_lines(src_lines, line_cursor.relative_offset()) {
+            // A directive without contents is followed by a line that is not indented relative to the marker
+            Some((indent, _)) if indent <= captures.get(1).unwrap().as_str().chars().count() => {
+                (detected_first_indent, 0)
+            }
             Some((indent, offset)) => (indent, offset),
             None => (detected_first_indent, 0), // EOF encountered => stay on same line
         };
@@ -1025,14 +1041,13 @@ pub fn directive(
                     unimplemented!("Citations (plural) directive is mentioned in the rST specification but is not implemented yet.")
                 }
 
-                "meta" => directive_parsers::parse_unknown_directive(
-                    doctree,
+                "meta" => directive_parsers::parse_aplus_meta(
                     src_lines,
+                    doctree,
                     line_cursor,
-                    detected_directive_label.as_str(),
+                    empty_after_marker,
                     detected_first_indent,
                     body_indent,
-                    empty_after_marker,
                 ),
 
                 // MISCELLANEOUS
@@ -1117,74 +1132,53 @@ pub fn directive(
                     empty_after_marker,
                 ),
 
-                "versionadded" => directive_parsers::parse_unknown_directive(
-                    doctree,
+                "versionadded" | "versionchanged" | "deprecated" => directive_parsers::parse_sphinx_version_modified(
                     src_lines,
-                    line_cursor,
-                    detected_directive_label.as_str(),
-                    detected_first_indent,
-                    body_indent,
-                    empty_after_marker,
-                ),
-
-                "versionchanged" => directive_parsers::parse_unknown_directive(
                     doctree,
-                    src_lines,
                     line_cursor,
-                    detected_directive_label.as_str(),
-                    detected_first_indent,
-                    body_indent,
+                    *section_level,
                     empty_after_marker,
-                ),
-
-                "deprecated" => directive_parsers::parse_unknown_directive(
-                    doctree,
-                    src_lines,
-                    line_cursor,
-                    detected_directive_label.as_str(),
                     detected_first_indent,
                     body_indent,
-                    empty_after_marker,
+                    detected_directive_label.as_str(),
                 ),
 
-                "seealso" => directive_parsers::parse_unknown_directive(
-                    doctree,
+                "seealso" => directive_parsers::parse_sphinx_seealso(
                     src_lines,
+                    doctree,
                     line_cursor,
-                    detected_directive_label.as_str(),
+                    *section_level,
+                    empty_after_marker,
                     detected_first_indent,
                     body_indent,
-                    empty_after_marker,
                 ),
 
-                "centered" => directive_parsers::parse_unknown_directive(
-                    doctree,
+                "centered" => directive_parsers::parse_sphinx_centered(
                     src_lines,
+                    doctree,
                     line_cursor,
-                    detected_directive_label.as_str(),
+                    empty_after_marker,
                     detected_first_indent,
                     body_indent,
-                    empty_after_marker,
                 ),
 
-                "hlist" => directive_parsers::parse_unknown_directive(
-                    doctree,
+                "hlist" => directive_parsers::parse_sphinx_hlist(
                     src_lines,
+                    doctree,
                     line_cursor,
-                    detected_directive_label.as_str(),
+                    *section_level,
+                    empty_after_marker,
                     detected_first_indent,
                     body_indent,
-                    empty_after_marker,
                 ),
 
-                "highlight" => directive_parsers::parse_unknown_directive(
-                    doctree,
+                "highlight" => directive_parsers::parse_sphinx_highlight(
                     src_lines,
+                    doctree,
                     line_cursor,
-                    detected_directive_label.as_str(),
+                    empty_after_marker,
                     detected_first_indent,
                     body_indent,
-                    empty_after_marker,
                 ),
 
                 "code-block" | "sourcecode" => directive_parsers::parse_sphinx_code_block(
@@ -1197,54 +1191,50 @@ pub fn directive(
                     Some(detected_first_indent),
                 ),
 
-                "literalinclude" => directive_parsers::parse_unknown_directive(
-                    doctree,
+                "literalinclude" => directive_parsers::parse_sphinx_literalinclude(
                     src_lines,
+                    doctree,
                     line_cursor,
-                    detected_directive_label.as_str(),
+                    empty_after_marker,
                     detected_first_indent,
                     body_indent,
-                    empty_after_marker,
                 ),
 
-                "glossary" => directive_parsers::parse_unknown_directive(
-                    doctree,
+                "glossary" => directive_parsers::parse_sphinx_glossary(
                     src_lines,
+                    doctree,
                     line_cursor,
-                    detected_directive_label.as_str(),
+                    *section_level,
+                    empty_after_marker,
                     detected_first_indent,
                     body_indent,
-                    empty_after_marker,
                 ),
 
-                "sectionauthor" => directive_parsers::parse_unknown_directive(
-                    doctree,
+                "sectionauthor" => directive_parsers::parse_sphinx_sectionauthor(
                     src_lines,
+                    doctree,
                     line_cursor,
-                    detected_directive_label.as_str(),
+                    empty_after_marker,
                     detected_first_indent,
                     body_indent,
-                    empty_after_marker,
                 ),
 
-                "codeauthor" => directive_parsers::parse_unknown_directive(
-                    doctree,
+                "codeauthor" => directive_parsers::parse_sphinx_codeauthor(
                     src_lines,
+                    doctree,
                     line_cursor,
-                    detected_directive_label.as_str(),
+                    empty_after_marker,
                     detected_first_indent,
                     body_indent,
-                    empty_after_marker,
                 ),
 
-                "index" => directive_parsers::parse_unknown_directive(
-                    doctree,
+                "index" => directive_parsers::parse_sphinx_index(
                     src_lines,
+                    doctree,
                     line_cursor,
-                    detected_directive_label.as_str(),
+                    empty_after_marker,
                     detected_first_indent,
                     body_indent,
-                    empty_after_marker,
                 ),
 
                 "only" => directive_parsers::parse_sphinx_only(
@@ -1256,24 +1246,22 @@ pub fn directive(
                     body_indent,
                 ),
 
-                "tabularcolumns" => directive_parsers::parse_unknown_directive(
-                    doctree,
+                "tabularcolumns" => directive_parsers::parse_sphinx_tabularcolumns(
                     src_lines,
+                    doctree,
                     line_cursor,
-                    detected_directive_label.as_str(),
+                    empty_after_marker,
                     detected_first_indent,
                     body_indent,
-                    empty_after_marker,
                 ),
 
-                "productionlist" => directive_parsers::parse_unknown_directive(
-                    doctree,
+                "productionlist" => directive_parsers::parse_sphinx_productionlist(
                     src_lines,
+                    doctree,
                     line_cursor,
-                    detected_directive_label.as_str(),
+                    empty_after_marker,
                     detected_first_indent,
                     body_indent,
-                    empty_after_marker,
                 ),
 
                 // A+-SPECIFIC DIRECTIVES
@@ -1316,14 +1304,13 @@ pub fn directive(
                     body_indent,
                 ),
 
-                "hidden_block" => directive_parsers::parse_unknown_directive(
-                    doctree,
+                "hidden-block" => directive_parsers::parse_aplus_hidden_block(
                     src_lines,
+                    doctree,
                     line_cursor,
-                    detected_directive_label.as_str(),
+                    empty_after_marker,
                     detected_first_indent,
                     body_indent,
-                    empty_after_marker,
                 ),
 
                 "point-of-interest" => directive_parsers::parse_aplus_point_of_interest(
@@ -1337,115 +1324,122 @@ pub fn directive(
                     *section_level,
                 ),
 
-                "annotated" => directive_parsers::parse_unknown_directive(
-                    doctree,
+                "annotated" => directive_parsers::parse_aplus_annotated(
                     src_lines,
+                    doctree,
                     line_cursor,
-                    detected_directive_label.as_str(),
+                    empty_after_marker,
                     detected_first_indent,
                     body_indent,
-                    empty_after_marker,
                 ),
 
-                "lineref-code-block" => directive_parsers::parse_unknown_directive(
-                    doctree,
+                "annotation" => directive_parsers::parse_aplus_annotation(
                     src_lines,
+                    doctree,
                     line_cursor,
-                    detected_directive_label.as_str(),
+                    empty_after_marker,
                     detected_first_indent,
                     body_indent,
-                    empty_after_marker,
                 ),
 
-                "repl-res-count-reset" => directive_parsers::parse_unknown_directive(
-                    doctree,
+                "lineref-code-block" => directive_parsers::parse_aplus_lineref_codeblock(
                     src_lines,
+                    doctree,
                     line_cursor,
-                    detected_directive_label.as_str(),
+                    empty_after_marker,
                     detected_first_indent,
                     body_indent,
-                    empty_after_marker,
                 ),
 
-                "acos-submit" => directive_parsers::parse_unknown_directive(
-                    doctree,
+                "repl" => directive_parsers::parse_aplus_repl(
                     src_lines,
+                    doctree,
                     line_cursor,
-                    detected_directive_label.as_str(),
+                    empty_after_marker,
                     detected_first_indent,
                     body_indent,
-                    empty_after_marker,
                 ),
 
-                "div" => directive_parsers::parse_unknown_directive(
+                "repl-res-count-reset" => directive_parsers::parse_aplus_repl_res_count_reset(
+                    src_lines,
                     doctree,
+                    line_cursor,
+                    empty_after_marker,
+                    detected_first_indent,
+                    body_indent,
+                ),
+
+                "acos-submit" => directive_parsers::parse_aplus_acos_submit(
                     src_lines,
+                    doctree,
                     line_cursor,
-                    detected_directive_label.as_str(),
                     detected_first_indent,
                     body_indent,
                     empty_after_marker,
                 ),
 
-                "styled-topic" => directive_parsers::parse_unknown_directive(
-                    doctree,
+                "div" => directive_parsers::parse_aplus_div(
                     src_lines,
+                    doctree,
                     line_cursor,
-                    detected_directive_label.as_str(),
+                    empty_after_marker,
                     detected_first_indent,
                     body_indent,
+                ),
+
+                "styled-topic" => directive_parsers::parse_aplus_styled_topic(
+                    src_lines,
+                    doctree,
+                    line_cursor,
                     empty_after_marker,
+                    detected_first_indent,
+                    body_indent,
                 ),
 
                 // A+ MEDIA DIRECTIVES
-                "story" => directive_parsers::parse_unknown_directive(
-                    doctree,
+                "story" => directive_parsers::parse_aplus_story(
                     src_lines,
+                    doctree,
                     line_cursor,
-                    detected_directive_label.as_str(),
+                    empty_after_marker,
                     detected_first_indent,
                     body_indent,
-                    empty_after_marker,
                 ),
 
-                "jsvee" => directive_parsers::parse_unknown_directive(
-                    doctree,
+                "jsvee" => directive_parsers::parse_aplus_jsvee(
                     src_lines,
+                    doctree,
                     line_cursor,
-                    detected_directive_label.as_str(),
+                    empty_after_marker,
                     detected_first_indent,
                     body_indent,
-                    empty_after_marker,
                 ),
 
-                "youtube" => directive_parsers::parse_unknown_directive(
-                    doctree,
+                "youtube" => directive_parsers::parse_aplus_youtube(
                     src_lines,
+                    doctree,
                     line_cursor,
-                    detected_directive_label.as_str(),
+                    empty_after_marker,
                     detected_first_indent,
                     body_indent,
-                    empty_after_marker,
                 ),
 
-                "local-video" => directive_parsers::parse_unknown_directive(
-                    doctree,
+                "local-video" => directive_parsers::parse_aplus_local_video(
                     src_lines,
+                    doctree,
                     line_cursor,
-                    detected_directive_label.as_str(),
+                    empty_after_marker,
                     detected_first_indent,
                     body_indent,
-                    empty_after_marker,
                 ),
 
-                "embedded-page" => directive_parsers::parse_unknown_directive(
-                    doctree,
+                "embedded-page" => directive_parsers::parse_aplus_embedded_page(
                     src_lines,
+                    doctree,
                     line_cursor,
-                    detected_directive_label.as_str(),
+                    empty_after_marker,
                     detected_first_indent,
                     body_indent,
-                    empty_after_marker,
                 ),
 
                 _ => directive_parsers::parse_unknown_directive(
@@ -2263,14 +2257,12 @@ fn parse_paragraph(
                 }
                 InlineParsingResult::NoNodes => {
                     doctree = doctree.focus_on_parent();
-                    match doctree.pop_child() {
-                        Some(child) => {
-                            eprintln!(
-                                "Removing an empty paragraph from the tree on line {}...",
-                                line_cursor.sum_total()
-                            );
-                        }
-                        None => {}
+                    if let Some(child) = doctree.pop_child() {
+                        doctree.add_system_message(
+                            SystemMessageLevel::Info,
+                            Some(line_cursor.sum_total() + 1),
+                            String::from("Removed an empty paragraph from the document."),
+                        );
                     };
                     doctree
                 }
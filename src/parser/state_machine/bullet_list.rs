@@ -61,7 +61,7 @@ pub fn bullet(
                     } else {
                         unreachable!("Returned from a nested parsing session on line {} without necessary information. Computer says no...", line_cursor.sum_total())
                     },
-                    Err(ParsingResult::Failure { message, doctree }) => return TransitionResult::Failure {
+                    Err(ParsingResult::Failure { message, doctree, .. }) => return TransitionResult::Failure {
                         message: format!("Looks like bullet list item on line {} has no content.\nComputer says no...", line_cursor.sum_total()),
                         doctree: doctree
                     },
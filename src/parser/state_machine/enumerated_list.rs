@@ -125,7 +125,7 @@ pub fn enumerator(
         ).parse() {
             ParsingResult::EOF {doctree, state_stack}
             | ParsingResult::EmptyStateStack {doctree, state_stack} => (doctree, state_stack),
-            ParsingResult::Failure {doctree, message }  => return TransitionResult::Failure {
+            ParsingResult::Failure {doctree, message, .. }  => return TransitionResult::Failure {
                 message: format!(
                     "Nested parsing failed on line {}.",
                     line_cursor.sum_total()
@@ -35,7 +35,7 @@ pub fn field_marker(
             // Parse the marker for inline nodes
             if *marker_indent == detected_marker_indent {
 
-                let marker_inline_nodes = if let InlineParsingResult::Nodes(nodes_data) = Parser::inline_parse(detected_marker_name.to_string(), None, line_cursor) {
+                let marker_inline_nodes = if let InlineParsingResult::Nodes(nodes_data) = Parser::inline_parse(detected_marker_name.to_string(), Some(&mut doctree), line_cursor) {
                     nodes_data
                 } else {
                     return TransitionResult::Failure {
@@ -76,7 +76,7 @@ pub fn field_marker(
                     } else {
                         unreachable!("Returned from a nested parsing session on line {} without necessary information. Computer says no...", line_cursor.sum_total())
                     },
-                    Err(ParsingResult::Failure { message, doctree }) => return TransitionResult::Failure {
+                    Err(ParsingResult::Failure { message, doctree, .. }) => return TransitionResult::Failure {
                         message: format!("Looks like field list item on line {} has no content. Computer says no... ", line_cursor.sum_total()),
                         doctree: doctree
                     },
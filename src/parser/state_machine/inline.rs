@@ -33,6 +33,7 @@ Copyright © 2020 Santtu Söderholm
 use super::*;
 use crate::common::normalize_refname;
 use crate::common::Reference;
+use crate::common::SystemMessageLevel;
 use utf8_to_latex::unicode_text_to_latex;
 
 /// Parses inline text elements that have identical opening
@@ -155,14 +156,10 @@ pub fn inline_target(
         char_count += lookbehind_len;
     }
 
+    // Without a doctree there is no target stack to push the label into
     if let Some(doctree) = opt_doctree_ref {
         let normalized_label = normalize_refname(content);
         doctree.push_to_internal_target_stack(normalized_label);
-    } else {
-        eprintln!(
-            "No doctree given so cannot process a new internal target \"{}{}{}\"...",
-            markup_start, content, markup_end
-        );
     }
 
     char_count += markup_start_len + content_len + markup_end_len;
@@ -237,7 +234,6 @@ pub fn interpreted_text(
     let lookahead_len = lookahead_str.chars().count();
 
     if !front_role_marker.is_empty() && !back_role_marker.is_empty() {
-        eprintln!("Warning: found both pre- and suffix roles for interpreted text. Returning whole match as inline literal...");
         let match_len = (lookbehind_str.to_string()
             + front_role_marker
             + markup_start_str
@@ -248,7 +244,12 @@ pub fn interpreted_text(
             .count();
         let match_string: String = whole_match.chars().take(match_len).collect();
         return (
-            vec![TreeNodeType::Literal { text: match_string }],
+            vec![problematic(
+                opt_doctree_ref,
+                match_string,
+                SystemMessageLevel::Error,
+                "Multiple roles in interpreted text (both prefix and suffix present; only one allowed).",
+            )],
             match_len,
         );
     }
@@ -315,10 +316,13 @@ pub fn interpreted_text(
         /// [reStructuredText Markup Specification](https://docutils.sourceforge.io/docs/ref/rst/roles.html).
         const DEFAULT_DEFAULT_ROLE: &str = "title-reference";
 
-        eprintln!(
-            "Warning: no role found for interpreted text. Using {}...",
-            DEFAULT_DEFAULT_ROLE
-        );
+        if let Some(doctree) = opt_doctree_ref {
+            doctree.add_system_message(
+                SystemMessageLevel::Info,
+                None,
+                format!("No role given for interpreted text. Using {}.", DEFAULT_DEFAULT_ROLE),
+            );
+        }
         return (
             vec![TreeNodeType::TitleReference {
                 displayed_text: content.to_string(),
@@ -417,20 +421,159 @@ pub fn interpreted_text(
             match_len,
         ),
 
+        "abbreviation" | "ab" | "abbr" => {
+            let (text, explanation) = abbreviation_and_explanation(content);
+            (
+                vec![TreeNodeType::Abbreviation {
+                    text: text,
+                    explanation: explanation,
+                }],
+                match_len,
+            )
+        }
+        "acronym" | "ac" => {
+            let (text, explanation) = abbreviation_and_explanation(content);
+            (
+                vec![TreeNodeType::Acronym {
+                    text: text,
+                    explanation: explanation,
+                }],
+                match_len,
+            )
+        }
+
         // Sphinx-specific roles
-        // "ref" => {
-        //   // TODO: Parse the content string with Parser::inline parse and handle the output accordingly.
-        //   (
-        //     vec![
-        //       TreeNodeType::Reference {
-        //         displayed_text: Some(content.to_string()),
-        //         reference: crate::common::Reference::Internal(normalize_refname(content))
-        //       }
-        //     ], match_len
-        //   )
-        // }
+        "index" => {
+            let (title, target) = explicit_title_and_target(content);
+            // Without an explicit title, the content is a single entry that is also displayed
+            let (displayed_text, entries) = if title.as_str() == content.trim() {
+                let (main, term) = if title.starts_with("!") {
+                    (true, title[1..].trim_start().to_string())
+                } else {
+                    (false, title)
+                };
+                let entries = vec![crate::common::SphinxIndexEntry {
+                    kind: crate::common::SphinxIndexEntryKind::Single,
+                    terms: vec![term.clone()],
+                    main: main,
+                }];
+                (term, entries)
+            } else {
+                match crate::common::parse_index_entries(target.as_str()) {
+                    Ok(entries) => (title, entries),
+                    Err(message) => {
+                        let message = format!("{} in an :index: role. Ignoring the entry.", message);
+                        if let Some(doctree) = opt_doctree_ref {
+                            doctree.add_system_message(SystemMessageLevel::Warning, None, message);
+                        }
+                        (title, Vec::new())
+                    }
+                }
+            };
+            (
+                vec![TreeNodeType::SphinxInlineIndex {
+                    displayed_text: unicode_text_to_latex(displayed_text.as_str()),
+                    entries: entries,
+                }],
+                match_len,
+            )
+        }
+        "kbd" => (
+            vec![TreeNodeType::SphinxKeyboard {
+                keys: keyboard_keys(content),
+            }],
+            match_len,
+        ),
+        "file" => (
+            vec![TreeNodeType::SphinxFile {
+                segments: sample_segments(content),
+            }],
+            match_len,
+        ),
+        "samp" => (
+            vec![TreeNodeType::SphinxSample {
+                segments: sample_segments(content),
+            }],
+            match_len,
+        ),
+        "command" => (
+            vec![TreeNodeType::SphinxCommand {
+                text: unicode_text_to_latex(content),
+            }],
+            match_len,
+        ),
+        "guilabel" => (
+            vec![TreeNodeType::SphinxGUILabel {
+                text: gui_label_text(content),
+            }],
+            match_len,
+        ),
+        "menuselection" => (
+            vec![TreeNodeType::SphinxMenuSelection {
+                items: content
+                    .split("-->")
+                    .map(|item| gui_label_text(item.trim()))
+                    .collect(),
+            }],
+            match_len,
+        ),
+        "term" => {
+            let (displayed_text, term) = explicit_title_and_target(content);
+            (
+                vec![TreeNodeType::SphinxTermReference {
+                    displayed_text: displayed_text,
+                    target_label: crate::common::glossary_term_label(term.as_str()),
+                    line: None,
+                }],
+                match_len,
+            )
+        }
+        "lineref" => {
+            use crate::common::SphinxCrossReferenceKind;
+            let (title, target) = explicit_title_and_target(content);
+            // Without an explicit title, the number of the referenced line is displayed
+            let displayed_text = if title.as_str() == content.trim() {
+                None
+            } else {
+                Some(title)
+            };
+            (
+                vec![TreeNodeType::SphinxCrossReference {
+                    kind: SphinxCrossReferenceKind::LineRef,
+                    displayed_text: displayed_text,
+                    target_label: crate::common::lineref_label(target.as_str()),
+                    line: None,
+                }],
+                match_len,
+            )
+        }
+        "ref" | "doc" | "numref" | "eq" => {
+            use crate::common::SphinxCrossReferenceKind;
+            let kind = match role {
+                "ref" => SphinxCrossReferenceKind::Ref,
+                "doc" => SphinxCrossReferenceKind::Doc,
+                "numref" => SphinxCrossReferenceKind::NumRef,
+                _ => SphinxCrossReferenceKind::Eq,
+            };
+            let (title, target) = explicit_title_and_target(content);
+            // A missing title is filled in after parsing, once the targets are known
+            let displayed_text = if title.as_str() == content.trim() {
+                None
+            } else {
+                Some(title)
+            };
+            (
+                vec![TreeNodeType::SphinxCrossReference {
+                    kind: kind,
+                    displayed_text: displayed_text,
+                    target_label: normalize_refname(target.as_str()),
+                    line: None,
+                }],
+                match_len,
+            )
+        }
         _ => {
-            // Unknown role into literal
+            // Unknown roles are errors, and their source is shown as problematic text, as in docutils
             let match_len = (lookbehind_str.to_string()
                 + front_role_marker
                 + markup_start_str
@@ -440,14 +583,15 @@ pub fn interpreted_text(
                 .chars()
                 .count();
             let match_string: String = whole_match.chars().take(match_len).collect();
-            return (
-                vec![
-                    TreeNodeType::InterpretedText {
-                        role: role.to_string(),
-                        content: content.to_string()
-                }],
+            (
+                vec![problematic(
+                    opt_doctree_ref,
+                    match_string,
+                    SystemMessageLevel::Error,
+                    format!("Unknown interpreted text role \"{}\".", role).as_str(),
+                )],
                 match_len,
-            );
+            )
         }
     }
 }
@@ -486,7 +630,6 @@ pub fn simple_ref(
                 if let Some(doctree) = opt_doctree_ref {
                     doctree.next_anon_reference_label()
                 } else {
-                    eprintln!("Warning: detected an automatic reference name but no doctree available to generate one...");
                     normalize_refname(content)
                 }
             }
@@ -579,7 +722,6 @@ pub fn phrase_ref(
             if let Some(doctree) = opt_doctree_ref {
                 Reference::Internal(doctree.next_anon_reference_label())
             } else {
-                eprintln!("Warning: detected an automatic reference name but no doctree available to generate one...");
                 Reference::Internal(normalize_refname(content))
             }
         }
@@ -830,7 +972,6 @@ pub fn substitution_ref(
                 if let Some(doctree) = opt_doctree_ref {
                     doctree.next_anon_reference_label()
                 } else {
-                    eprintln!("Warning: detected an automatic reference name but no doctree available to generate one...");
                     normalize_refname(content)
                 }
             }
@@ -966,7 +1107,16 @@ pub fn uri(
             };
 
             if !path.is_empty() && !has_slash {
-                eprintln!("URI {} has an autority field and a non-empty path that doesn't start with a '/'. URI invalid.", whole_match);
+                if let Some(doctree) = opt_doctree_ref {
+                    doctree.add_system_message(
+                        SystemMessageLevel::Warning,
+                        None,
+                        format!(
+                            "URI \"{}\" has an authority field and a non-empty path that does not start with a \"/\". Writing it as text.",
+                            whole_match
+                        ),
+                    );
+                }
                 is_valid = false;
             }
         }
@@ -1016,6 +1166,27 @@ pub fn text(
 //  Constants and helpers
 // =======================
 
+/// Records a system message about the given inline source text in the doctree
+/// and returns a `Problematic` node linked to it. Without a doctree the message
+/// cannot be recorded, so the source text is simply returned as an inline literal.
+fn problematic(
+    opt_doctree_ref: &mut Option<&mut DocTree>,
+    source_text: String,
+    level: SystemMessageLevel,
+    message: &str,
+) -> TreeNodeType {
+    match opt_doctree_ref {
+        Some(doctree) => {
+            let message_label = doctree.add_system_message(level, None, message.to_string());
+            TreeNodeType::Problematic {
+                text: unicode_text_to_latex(source_text.as_str()),
+                message_label: message_label,
+            }
+        }
+        None => TreeNodeType::Literal { text: source_text },
+    }
+}
+
 /// Checks the two given string slices for matching reStructuredText quotation characters.
 fn quotation_matches(start: &str, end: &str) -> bool {
     for (i, c) in OPENERS.iter().enumerate() {
@@ -1108,3 +1279,139 @@ const DELIMITERS: &[char] = &[
     '\u{ff03}', '\u{ff05}', '-', '\u{ff07}', '\u{ff0a}', '\u{ff0c}', '-', '\u{ff0f}', '\u{ff1a}',
     '\u{ff1b}', '\u{ff1f}', '\u{ff20}', '\u{ff3c}', '\u{ff61}', '\u{ff64}', '\u{ff65}',
 ];
+
+/// Splits the content of a Sphinx cross-referencing role of the form `title <target>`
+/// into the displayed title and the target. If no explicit title is given,
+/// the content works as both.
+fn explicit_title_and_target(content: &str) -> (String, String) {
+    let content = content.trim();
+    if content.ends_with(">") {
+        if let Some(target_start) = content.rfind("<") {
+            let title = content[..target_start].trim();
+            let target = content[target_start + 1..content.len() - 1].trim();
+            if ! title.is_empty() && ! target.is_empty() {
+                return (title.to_string(), target.to_string());
+            }
+        }
+    }
+    (content.to_string(), content.to_string())
+}
+
+
+/// Splits the content of an abbreviation role of the form `ABBR (explanation)`
+/// into the abbreviation and its optional explanation.
+fn abbreviation_and_explanation(content: &str) -> (String, Option<String>) {
+    let content = content.trim();
+    if content.ends_with(")") {
+        if let Some(explanation_start) = content.find(" (") {
+            let text = content[..explanation_start].trim();
+            let explanation = content[explanation_start + 2..content.len() - 1].trim();
+            if ! text.is_empty() && ! explanation.is_empty() {
+                return (
+                    unicode_text_to_latex(text),
+                    Some(unicode_text_to_latex(explanation)),
+                );
+            }
+        }
+    }
+    (unicode_text_to_latex(content), None)
+}
+
+/// Splits the content of a `:kbd:` role into keys and the separators `-`, `+`, `^`
+/// and whitespace between them, in alternating order.
+/// A separator character that is not surrounded by keys is taken to be a key itself,
+/// so that for example `Ctrl+-` is split into `Ctrl`, `+` and `-`.
+fn keyboard_keys(content: &str) -> Vec<String> {
+    let mut keys = Vec::<String>::new();
+    let mut key = String::new();
+    let mut separator = String::new();
+    for c in content.trim().chars() {
+        let is_separator = c == '-' || c == '+' || c == '^' || c.is_whitespace();
+        if is_separator && ! key.is_empty() && separator.is_empty() {
+            keys.push(unicode_text_to_latex(key.as_str()));
+            key.clear();
+            separator.push(if c.is_whitespace() { ' ' } else { c });
+        } else if is_separator && ! c.is_whitespace() && separator == " " {
+            // Spaces around a separator, as in "Ctrl + C"
+            separator = c.to_string();
+        } else if c.is_whitespace() {
+            continue
+        } else {
+            if ! separator.is_empty() {
+                keys.push(unicode_text_to_latex(separator.as_str()));
+                separator.clear();
+            }
+            key.push(c);
+        }
+    }
+    if ! separator.is_empty() {
+        // A trailing separator is the last key
+        key = separator;
+    }
+    if ! key.is_empty() {
+        keys.push(unicode_text_to_latex(key.as_str()));
+    }
+    keys
+}
+
+/// Splits the content of a `:samp:` or `:file:` role into literal and variable segments.
+/// Variables are enclosed in curly braces, and literal braces can be escaped with a backslash.
+fn sample_segments(content: &str) -> Vec<crate::common::SphinxSampleSegment> {
+    use crate::common::SphinxSampleSegment;
+
+    let mut segments = Vec::<SphinxSampleSegment>::new();
+    let mut text = String::new();
+    let mut variable: Option<String> = None;
+    let mut chars = content.chars();
+    while let Some(c) = chars.next() {
+        match (c, variable.as_mut()) {
+            ('\\', _) => {
+                let escaped = chars.next().unwrap_or('\\');
+                match variable.as_mut() {
+                    Some(variable) => variable.push(escaped),
+                    None => text.push(escaped),
+                }
+            }
+            ('{', None) => {
+                if ! text.is_empty() {
+                    segments.push(SphinxSampleSegment::Text(unicode_text_to_latex(text.as_str())));
+                    text.clear();
+                }
+                variable = Some(String::new());
+            }
+            ('}', Some(name)) => {
+                segments.push(SphinxSampleSegment::Variable(unicode_text_to_latex(name.as_str())));
+                variable = None;
+            }
+            (c, Some(name)) => name.push(c),
+            (c, None) => text.push(c),
+        }
+    }
+    if let Some(name) = variable {
+        // An unclosed brace is taken literally
+        text.push('{');
+        text.push_str(name.as_str());
+    }
+    if ! text.is_empty() {
+        segments.push(SphinxSampleSegment::Text(unicode_text_to_latex(text.as_str())));
+    }
+    segments
+}
+
+/// Removes the accelerator key markers `&` from the label of a `:guilabel:` or `:menuselection:` item.
+/// A literal ampersand is written as `&&`.
+fn gui_label_text(content: &str) -> String {
+    let mut label = String::new();
+    let mut chars = content.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '&' {
+            if let Some('&') = chars.peek() {
+                chars.next();
+                label.push('&');
+            }
+        } else {
+            label.push(c);
+        }
+    }
+    unicode_text_to_latex(label.as_str())
+}
@@ -83,9 +83,14 @@ fn parse_indented_literal(
         };
     };
 
+    let language = doctree.shared_highlight_language().cloned();
+    let linenos = doctree.highlight_linenos(literal_string.lines().count());
+
     doctree = match doctree.push_data(
             TreeNodeType::LiteralBlock {
             text: literal_string,
+            language: language,
+            linenos: linenos,
         }
     ) {
         Ok(tree) => tree,
@@ -192,8 +197,15 @@ fn parse_quoted_literal(
         }
     };
 
+    let language = doctree.shared_highlight_language().cloned();
+    let linenos = doctree.highlight_linenos(literal_string.lines().count());
+
     doctree = match doctree.push_data(
-        TreeNodeType::LiteralBlock { text: literal_string }
+        TreeNodeType::LiteralBlock {
+            text: literal_string,
+            language: language,
+            linenos: linenos,
+        }
     ) {
         Ok(tree) => tree,
         Err(tree) => {
@@ -24,8 +24,18 @@ use crate::parser::Parser;
 use crate::parser::state_machine::State;
 
 mod test_admonitions;
+mod test_aplus_annotated;
+mod test_aplus_div;
+mod test_aplus_export;
+mod test_aplus_feedback_questionnaire;
+mod test_aplus_hidden_block;
+mod test_aplus_lineref_code_block;
+mod test_aplus_media;
+mod test_aplus_meta;
 mod test_aplus_point_of_interest;
 mod test_aplus_questionnaire;
+mod test_aplus_repl;
+mod test_aplus_submit;
 mod test_block_quotes;
 mod test_block_reading;
 mod test_bullet_lists;
@@ -44,5 +54,18 @@ mod test_math_blocks;
 mod test_mixed_structures;
 mod test_regexes;
 mod test_sections_and_transitions;
+mod test_sphinx_authors;
+mod test_sphinx_cross_references;
+mod test_sphinx_glossary;
+mod test_sphinx_highlight;
+mod test_sphinx_hlist;
+mod test_sphinx_index;
+mod test_sphinx_inline_markup;
+mod test_sphinx_literalinclude;
 mod test_sphinx_only;
+mod test_sphinx_paragraph_markup;
+mod test_sphinx_productionlist;
+mod test_sphinx_tabularcolumns;
+mod test_system_messages;
 mod test_unknown_directives;
+mod test_utf8_to_latex;
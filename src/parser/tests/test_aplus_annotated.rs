@@ -0,0 +1,195 @@
+/*!
+A submodule for testing the A+ `annotated` and `annotation` directives.
+
+Copyright © 2020 Santtu Söderholm
+*/
+
+use super::*;
+
+#[cfg(test)]
+#[test]
+fn aplus_annotated_01() {
+    let src =
+r#"
+.. annotated::
+
+  .. code-block:: python
+
+    def square(x):«1»
+        return x * x«2»
+
+  .. annotation::
+
+    The function takes a single parameter.
+
+  .. annotation::
+
+    The result is *returned* to the caller.
+
+A paragraph after the code.
+"#
+    .lines()
+    .map(|s| s.to_string())
+    .collect::<Vec<String>>();
+
+    let mut doctree = DocTree::new(PathBuf::from("test"));
+
+    let mut parser = Parser::new(&src, doctree, 0, 0, State::Body, 0);
+
+    doctree = parser.parse().unwrap_tree();
+    doctree = doctree.walk_to_root();
+    doctree.print_tree();
+
+    if let TreeNodeType::AplusAnnotated { code_lines, callouts, n_of_annotations, .. } = doctree
+        .shared_child(0).unwrap().shared_data()
+    {
+        assert_eq!(code_lines, &vec!["def square(x):".to_string(), "    return x * x".to_string()]);
+        assert_eq!(callouts, &vec![(0, 1), (1, 2)]);
+        assert_eq!(*n_of_annotations, 2);
+    } else {
+        panic!()
+    }
+
+    if let TreeNodeType::AplusAnnotation { number, .. } = doctree
+        .shared_child(0).unwrap()
+        .shared_child(1).unwrap().shared_data()
+    {
+        assert_eq!(*number, 2);
+    } else {
+        panic!()
+    }
+
+    if let TreeNodeType::Paragraph { .. } = doctree
+        .shared_child(0).unwrap()
+        .shared_child(1).unwrap()
+        .shared_child(0).unwrap().shared_data()
+    {
+    } else {
+        panic!()
+    }
+
+    if let TreeNodeType::Paragraph { .. } = doctree
+        .shared_child(1).unwrap().shared_data()
+    {
+    } else {
+        panic!()
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn aplus_annotated_02() {
+    let src =
+r#"
+.. annotated::
+
+  x = {"a": 1}«1»
+
+  .. annotation::
+
+    A dictionary literal.
+"#
+    .lines()
+    .map(|s| s.to_string())
+    .collect::<Vec<String>>();
+
+    let mut doctree = DocTree::new(PathBuf::from("test"));
+
+    let mut parser = Parser::new(&src, doctree, 0, 0, State::Body, 0);
+
+    doctree = parser.parse().unwrap_tree();
+    doctree = doctree.walk_to_root();
+    doctree.print_tree();
+
+    if let TreeNodeType::AplusAnnotated { code_lines, callouts, .. } = doctree
+        .shared_child(0).unwrap().shared_data()
+    {
+        assert_eq!(code_lines, &vec![r#"x = {"a": 1}"#.to_string()]);
+        assert_eq!(callouts, &vec![(0, 1)]);
+    } else {
+        panic!()
+    }
+
+    if let TreeNodeType::AplusAnnotation { number, .. } = doctree
+        .shared_child(0).unwrap()
+        .shared_child(0).unwrap().shared_data()
+    {
+        assert_eq!(*number, 1);
+    } else {
+        panic!()
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn aplus_annotation_outside_annotated() {
+    let src =
+r#"
+.. annotation::
+
+  An annotation without code.
+
+Parsing continues here.
+"#
+    .lines()
+    .map(|s| s.to_string())
+    .collect::<Vec<String>>();
+
+    let mut doctree = DocTree::new(PathBuf::from("test"));
+
+    let mut parser = Parser::new(&src, doctree, 0, 0, State::Body, 0);
+
+    doctree = parser.parse().unwrap_tree();
+    doctree = doctree.walk_to_root();
+    doctree.print_tree();
+
+    assert_eq!(doctree.shared_children().unwrap().len(), 1);
+
+    if let TreeNodeType::Paragraph { .. } = doctree
+        .shared_child(0).unwrap().shared_data()
+    {
+    } else {
+        panic!()
+    }
+
+    let messages = doctree.shared_system_messages();
+    assert_eq!(messages.len(), 1);
+    assert_eq!(messages[0].level, crate::common::SystemMessageLevel::Error);
+    assert_eq!(messages[0].line, Some(2));
+}
+
+#[cfg(test)]
+#[test]
+fn aplus_annotated_marker_overflow() {
+    let src =
+r#"
+.. annotated::
+
+  x = 1«99999999999999999999999»
+  y = 2«1»
+
+  .. annotation::
+
+    The second line.
+"#
+    .lines()
+    .map(|s| s.to_string())
+    .collect::<Vec<String>>();
+
+    let mut doctree = DocTree::new(PathBuf::from("test"));
+
+    let mut parser = Parser::new(&src, doctree, 0, 0, State::Body, 0);
+
+    doctree = parser.parse().unwrap_tree();
+    doctree = doctree.walk_to_root();
+    doctree.print_tree();
+
+    if let TreeNodeType::AplusAnnotated { code_lines, callouts, .. } = doctree
+        .shared_child(0).unwrap().shared_data()
+    {
+        assert_eq!(code_lines, &vec!["x = 1«99999999999999999999999»".to_string(), "y = 2".to_string()]);
+        assert_eq!(callouts, &vec![(1, 1)]);
+    } else {
+        panic!()
+    }
+}
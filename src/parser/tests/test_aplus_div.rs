@@ -0,0 +1,129 @@
+/*!
+A submodule for testing the A+ `div` and `styled-topic` directives.
+
+Copyright © 2020 Santtu Söderholm
+*/
+
+use super::*;
+
+#[cfg(test)]
+#[test]
+fn aplus_div_01() {
+    let src =
+r#"
+.. div:: alert alert-info
+
+  A paragraph inside a div.
+
+A paragraph after the div.
+"#
+    .lines()
+    .map(|s| s.to_string())
+    .collect::<Vec<String>>();
+
+    let mut doctree = DocTree::new(PathBuf::from("test"));
+
+    let mut parser = Parser::new(&src, doctree, 0, 0, State::Body, 0);
+
+    doctree = parser.parse().unwrap_tree();
+    doctree = doctree.walk_to_root();
+    doctree.print_tree();
+
+    let div = doctree.shared_child(0).unwrap();
+    if let TreeNodeType::AplusDiv { .. } = div.shared_data() {
+    } else {
+        panic!()
+    }
+    assert_eq!(
+        div.shared_classes().as_ref().unwrap(),
+        &vec!["alert".to_string(), "alert-info".to_string()]
+    );
+
+    if let TreeNodeType::Paragraph { .. } = div.shared_child(0).unwrap().shared_data() {
+    } else {
+        panic!()
+    }
+
+    // The classes are not passed on to the following nodes
+    let paragraph = doctree.shared_child(1).unwrap();
+    if let TreeNodeType::Paragraph { .. } = paragraph.shared_data() {
+        assert!(paragraph.shared_classes().is_none());
+    } else {
+        panic!()
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn aplus_styled_topic_01() {
+    let src =
+r#"
+.. styled-topic:: Remember this
+  :class: bg-warning
+
+  A paragraph inside a styled topic.
+"#
+    .lines()
+    .map(|s| s.to_string())
+    .collect::<Vec<String>>();
+
+    let mut doctree = DocTree::new(PathBuf::from("test"));
+
+    let mut parser = Parser::new(&src, doctree, 0, 0, State::Body, 0);
+
+    doctree = parser.parse().unwrap_tree();
+    doctree = doctree.walk_to_root();
+    doctree.print_tree();
+
+    let topic = doctree.shared_child(0).unwrap();
+    if let TreeNodeType::AplusStyledTopic { title, .. } = topic.shared_data() {
+        assert_eq!(title, "Remember this");
+    } else {
+        panic!()
+    }
+    assert_eq!(topic.shared_classes().as_ref().unwrap(), &vec!["bg-warning".to_string()]);
+
+    if let TreeNodeType::Paragraph { .. } = topic.shared_child(0).unwrap().shared_data() {
+    } else {
+        panic!()
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn aplus_styled_topic_without_title() {
+    let src =
+r#"
+.. styled-topic::
+  :class: bg-warning
+
+  A paragraph inside an untitled topic.
+
+Parsing continues here.
+"#
+    .lines()
+    .map(|s| s.to_string())
+    .collect::<Vec<String>>();
+
+    let mut doctree = DocTree::new(PathBuf::from("test"));
+
+    let mut parser = Parser::new(&src, doctree, 0, 0, State::Body, 0);
+
+    doctree = parser.parse().unwrap_tree();
+    doctree = doctree.walk_to_root();
+    doctree.print_tree();
+
+    assert_eq!(doctree.shared_children().unwrap().len(), 1);
+
+    let paragraph = doctree.shared_child(0).unwrap();
+    if let TreeNodeType::Paragraph { .. } = paragraph.shared_data() {
+        assert!(paragraph.shared_classes().is_none());
+    } else {
+        panic!()
+    }
+
+    let messages = doctree.shared_system_messages();
+    assert_eq!(messages.len(), 1);
+    assert_eq!(messages[0].level, crate::common::SystemMessageLevel::Error);
+    assert_eq!(messages[0].line, Some(2));
+}
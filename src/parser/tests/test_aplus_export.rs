@@ -0,0 +1,199 @@
+/*!
+A submodule for testing the export of A+ questionnaires into grader configurations.
+
+Copyright © 2020 Santtu Söderholm
+*/
+
+use super::*;
+
+#[cfg(test)]
+#[test]
+fn aplus_export_01() {
+    let src =
+r#"
+.. questionnaire:: 1 A
+  :submissions: 4
+  :title: A "quoted" quiz
+
+  .. pick-any:: 10
+    :partial-points:
+
+    Pick the **first** two.
+
+    +*a. first
+    *b. second
+    c. third
+    ?d. neutral
+
+    !a § Pick the first one too.
+
+  .. freetext:: 30 string-ignorews
+    :length: 10
+
+    Type test.
+
+    test
+    regexp:Test|TEST § Use the lower case!
+
+.. questionnaire::
+  :feedback:
+
+  .. freetext::
+    :height: 5
+
+    Any comments?
+"#
+    .lines()
+    .map(|s| s.to_string())
+    .collect::<Vec<String>>();
+
+    let mut folder = std::env::temp_dir();
+    folder.push("rustla_aplus_export_01");
+    let _ = std::fs::remove_dir_all(&folder);
+
+    let mut doctree = DocTree::new(folder.join("chapter.rst"));
+
+    let mut parser = Parser::new(&src, doctree, 0, 0, State::Body, 0);
+
+    doctree = parser.parse().unwrap_tree();
+    doctree = doctree.walk_to_root();
+    doctree.print_tree();
+
+    let export_folder = folder.join("export");
+    let (_, paths) = match doctree.write_aplus_exercises(&export_folder) {
+        Ok(result) => result,
+        Err(message) => panic!("{}", message),
+    };
+
+    assert_eq!(paths, vec![export_folder.join("chapter_1.json"), export_folder.join("chapter_feedback.json")]);
+
+    let quiz = std::fs::read_to_string(&paths[0]).unwrap();
+    assert!(quiz.contains(r#""key": "1","#));
+    assert!(quiz.contains(r#""title": "A \"quoted\" quiz","#));
+    assert!(quiz.contains(r#""max_points": 40,"#));
+    assert!(quiz.contains(r#""max_submissions": 4,"#));
+    assert!(quiz.contains(r#""title": "Pick the **first** two.","#));
+    assert!(quiz.contains(r#""type": "checkbox","#));
+    assert!(quiz.contains(r#""partial_points": true,"#));
+    assert!(quiz.contains(
+r#"            {
+              "value": "a",
+              "label": "first",
+              "correct": true,
+              "selected": true
+            },"#
+    ));
+    assert!(quiz.contains(r#""neutral": true"#));
+    assert!(quiz.contains(r#""value": "Pick the first one too.""#));
+    assert!(quiz.contains(r#""compare_method": "string-ignorews","#));
+    assert!(quiz.contains(r#""model": "test","#));
+    assert!(quiz.contains(r#""label": "regexp:Test|TEST","#));
+
+    let feedback = std::fs::read_to_string(&paths[1]).unwrap();
+    assert!(feedback.contains(r#""key": "feedback","#));
+    assert!(feedback.contains(r#""category": "feedback","#));
+    assert!(feedback.contains(r#""max_points": 0,"#));
+    assert!(feedback.contains(r#""feedback": true,"#));
+    assert!(feedback.contains(r#""type": "textarea","#));
+    assert!(feedback.contains(r#""model": "","#));
+}
+
+#[cfg(test)]
+#[test]
+fn aplus_export_special_characters() {
+    let src =
+r#"
+.. questionnaire:: 1 A
+
+  Answer 100% & more.
+
+  .. pick-one:: 10
+
+    Which path uses a \\ as a separator?
+
+    a. C:\\Users & 50%
+    *b. /home
+
+    a § Not 100% & not \\.
+"#
+    .lines()
+    .map(|s| s.to_string())
+    .collect::<Vec<String>>();
+
+    let mut folder = std::env::temp_dir();
+    folder.push("rustla_aplus_export_special_characters");
+    let _ = std::fs::remove_dir_all(&folder);
+
+    let mut doctree = DocTree::new(folder.join("chapter.rst"));
+
+    let mut parser = Parser::new(&src, doctree, 0, 0, State::Body, 0);
+
+    doctree = parser.parse().unwrap_tree();
+    doctree = doctree.walk_to_root();
+    doctree.print_tree();
+
+    let (_, paths) = match doctree.write_aplus_exercises(&folder) {
+        Ok(result) => result,
+        Err(message) => panic!("{}", message),
+    };
+
+    // The texts are exported as written in the source, without LaTeX escapes
+    let quiz = std::fs::read_to_string(&paths[0]).unwrap();
+    assert!(quiz.contains(r#""instructions": "Answer 100% & more.","#));
+    assert!(quiz.contains(r#""title": "Which path uses a \\\\ as a separator?","#));
+    assert!(quiz.contains(r#""label": "C:\\\\Users & 50%","#));
+    assert!(quiz.contains(r#""value": "Not 100% & not \\\\.""#));
+}
+
+#[cfg(test)]
+#[test]
+fn aplus_export_duplicate_keys() {
+    let src =
+r#"
+.. questionnaire:: 1 A
+
+  .. freetext:: 10
+
+    First?
+
+    first
+
+.. questionnaire:: 1 A
+
+  .. freetext:: 10
+
+    Second?
+
+    second
+"#
+    .lines()
+    .map(|s| s.to_string())
+    .collect::<Vec<String>>();
+
+    let mut folder = std::env::temp_dir();
+    folder.push("rustla_aplus_export_duplicate_keys");
+    let _ = std::fs::remove_dir_all(&folder);
+
+    let mut doctree = DocTree::new(folder.join("chapter.rst"));
+
+    let mut parser = Parser::new(&src, doctree, 0, 0, State::Body, 0);
+
+    doctree = parser.parse().unwrap_tree();
+    doctree = doctree.walk_to_root();
+    doctree.print_tree();
+
+    let messages = doctree.shared_system_messages();
+    assert_eq!(messages.len(), 1);
+    assert_eq!(messages[0].level, crate::common::SystemMessageLevel::Error);
+    assert_eq!(messages[0].line, Some(10));
+
+    let (_, paths) = match doctree.write_aplus_exercises(&folder) {
+        Ok(result) => result,
+        Err(message) => panic!("{}", message),
+    };
+
+    // The second questionnaire does not overwrite the first one
+    assert_eq!(paths, vec![folder.join("chapter_1.json")]);
+    let quiz = std::fs::read_to_string(&paths[0]).unwrap();
+    assert!(quiz.contains(r#""title": "First?","#));
+}
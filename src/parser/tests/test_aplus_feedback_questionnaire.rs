@@ -0,0 +1,194 @@
+/*!
+A submodule for testing A+ feedback questionnaires.
+
+Copyright © 2020 Santtu Söderholm
+*/
+
+use super::*;
+use crate::common::{AplusFeedbackKind, SystemMessageLevel};
+
+#[cfg(test)]
+#[test]
+fn aplus_feedback_questionnaire_01() {
+    let src =
+r#"
+.. questionnaire::
+  :chapter-feedback:
+  :title: Chapter feedback
+
+  .. freetext::
+    :length: 40
+
+    What did you think of this chapter?
+"#
+    .lines()
+    .map(|s| s.to_string())
+    .collect::<Vec<String>>();
+
+    let mut doctree = DocTree::new(PathBuf::from("test"));
+
+    let mut parser = Parser::new(&src, doctree, 0, 0, State::Body, 0);
+
+    doctree = parser.parse().unwrap_tree();
+    doctree = doctree.walk_to_root();
+    doctree.print_tree();
+
+    if let TreeNodeType::AplusQuestionnaire {
+        key, max_points, feedback, category, title, ..
+    } = doctree.shared_child(0).unwrap().shared_data()
+    {
+        assert_eq!(key, "feedback");
+        assert_eq!(*max_points, Some(0));
+        assert_eq!(*feedback, Some(AplusFeedbackKind::Chapter));
+        assert_eq!(feedback.unwrap().css_class(), "chapter-feedback");
+        assert_eq!(category.as_ref().unwrap(), "feedback");
+        assert_eq!(title.as_ref().unwrap(), "Chapter feedback");
+    } else {
+        panic!()
+    }
+
+    assert!(doctree.shared_system_messages().is_empty());
+}
+
+#[cfg(test)]
+#[test]
+fn aplus_feedback_questionnaire_overrides() {
+    let src =
+r#"
+.. questionnaire:: 5
+  :feedback:
+  :category: course
+
+  .. freetext::
+
+    Any comments?
+"#
+    .lines()
+    .map(|s| s.to_string())
+    .collect::<Vec<String>>();
+
+    let mut doctree = DocTree::new(PathBuf::from("test"));
+
+    let mut parser = Parser::new(&src, doctree, 0, 0, State::Body, 0);
+
+    doctree = parser.parse().unwrap_tree();
+    doctree = doctree.walk_to_root();
+    doctree.print_tree();
+
+    if let TreeNodeType::AplusQuestionnaire {
+        key, max_points, feedback, category, ..
+    } = doctree.shared_child(0).unwrap().shared_data()
+    {
+        assert_eq!(key, "feedback");
+        assert_eq!(*max_points, Some(0));
+        assert_eq!(*feedback, Some(AplusFeedbackKind::Feedback));
+        assert_eq!(category.as_ref().unwrap(), "course");
+    } else {
+        panic!()
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn second_aplus_feedback_questionnaire() {
+    let src =
+r#"
+.. questionnaire::
+  :feedback:
+
+  .. freetext::
+
+    Any comments?
+
+.. questionnaire::
+  :weekly-feedback:
+
+  .. pick-one::
+
+    How was the week?
+
+    a. Good
+    b. Bad
+"#
+    .lines()
+    .map(|s| s.to_string())
+    .collect::<Vec<String>>();
+
+    let mut doctree = DocTree::new(PathBuf::from("test"));
+
+    let mut parser = Parser::new(&src, doctree, 0, 0, State::Body, 0);
+
+    doctree = parser.parse().unwrap_tree();
+    doctree = doctree.walk_to_root();
+    doctree.print_tree();
+
+    let messages = doctree.shared_system_messages();
+    assert_eq!(messages.len(), 1);
+    assert_eq!(messages[0].level, SystemMessageLevel::Error);
+    assert_eq!(messages[0].line, Some(9));
+}
+
+#[cfg(test)]
+#[test]
+fn aplus_questionnaire_keyed_feedback() {
+    let src =
+r#"
+.. questionnaire:: feedback 10
+
+  .. pick-one:: 10
+
+    What is 1+1?
+
+    a. 1
+    *b. 2
+
+.. questionnaire::
+  :feedback:
+
+  .. freetext::
+
+    Any comments?
+"#
+    .lines()
+    .map(|s| s.to_string())
+    .collect::<Vec<String>>();
+
+    let mut doctree = DocTree::new(PathBuf::from("test"));
+
+    let mut parser = Parser::new(&src, doctree, 0, 0, State::Body, 0);
+
+    doctree = parser.parse().unwrap_tree();
+    doctree = doctree.walk_to_root();
+    doctree.print_tree();
+
+    // The regular questionnaire took the key of the feedback questionnaire
+    let messages = doctree.shared_system_messages();
+    assert_eq!(messages.len(), 1);
+    assert_eq!(messages[0].level, SystemMessageLevel::Error);
+    assert_eq!(messages[0].line, Some(11));
+}
+
+#[cfg(test)]
+#[test]
+fn aplus_questionnaire_without_arguments() {
+    let src =
+r#"
+.. questionnaire::
+
+  .. freetext::
+
+    Any comments?
+"#
+    .lines()
+    .map(|s| s.to_string())
+    .collect::<Vec<String>>();
+
+    let doctree = DocTree::new(PathBuf::from("test"));
+
+    let mut parser = Parser::new(&src, doctree, 0, 0, State::Body, 0);
+
+    if let crate::common::ParsingResult::Failure { .. } = parser.parse() {
+    } else {
+        panic!()
+    }
+}
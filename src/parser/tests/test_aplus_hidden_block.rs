@@ -0,0 +1,119 @@
+/*!
+A submodule for testing the A+ `hidden-block` directive.
+
+Copyright © 2020 Santtu Söderholm
+*/
+
+use super::*;
+
+#[cfg(test)]
+#[test]
+fn aplus_hidden_block_01() {
+    let src =
+r#"
+.. hidden-block:: solution
+  :label: Show the model solution
+  :visible:
+
+  The answer is 42.
+
+  .. hidden-block:: details
+
+    Nested hidden content.
+
+A paragraph after the block.
+"#
+    .lines()
+    .map(|s| s.to_string())
+    .collect::<Vec<String>>();
+
+    let mut doctree = DocTree::new(PathBuf::from("test"));
+
+    let mut parser = Parser::new(&src, doctree, 0, 0, State::Body, 0);
+
+    doctree = parser.parse().unwrap_tree();
+    doctree = doctree.walk_to_root();
+    doctree.print_tree();
+
+    if let TreeNodeType::AplusHiddenBlock { name, link_text, visible, .. } = doctree
+        .shared_child(0).unwrap().shared_data()
+    {
+        assert_eq!(name, "solution");
+        assert_eq!(link_text.as_ref().unwrap(), "Show the model solution");
+        assert!(*visible);
+    } else {
+        panic!()
+    }
+
+    if let TreeNodeType::Paragraph { .. } = doctree
+        .shared_child(0).unwrap()
+        .shared_child(0).unwrap().shared_data()
+    {
+    } else {
+        panic!()
+    }
+
+    if let TreeNodeType::AplusHiddenBlock { name, link_text, visible, .. } = doctree
+        .shared_child(0).unwrap()
+        .shared_child(1).unwrap().shared_data()
+    {
+        assert_eq!(name, "details");
+        assert!(link_text.is_none());
+        assert!(! *visible);
+    } else {
+        panic!()
+    }
+
+    if let TreeNodeType::Paragraph { .. } = doctree
+        .shared_child(0).unwrap()
+        .shared_child(1).unwrap()
+        .shared_child(0).unwrap().shared_data()
+    {
+    } else {
+        panic!()
+    }
+
+    if let TreeNodeType::Paragraph { .. } = doctree
+        .shared_child(1).unwrap().shared_data()
+    {
+    } else {
+        panic!()
+    }
+}
+
+#[test]
+fn aplus_hidden_block_without_name() {
+    let src =
+r#"
+.. hidden-block::
+
+  Content without a name.
+
+Parsing continues here.
+"#
+    .lines()
+    .map(|s| s.to_string())
+    .collect::<Vec<String>>();
+
+    let mut doctree = DocTree::new(PathBuf::from("test"));
+
+    let mut parser = Parser::new(&src, doctree, 0, 0, State::Body, 0);
+
+    doctree = parser.parse().unwrap_tree();
+    doctree = doctree.walk_to_root();
+    doctree.print_tree();
+
+    assert_eq!(doctree.shared_children().unwrap().len(), 1);
+
+    if let TreeNodeType::Paragraph { .. } = doctree
+        .shared_child(0).unwrap().shared_data()
+    {
+    } else {
+        panic!()
+    }
+
+    let messages = doctree.shared_system_messages();
+    assert_eq!(messages.len(), 1);
+    assert_eq!(messages[0].level, crate::common::SystemMessageLevel::Error);
+    assert_eq!(messages[0].line, Some(2));
+}
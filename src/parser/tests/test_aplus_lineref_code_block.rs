@@ -0,0 +1,145 @@
+/*!
+A submodule for testing the A+ `lineref-code-block` directive and the `:lineref:` role.
+
+Copyright © 2020 Santtu Söderholm
+*/
+
+use super::*;
+use crate::common::{SphinxCrossReferenceKind, SystemMessageLevel};
+
+#[cfg(test)]
+#[test]
+fn aplus_lineref_code_block_01() {
+    let src =
+r#"
+.. lineref-code-block:: scala
+  :linenos:
+
+  def square(x: Int) = ::def::
+    x * x ::body::
+
+The function is defined on line :lineref:`def`
+and its :lineref:`body <body>` follows.
+"#
+    .lines()
+    .map(|s| s.to_string())
+    .collect::<Vec<String>>();
+
+    let mut doctree = DocTree::new(PathBuf::from("test"));
+
+    let mut parser = Parser::new(&src, doctree, 0, 0, State::Body, 0);
+
+    doctree = parser.parse().unwrap_tree();
+    doctree = doctree.walk_to_root();
+    doctree.print_tree();
+
+    if let TreeNodeType::AplusLineRefCodeBlock { language, code_lines, line_labels, .. } = doctree
+        .shared_child(0).unwrap().shared_data()
+    {
+        assert_eq!(language.as_ref().unwrap(), "scala");
+        assert_eq!(
+            code_lines,
+            &vec!["def square(x: Int) =".to_string(), "  x * x".to_string()]
+        );
+        assert_eq!(
+            line_labels,
+            &vec![(0, "lineref-def".to_string()), (1, "lineref-body".to_string())]
+        );
+    } else {
+        panic!()
+    }
+
+    assert!(doctree.shared_targets().contains_key("lineref-def"));
+    assert!(doctree.shared_targets().contains_key("lineref-body"));
+
+    if let TreeNodeType::SphinxCrossReference { kind, displayed_text, target_label, .. } = doctree
+        .shared_child(1).unwrap()
+        .shared_child(12).unwrap().shared_data()
+    {
+        assert_eq!(*kind, SphinxCrossReferenceKind::LineRef);
+        assert!(displayed_text.is_none());
+        assert_eq!(target_label, "lineref-def");
+    } else {
+        panic!()
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn aplus_lineref_markers_end_the_line() {
+    let src =
+r#"
+.. lineref-code-block:: cpp
+
+  auto end = std::string::npos;  ::start::
+  auto other = a::b::c;
+  return end;
+"#
+    .lines()
+    .map(|s| s.to_string())
+    .collect::<Vec<String>>();
+
+    let mut doctree = DocTree::new(PathBuf::from("test"));
+
+    let mut parser = Parser::new(&src, doctree, 0, 0, State::Body, 0);
+
+    doctree = parser.parse().unwrap_tree();
+    doctree = doctree.walk_to_root();
+    doctree.print_tree();
+
+    // The code starts right away, even though it is preceded by an empty line in the source
+    if let TreeNodeType::AplusLineRefCodeBlock { code_lines, line_labels, escape_char, .. } = doctree
+        .shared_child(0).unwrap().shared_data()
+    {
+        assert_eq!(
+            code_lines,
+            &vec![
+                "auto end = std::string::npos;".to_string(),
+                "auto other = a::b::c;".to_string(),
+                "return end;".to_string(),
+            ]
+        );
+        assert_eq!(line_labels, &vec![(0, "lineref-start".to_string())]);
+        assert_eq!(*escape_char, Some('|'));
+    } else {
+        panic!()
+    }
+
+    assert!(! doctree.shared_targets().contains_key("lineref-string"));
+}
+
+#[cfg(test)]
+#[test]
+fn aplus_lineref_without_escape_chars() {
+    let src =
+r#"
+.. lineref-code-block::
+
+  a | b ! c @ d ` e ? f ; g ::all::
+"#
+    .lines()
+    .map(|s| s.to_string())
+    .collect::<Vec<String>>();
+
+    let mut doctree = DocTree::new(PathBuf::from("test"));
+
+    let mut parser = Parser::new(&src, doctree, 0, 0, State::Body, 0);
+
+    doctree = parser.parse().unwrap_tree();
+    doctree = doctree.walk_to_root();
+    doctree.print_tree();
+
+    if let TreeNodeType::AplusLineRefCodeBlock { line_labels, escape_char, .. } = doctree
+        .shared_child(0).unwrap().shared_data()
+    {
+        assert!(line_labels.is_empty());
+        assert!(escape_char.is_none());
+    } else {
+        panic!()
+    }
+
+    let messages = doctree.shared_system_messages();
+    assert_eq!(messages.len(), 1);
+    assert_eq!(messages[0].level, SystemMessageLevel::Warning);
+    assert_eq!(messages[0].line, Some(2));
+}
@@ -0,0 +1,168 @@
+/*!
+A submodule for testing the A+ media directives
+`youtube`, `local-video`, `embedded-page`, `story` and `jsvee`.
+
+Copyright © 2020 Santtu Söderholm
+*/
+
+use super::*;
+
+#[cfg(test)]
+#[test]
+fn aplus_media_01() {
+    let src =
+r#"
+.. youtube:: dQw4w9WgXcQ 3:32
+  :title: An introduction
+
+.. local-video:: videos/lecture.mp4 45:00
+
+.. embedded-page:: https://example.com/widget 800 600
+  :title: A widget
+
+.. story:: stories/first.json
+
+.. jsvee:: recursion
+  :title: Recursion step by step
+"#
+    .lines()
+    .map(|s| s.to_string())
+    .collect::<Vec<String>>();
+
+    // The poster of the local video is found next to the video file
+    let mut folder = std::env::temp_dir();
+    folder.push("rustla_aplus_media_01");
+    std::fs::create_dir_all(folder.join("videos")).unwrap();
+    std::fs::write(folder.join("videos").join("lecture.png"), "").unwrap();
+
+    let mut doctree = DocTree::new(folder.join("document.rst"));
+
+    let mut parser = Parser::new(&src, doctree, 0, 0, State::Body, 0);
+
+    doctree = parser.parse().unwrap_tree();
+    doctree = doctree.walk_to_root();
+    doctree.print_tree();
+
+    if let TreeNodeType::AplusYouTube { video_id, title, duration, poster } = doctree
+        .shared_child(0).unwrap().shared_data()
+    {
+        assert_eq!(video_id, "dQw4w9WgXcQ");
+        assert_eq!(title.as_ref().unwrap(), "An introduction");
+        assert_eq!(duration.as_ref().unwrap(), "3:32");
+        assert!(poster.is_none());
+    } else {
+        panic!()
+    }
+
+    if let TreeNodeType::AplusLocalVideo { video_path, title, duration, poster } = doctree
+        .shared_child(1).unwrap().shared_data()
+    {
+        assert_eq!(video_path, "videos/lecture.mp4");
+        assert!(title.is_none());
+        assert_eq!(duration.as_ref().unwrap(), "45:00");
+        assert_eq!(poster.as_ref().unwrap(), "videos/lecture.png");
+    } else {
+        panic!()
+    }
+
+    if let TreeNodeType::AplusEmbeddedPage { url, title, width, height } = doctree
+        .shared_child(2).unwrap().shared_data()
+    {
+        assert_eq!(url, "https://example.com/widget");
+        assert_eq!(title.as_ref().unwrap(), "A widget");
+        assert_eq!(width.as_ref().unwrap(), "800");
+        assert_eq!(height.as_ref().unwrap(), "600");
+    } else {
+        panic!()
+    }
+
+    if let TreeNodeType::AplusStory { source, .. } = doctree
+        .shared_child(3).unwrap().shared_data()
+    {
+        assert_eq!(source, "stories/first.json");
+    } else {
+        panic!()
+    }
+
+    if let TreeNodeType::AplusJSVee { visualization_id, title } = doctree
+        .shared_child(4).unwrap().shared_data()
+    {
+        assert_eq!(visualization_id, "recursion");
+        assert_eq!(title.as_ref().unwrap(), "Recursion step by step");
+    } else {
+        panic!()
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn aplus_media_details_are_escaped() {
+    let src =
+r#"
+.. embedded-page:: https://example.com/widget
+  :width: 100%
+  :height: 50%
+"#
+    .lines()
+    .map(|s| s.to_string())
+    .collect::<Vec<String>>();
+
+    let mut doctree = DocTree::new(PathBuf::from("test"));
+
+    let mut parser = Parser::new(&src, doctree, 0, 0, State::Body, 0);
+
+    doctree = parser.parse().unwrap_tree();
+    doctree = doctree.walk_to_root();
+    doctree.print_tree();
+
+    if let TreeNodeType::AplusEmbeddedPage { width, height, .. } = doctree
+        .shared_child(0).unwrap().shared_data()
+    {
+        assert_eq!(width.as_ref().unwrap(), "100\\%");
+        assert_eq!(height.as_ref().unwrap(), "50\\%");
+    } else {
+        panic!()
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn aplus_youtube_without_id() {
+    let src =
+r#"
+.. youtube::
+  :title: A video without an id
+
+.. local-video:: lecture.mp4
+
+.. story::
+"#
+    .lines()
+    .map(|s| s.to_string())
+    .collect::<Vec<String>>();
+
+    let mut doctree = DocTree::new(PathBuf::from("test"));
+
+    let mut parser = Parser::new(&src, doctree, 0, 0, State::Body, 0);
+
+    doctree = parser.parse().unwrap_tree();
+    doctree = doctree.walk_to_root();
+    doctree.print_tree();
+
+    assert_eq!(doctree.shared_children().unwrap().len(), 1);
+
+    if let TreeNodeType::AplusLocalVideo { video_path, .. } = doctree
+        .shared_child(0).unwrap().shared_data()
+    {
+        assert_eq!(video_path, "lecture.mp4");
+    } else {
+        panic!()
+    }
+
+    let messages = doctree.shared_system_messages();
+    assert_eq!(messages.len(), 2);
+    assert_eq!(messages[0].level, crate::common::SystemMessageLevel::Error);
+    assert_eq!(messages[0].line, Some(2));
+    assert_eq!(messages[1].level, crate::common::SystemMessageLevel::Error);
+    assert_eq!(messages[1].line, Some(7));
+}
@@ -0,0 +1,239 @@
+/*!
+A submodule for testing the A+ `meta` directive, that holds the settings of an exercise round.
+
+Copyright © 2020 Santtu Söderholm
+*/
+
+use super::*;
+use crate::common::{AplusAudience, AplusDateTime, SystemMessageLevel};
+
+#[cfg(test)]
+#[test]
+fn aplus_meta_01() {
+    let src =
+r#"
+.. meta::
+  :open-time: 2020-09-07 12:00
+  :close-time: 2020-09-21 12:00
+  :late-time: 2020-09-28 12:00
+  :late-penalty: 0.5
+  :audience: registered
+  :points-to-pass: 20
+  :introduction: The first round.
+
+A paragraph.
+"#
+    .lines()
+    .map(|s| s.to_string())
+    .collect::<Vec<String>>();
+
+    let mut doctree = DocTree::new(PathBuf::from("test"));
+
+    let mut parser = Parser::new(&src, doctree, 0, 0, State::Body, 0);
+
+    doctree = parser.parse().unwrap_tree();
+    doctree = doctree.walk_to_root();
+    doctree.print_tree();
+
+    if let TreeNodeType::AplusRoundSettings {
+        open_time, close_time, late_time, late_penalty, audience, hidden, points_to_pass, introduction
+    } = doctree.shared_child(0).unwrap().shared_data()
+    {
+        assert_eq!(
+            open_time.unwrap(),
+            AplusDateTime { year: 2020, month: 9, day: 7, hour: 12, minute: 0, second: 0 }
+        );
+        assert_eq!(close_time.unwrap().day, 21);
+        assert_eq!(late_time.unwrap().day, 28);
+        assert_eq!(*late_penalty, Some(0.5));
+        assert_eq!(*audience, Some(AplusAudience::Registered));
+        assert!(! *hidden);
+        assert_eq!(*points_to_pass, Some(20));
+        assert_eq!(introduction.as_ref().unwrap(), "The first round.");
+    } else {
+        panic!()
+    }
+
+    if let TreeNodeType::Paragraph { .. } = doctree.shared_child(1).unwrap().shared_data() {
+    } else {
+        panic!()
+    }
+
+    assert!(doctree.shared_system_messages().is_empty());
+}
+
+#[cfg(test)]
+#[test]
+fn aplus_meta_invalid_dates() {
+    let src =
+r#"
+.. meta::
+  :open-time: 2021-02-30 12:00
+  :close-time: 2021-03-10
+  :late-time: 2021-03-01
+  :hidden:
+"#
+    .lines()
+    .map(|s| s.to_string())
+    .collect::<Vec<String>>();
+
+    let mut doctree = DocTree::new(PathBuf::from("test"));
+
+    let mut parser = Parser::new(&src, doctree, 0, 0, State::Body, 0);
+
+    doctree = parser.parse().unwrap_tree();
+    doctree = doctree.walk_to_root();
+    doctree.print_tree();
+
+    if let TreeNodeType::AplusRoundSettings { open_time, close_time, hidden, .. } = doctree
+        .shared_child(0).unwrap().shared_data()
+    {
+        assert!(open_time.is_none());
+        assert!(close_time.is_some());
+        assert!(*hidden);
+    } else {
+        panic!()
+    }
+
+    let messages = doctree.shared_system_messages();
+    assert_eq!(messages.len(), 2);
+    assert_eq!(messages[0].level, SystemMessageLevel::Error);
+    assert_eq!(messages[0].line, Some(2));
+    assert_eq!(messages[1].level, SystemMessageLevel::Warning);
+}
+
+#[cfg(test)]
+#[test]
+fn aplus_meta_under_module_title() {
+    let src =
+r#"
+Round 1
+=======
+
+.. meta::
+  :points-to-pass: 10
+
+A paragraph.
+"#
+    .lines()
+    .map(|s| s.to_string())
+    .collect::<Vec<String>>();
+
+    let mut doctree = DocTree::new(PathBuf::from("test"));
+
+    let mut parser = Parser::new(&src, doctree, 0, 0, State::Body, 0);
+
+    doctree = parser.parse().unwrap_tree();
+    doctree = doctree.walk_to_root();
+    doctree.print_tree();
+
+    // The settings are attached to the document root, in front of the module title
+    if let TreeNodeType::AplusRoundSettings { points_to_pass, .. } = doctree
+        .shared_child(0).unwrap().shared_data()
+    {
+        assert_eq!(*points_to_pass, Some(10));
+    } else {
+        panic!()
+    }
+
+    if let TreeNodeType::Section { .. } = doctree.shared_child(1).unwrap().shared_data() {
+    } else {
+        panic!()
+    }
+
+    if let TreeNodeType::Paragraph { .. } = doctree
+        .shared_child(1).unwrap()
+        .shared_child(0).unwrap().shared_data()
+    {
+    } else {
+        panic!()
+    }
+
+    assert!(doctree.shared_system_messages().is_empty());
+}
+
+#[cfg(test)]
+#[test]
+fn aplus_meta_duplicate() {
+    let src =
+r#"
+.. meta::
+  :points-to-pass: 10
+
+Round 1
+=======
+
+.. meta::
+  :points-to-pass: 20
+  :hidden:
+
+A paragraph.
+"#
+    .lines()
+    .map(|s| s.to_string())
+    .collect::<Vec<String>>();
+
+    let mut doctree = DocTree::new(PathBuf::from("test"));
+
+    let mut parser = Parser::new(&src, doctree, 0, 0, State::Body, 0);
+
+    doctree = parser.parse().unwrap_tree();
+    doctree = doctree.walk_to_root();
+    doctree.print_tree();
+
+    // Only the settings of the first directive are kept
+    if let TreeNodeType::AplusRoundSettings { points_to_pass, hidden, .. } = doctree
+        .shared_child(0).unwrap().shared_data()
+    {
+        assert_eq!(*points_to_pass, Some(10));
+        assert!(! *hidden);
+    } else {
+        panic!()
+    }
+
+    if let TreeNodeType::Section { .. } = doctree.shared_child(1).unwrap().shared_data() {
+    } else {
+        panic!()
+    }
+
+    if let TreeNodeType::Paragraph { .. } = doctree
+        .shared_child(1).unwrap()
+        .shared_child(0).unwrap().shared_data()
+    {
+    } else {
+        panic!()
+    }
+
+    let messages = doctree.shared_system_messages();
+    assert_eq!(messages.len(), 1);
+    assert_eq!(messages[0].level, SystemMessageLevel::Error);
+    assert_eq!(messages[0].line, Some(8));
+}
+
+#[cfg(test)]
+#[test]
+fn html_meta_is_ignored() {
+    let src =
+r#"
+.. meta::
+  :keywords: reStructuredText, LaTeX
+
+A paragraph.
+"#
+    .lines()
+    .map(|s| s.to_string())
+    .collect::<Vec<String>>();
+
+    let mut doctree = DocTree::new(PathBuf::from("test"));
+
+    let mut parser = Parser::new(&src, doctree, 0, 0, State::Body, 0);
+
+    doctree = parser.parse().unwrap_tree();
+    doctree = doctree.walk_to_root();
+    doctree.print_tree();
+
+    if let TreeNodeType::Paragraph { .. } = doctree.shared_child(0).unwrap().shared_data() {
+    } else {
+        panic!()
+    }
+}
@@ -0,0 +1,88 @@
+/*!
+A submodule for testing the A+ `repl` and `repl-res-count-reset` directives.
+
+Copyright © 2020 Santtu Söderholm
+*/
+
+use super::*;
+use crate::common::AplusREPLLine;
+
+#[cfg(test)]
+#[test]
+fn aplus_repl_01() {
+    let src =
+r#"
+.. repl::
+
+  scala> 1 + 1
+  res3: Int = 2
+
+  scala> def double(x: Int) =
+       |   x * 2
+  def double(x: Int): Int
+
+.. repl::
+
+  scala> res3 * 2
+  res7: Int = 4
+
+.. repl-res-count-reset::
+
+.. repl::
+
+  scala> 5
+  res2: Int = 5
+"#
+    .lines()
+    .map(|s| s.to_string())
+    .collect::<Vec<String>>();
+
+    let mut doctree = DocTree::new(PathBuf::from("test"));
+
+    let mut parser = Parser::new(&src, doctree, 0, 0, State::Body, 0);
+
+    doctree = parser.parse().unwrap_tree();
+    doctree = doctree.walk_to_root();
+    doctree.print_tree();
+
+    if let TreeNodeType::AplusREPLSession { lines } = doctree
+        .shared_child(0).unwrap().shared_data()
+    {
+        assert_eq!(
+            lines,
+            &vec![
+                AplusREPLLine::Input { prompt: "scala>".to_string(), text: "1 + 1".to_string() },
+                AplusREPLLine::Output("res0: Int = 2".to_string()),
+                AplusREPLLine::Output("".to_string()),
+                AplusREPLLine::Input { prompt: "scala>".to_string(), text: "def double(x: Int) =".to_string() },
+                AplusREPLLine::Input { prompt: "     |".to_string(), text: "  x * 2".to_string() },
+                AplusREPLLine::Output("def double(x: Int): Int".to_string()),
+            ]
+        );
+    } else {
+        panic!()
+    }
+
+    // The numbering continues from the previous session
+    if let TreeNodeType::AplusREPLSession { lines } = doctree
+        .shared_child(1).unwrap().shared_data()
+    {
+        assert_eq!(
+            lines,
+            &vec![
+                AplusREPLLine::Input { prompt: "scala>".to_string(), text: "res0 * 2".to_string() },
+                AplusREPLLine::Output("res1: Int = 4".to_string()),
+            ]
+        );
+    } else {
+        panic!()
+    }
+
+    if let TreeNodeType::AplusREPLSession { lines } = doctree
+        .shared_child(2).unwrap().shared_data()
+    {
+        assert_eq!(lines[1], AplusREPLLine::Output("res0: Int = 5".to_string()));
+    } else {
+        panic!()
+    }
+}
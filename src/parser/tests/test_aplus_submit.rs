@@ -0,0 +1,162 @@
+/*!
+A submodule for testing the A+ `submit` and `acos-submit` directives.
+
+Copyright © 2020 Santtu Söderholm
+*/
+
+use super::*;
+use crate::common::SystemMessageLevel;
+
+#[cfg(test)]
+#[test]
+fn aplus_lti_submit() {
+    let src =
+r#"
+.. submit:: external 50
+  :lti: Rubyric
+  :url: /aalto/12345/
+  :lti_resource_link_id: rubyric-1
+  :lti_open_in_iframe:
+
+  Submit your work to Rubyric.
+"#
+    .lines()
+    .map(|s| s.to_string())
+    .collect::<Vec<String>>();
+
+    let mut doctree = DocTree::new(PathBuf::from("test"));
+
+    let mut parser = Parser::new(&src, doctree, 0, 0, State::Body, 0);
+
+    doctree = parser.parse().unwrap_tree();
+    doctree = doctree.walk_to_root();
+    doctree.print_tree();
+
+    if let TreeNodeType::AplusSubmit {
+        key, max_points, config, url, lti, lti_resource_link_id,
+        lti_open_in_iframe, lti_aplus_get_and_post, ajax, ..
+    } = doctree.shared_child(0).unwrap().shared_data()
+    {
+        assert_eq!(key, "external");
+        assert_eq!(*max_points, 50);
+        assert!(config.is_empty());
+        assert_eq!(url, "/aalto/12345/");
+        assert_eq!(lti, "Rubyric");
+        assert_eq!(lti_resource_link_id, "rubyric-1");
+        assert!(*lti_open_in_iframe);
+        assert!(! *lti_aplus_get_and_post);
+        assert!(! *ajax);
+    } else {
+        panic!()
+    }
+
+    if let TreeNodeType::Paragraph { .. } = doctree
+        .shared_child(0).unwrap()
+        .shared_child(0).unwrap().shared_data()
+    {
+    } else {
+        panic!()
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn aplus_acos_submit() {
+    let src =
+r#"
+.. acos-submit:: jsvee 10
+  :url: /aplus/jsvee/example
+
+  An exercise hosted on the ACOS server.
+"#
+    .lines()
+    .map(|s| s.to_string())
+    .collect::<Vec<String>>();
+
+    let mut doctree = DocTree::new(PathBuf::from("test"));
+
+    let mut parser = Parser::new(&src, doctree, 0, 0, State::Body, 0);
+
+    doctree = parser.parse().unwrap_tree();
+    doctree = doctree.walk_to_root();
+    doctree.print_tree();
+
+    if let TreeNodeType::AplusSubmit { key, url, ajax, .. } = doctree
+        .shared_child(0).unwrap().shared_data()
+    {
+        assert_eq!(key, "jsvee");
+        assert_eq!(url, "/aplus/jsvee/example");
+        assert!(*ajax);
+    } else {
+        panic!()
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn aplus_acos_submit_without_url() {
+    let src =
+r#"
+.. acos-submit:: jsvee 10
+
+  The location of the exercise is missing.
+"#
+    .lines()
+    .map(|s| s.to_string())
+    .collect::<Vec<String>>();
+
+    let doctree = DocTree::new(PathBuf::from("test"));
+
+    let mut parser = Parser::new(&src, doctree, 0, 0, State::Body, 0);
+
+    if let crate::common::ParsingResult::Failure { .. } = parser.parse() {
+    } else {
+        panic!()
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn duplicate_aplus_exercise_keys() {
+    let src =
+r#"
+.. questionnaire:: exercise1 10
+
+  .. pick-one:: 10
+
+    What is 1+1?
+
+    a. 1
+    *b. 2
+
+.. submit:: exercise1 50
+  :config: exercises/exercise1/config.yaml
+
+  Submit your work.
+
+.. submit:: exercise2 50
+  :config: exercises/exercise2/config.yaml
+
+  Submit your other work.
+"#
+    .lines()
+    .map(|s| s.to_string())
+    .collect::<Vec<String>>();
+
+    let mut doctree = DocTree::new(PathBuf::from("test"));
+
+    let mut parser = Parser::new(&src, doctree, 0, 0, State::Body, 0);
+
+    doctree = parser.parse().unwrap_tree();
+    doctree = doctree.walk_to_root();
+    doctree.print_tree();
+
+    let messages = doctree.shared_system_messages();
+    assert_eq!(messages.len(), 1);
+    assert_eq!(messages[0].level, SystemMessageLevel::Error);
+    assert_eq!(messages[0].line, Some(11));
+    assert_eq!(
+        messages[0].text,
+        "Duplicate A+ exercise key \"exercise1\". The key is already used on line 2."
+    );
+}
@@ -61,3 +61,25 @@ fn lower_roman_to_usize_01() {
     assert_eq!(4999, mmmmcmxcix_as_u32);
     assert_eq!(None, over_max_as_u32);
 }
+
+#[test]
+fn str_to_aplus_datetime_01() {
+    use crate::common::AplusDateTime;
+
+    assert_eq!(
+        converters::str_to_aplus_datetime("2020-09-07 12:00"),
+        Some(AplusDateTime { year: 2020, month: 9, day: 7, hour: 12, minute: 0, second: 0 })
+    );
+    assert_eq!(
+        converters::str_to_aplus_datetime("2020-02-29T23:59:59"),
+        Some(AplusDateTime { year: 2020, month: 2, day: 29, hour: 23, minute: 59, second: 59 })
+    );
+    assert_eq!(
+        converters::str_to_aplus_datetime("2021-01-31"),
+        Some(AplusDateTime { year: 2021, month: 1, day: 31, hour: 0, minute: 0, second: 0 })
+    );
+    assert_eq!(converters::str_to_aplus_datetime("2021-02-29"), None);
+    assert_eq!(converters::str_to_aplus_datetime("2021-13-01"), None);
+    assert_eq!(converters::str_to_aplus_datetime("2021-01-01 24:00"), None);
+    assert_eq!(converters::str_to_aplus_datetime("7.9.2020"), None);
+}
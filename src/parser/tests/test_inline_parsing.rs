@@ -639,9 +639,9 @@ The following should produce a title reference: `Söderholm2020`.
 fn interpreted_text_02() {
     let src = String::from(
         r#"
-Interpreted text with two roles produces a literal: :emphasis:`text here`:strong:.
+Interpreted text with two roles is problematic: :emphasis:`text here`:strong:.
 
-Unknown roles also trigger literal text: :role1:`this is a literal`,
+Unknown roles are problematic as well: :role1:`this is problematic`,
 `as is this`:role2:.
 
 "#,
@@ -658,30 +658,39 @@ Unknown roles also trigger literal text: :role1:`this is a literal`,
     doctree = doctree.walk_to_root();
     doctree.print_tree();
 
-    if let TreeNodeType::Literal { text } = doctree
+    if let TreeNodeType::Problematic { text, message_label } = doctree
         .shared_child(0).unwrap()
-        .shared_child(16).unwrap().shared_data() {
+        .shared_child(14).unwrap().shared_data() {
         assert_eq!(text, ":emphasis:`text here`:strong:");
+        assert_eq!(message_label, "system-message-1");
     } else {
         panic!()
     }
 
-    if let TreeNodeType::InterpretedText { role, content } = doctree
+    let messages = doctree.shared_system_messages();
+    assert_eq!(messages[0].level, crate::common::SystemMessageLevel::Error);
+    assert_eq!(messages[0].line, Some(2));
+    assert_eq!(messages[1].level, crate::common::SystemMessageLevel::Error);
+    assert_eq!(messages[1].text, "Unknown interpreted text role \"role1\".");
+    assert_eq!(messages[2].level, crate::common::SystemMessageLevel::Error);
+    assert_eq!(messages[2].text, "Unknown interpreted text role \"role2\".");
+
+    if let TreeNodeType::Problematic { text, message_label } = doctree
         .shared_child(1).unwrap()
         .shared_child(12).unwrap().shared_data()
     {
-        assert_eq!(role, "role1");
-        assert_eq!(content, "this is a literal");
+        assert_eq!(text, ":role1:`this is problematic`");
+        assert_eq!(message_label, "system-message-2");
     } else {
         panic!()
     }
 
-    if let TreeNodeType::InterpretedText { role, content } = doctree
+    if let TreeNodeType::Problematic { text, message_label } = doctree
         .shared_child(1).unwrap()
         .shared_child(15).unwrap().shared_data()
     {
-        assert_eq!(role, "role2");
-        assert_eq!(content, "as is this");
+        assert_eq!(text, "`as is this`:role2:");
+        assert_eq!(message_label, "system-message-3");
     } else {
         panic!()
     }
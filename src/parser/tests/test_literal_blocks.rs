@@ -31,7 +31,7 @@ fn literal_block_01() {
 
     match doctree
         .shared_child(0).unwrap().shared_data() {
-        TreeNodeType::LiteralBlock { text } => {
+        TreeNodeType::LiteralBlock { text, .. } => {
             assert_eq!(text.as_str(), "This is a literal block of text,\nindicated by the \"::\" at the end of last paragraph.")
         }
         _ => panic!(),
@@ -69,7 +69,7 @@ as that of the literal block indicator "::".
 
     match doctree
         .shared_child(0).unwrap().shared_data() {
-        TreeNodeType::LiteralBlock { text } => {
+        TreeNodeType::LiteralBlock { text, .. } => {
             assert_eq!(text.as_str(), "An indented literal block with\nmultiple lines\n\n  Even more indent here.\n\n    And even more...\nReturn to original level of indentation\n")
         }
         _ => panic!(),
@@ -36,6 +36,7 @@ fn math_block_01() {
             math_block,
             name,
             class,
+            ..
         } => {
             assert_eq!(math_block.as_str(), r"\alpha + \beta = \gamma");
             assert_eq!(name.as_ref().unwrap().as_str(), r"name");
@@ -77,6 +78,7 @@ fn math_block_02() {
             math_block: block_text,
             name,
             class,
+            ..
         } => {
             assert_eq!(
                 block_text.as_str(),
@@ -94,6 +96,7 @@ fn math_block_02() {
             math_block: block_text,
             name,
             class,
+            ..
         } => {
             assert_eq!(
                 block_text.as_str(),
@@ -105,3 +108,101 @@ fn math_block_02() {
         _ => panic!(),
     }
 }
+
+#[test]
+fn math_block_03() {
+    let src =
+        r#"
+.. math::
+  :label: Euler
+
+  e^{i\pi} + 1 = 0
+
+.. math::
+  :nowrap:
+
+  \begin{eqnarray}
+    y & = & ax^2 + bx + c
+  \end{eqnarray}
+
+See :eq:`euler`.
+"#
+    .lines()
+    .map(|s| s.to_string())
+    .collect::<Vec<String>>();
+
+    let mut doctree = DocTree::new(PathBuf::from("test"));
+
+    let mut parser = Parser::new(&src, doctree, 0, 0, State::Body, 0);
+
+    doctree = parser.parse().unwrap_tree();
+    doctree = doctree.walk_to_root();
+    doctree.print_tree();
+
+    match doctree
+        .shared_child(0).unwrap().shared_data() {
+        TreeNodeType::MathBlock { math_block, name, nowrap, .. } => {
+            assert_eq!(math_block.as_str(), r"e^{i\pi} + 1 = 0");
+            assert_eq!(name.as_deref(), Some("euler"));
+            assert!(!*nowrap);
+        }
+        _ => panic!(),
+    }
+
+    match doctree
+        .shared_child(1).unwrap().shared_data() {
+        TreeNodeType::MathBlock { name, nowrap, .. } => {
+            assert_eq!(*name, None);
+            assert!(*nowrap);
+        }
+        _ => panic!(),
+    }
+
+    assert!(doctree.shared_targets().contains_key("euler"));
+    assert!(doctree.shared_references().contains_key("euler"));
+}
+
+#[test]
+fn math_block_label_with_multiple_blocks() {
+    let src =
+        r#"
+.. math::
+  :label: euler
+
+  e^{i\pi} + 1 = 0
+
+  e^{i\theta} = \cos\theta + i \sin\theta
+
+See :eq:`euler`.
+"#
+    .lines()
+    .map(|s| s.to_string())
+    .collect::<Vec<String>>();
+
+    let mut doctree = DocTree::new(PathBuf::from("test"));
+
+    let mut parser = Parser::new(&src, doctree, 0, 0, State::Body, 0);
+
+    doctree = parser.parse().unwrap_tree();
+    doctree = doctree.walk_to_root();
+    doctree.print_tree();
+
+    match doctree
+        .shared_child(0).unwrap().shared_data() {
+        TreeNodeType::MathBlock { math_block, name, .. } => {
+            assert_eq!(math_block.as_str(), r"e^{i\pi} + 1 = 0");
+            assert_eq!(name.as_deref(), Some("euler"));
+        }
+        _ => panic!(),
+    }
+
+    match doctree
+        .shared_child(1).unwrap().shared_data() {
+        TreeNodeType::MathBlock { name, .. } => assert_eq!(*name, None),
+        _ => panic!(),
+    }
+
+    assert!(doctree.shared_targets().contains_key("euler"));
+    assert!(!doctree.shared_targets().contains_key("euler1"));
+    assert!(doctree.shared_system_messages().is_empty());
+}
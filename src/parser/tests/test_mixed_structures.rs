@@ -189,7 +189,7 @@ Vaihtoehtoja alkeistapahtumiksi, joihin liittyy :math:`x` onnistumista, on :math
 
     match doctree
         .shared_child(0).unwrap().shared_data() {
-        TreeNodeType::Section {title_text, level, line_style} => {
+        TreeNodeType::Section {title_text, level, line_style, ..} => {
             assert_eq!(title_text, "Binomijakauma");
         }
         _ => panic!()
@@ -241,7 +241,7 @@ Vaihtoehtoja alkeistapahtumiksi, joihin liittyy :math:`x` onnistumista, on :math
     match doctree
         .shared_child(0).unwrap()
         .shared_child(3).unwrap().shared_data() {
-        TreeNodeType::MathBlock { math_block, name, class } => {
+        TreeNodeType::MathBlock { math_block, name, class, .. } => {
             assert_eq!(math_block, "P(X=x)=\\binom{n}{x}p^x(1-p)^{n-x}.");
         }
         _ => panic!()
@@ -249,7 +249,7 @@ Vaihtoehtoja alkeistapahtumiksi, joihin liittyy :math:`x` onnistumista, on :math
     match doctree
         .shared_child(0).unwrap()
         .shared_child(3).unwrap().shared_data() {
-        TreeNodeType::MathBlock { math_block, name, class } => {
+        TreeNodeType::MathBlock { math_block, name, class, .. } => {
             assert_eq!(math_block, "P(X=x)=\\binom{n}{x}p^x(1-p)^{n-x}.");
         }
         _ => panic!()
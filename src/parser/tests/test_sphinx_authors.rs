@@ -0,0 +1,69 @@
+/*!
+A submodule for testing the Sphinx `sectionauthor` and `codeauthor` directives.
+
+Copyright © 2020 Santtu Söderholm
+*/
+
+use super::*;
+use crate::common::SphinxAuthorKind;
+
+#[cfg(test)]
+#[test]
+fn sphinx_authors_01() {
+    let src =
+r#"
+.. codeauthor:: Ada Lovelace
+
+Introduction
+============
+
+.. sectionauthor:: Guido van Rossum <guido@python.org>
+
+.. codeauthor:: Ada Lovelace
+
+A paragraph.
+"#
+    .lines()
+    .map(|s| s.to_string())
+    .collect::<Vec<String>>();
+
+    let mut doctree = DocTree::new(PathBuf::from("test"));
+
+    let mut parser = Parser::new(&src, doctree, 0, 0, State::Body, 0);
+
+    doctree = parser.parse().unwrap_tree();
+    doctree = doctree.walk_to_root();
+    doctree.print_tree();
+
+    if let TreeNodeType::SphinxAuthor { kind, name, email } = doctree
+        .shared_child(0).unwrap().shared_data() {
+        assert_eq!(*kind, SphinxAuthorKind::Code);
+        assert_eq!(name, "Ada Lovelace");
+        assert_eq!(*email, None);
+    } else {
+        panic!()
+    }
+
+    let section = doctree.shared_child(1).unwrap();
+    if let TreeNodeType::Section { authors, .. } = section.shared_data() {
+        assert_eq!(authors, &vec![String::from("Guido van Rossum")]);
+    } else {
+        panic!()
+    }
+
+    if let TreeNodeType::SphinxAuthor { kind, name, email } = section
+        .shared_child(0).unwrap().shared_data() {
+        assert_eq!(*kind, SphinxAuthorKind::Section);
+        assert_eq!(name, "Guido van Rossum");
+        assert_eq!(email.as_deref(), Some("guido@python.org"));
+    } else {
+        panic!()
+    }
+
+    doctree = doctree.perform_restructuredtext_transforms();
+    if let TreeNodeType::Document { authors, .. } = doctree.shared_node_data() {
+        assert_eq!(authors, &vec![String::from("Ada Lovelace"), String::from("Guido van Rossum")]);
+    } else {
+        panic!()
+    }
+}
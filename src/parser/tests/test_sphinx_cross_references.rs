@@ -0,0 +1,70 @@
+/*!
+A submodule for testing the Sphinx cross-referencing roles
+`:ref:`, `:doc:`, `:numref:` and `:eq:`.
+
+Copyright © 2020 Santtu Söderholm
+*/
+
+use super::*;
+use crate::common::SphinxCrossReferenceKind;
+
+#[cfg(test)]
+#[test]
+fn sphinx_cross_references_01() {
+    let src =
+r#"
+.. _intro-label:
+
+Introduction
+============
+
+See :ref:`intro-label`, :ref:`the beginning <Intro-Label>` and :doc:`the guide <guide/index>`.
+
+Then see :numref:`Figure %s <my-figure>`, :numref:`my-figure` and :eq:`euler`.
+"#
+    .lines()
+    .map(|s| s.to_string())
+    .collect::<Vec<String>>();
+
+    let mut doctree = DocTree::new(PathBuf::from("test"));
+
+    let mut parser = Parser::new(&src, doctree, 0, 0, State::Body, 0);
+
+    doctree = parser.parse().unwrap_tree();
+    doctree = doctree.walk_to_root();
+    doctree = doctree.perform_restructuredtext_transforms();
+    doctree.print_tree();
+
+    let paragraph = doctree
+        .shared_child(0).unwrap()
+        .shared_child(0).unwrap();
+
+    if let TreeNodeType::SphinxCrossReference { kind, displayed_text, target_label, .. } = paragraph
+        .shared_child(2).unwrap().shared_data() {
+        assert_eq!(*kind, SphinxCrossReferenceKind::Ref);
+        assert_eq!(displayed_text.as_deref(), Some("Introduction"));
+        assert_eq!(target_label, "intro-label");
+    } else {
+        panic!()
+    }
+
+    if let TreeNodeType::SphinxCrossReference { kind, displayed_text, target_label, .. } = paragraph
+        .shared_child(5).unwrap().shared_data() {
+        assert_eq!(*kind, SphinxCrossReferenceKind::Ref);
+        assert_eq!(displayed_text.as_deref(), Some("the beginning"));
+        assert_eq!(target_label, "intro-label");
+    } else {
+        panic!()
+    }
+
+    // The guide is not a label of this document, so there is nothing to link to
+    if let TreeNodeType::Text { text } = paragraph
+        .shared_child(9).unwrap().shared_data() {
+        assert_eq!(text, "the guide");
+    } else {
+        panic!()
+    }
+
+    assert!(doctree.shared_references().contains_key("my-figure"));
+    assert!(doctree.shared_references().contains_key("euler"));
+}
@@ -0,0 +1,78 @@
+/*!
+A submodule for testing the Sphinx `glossary` directive and the `:term:` role.
+
+Copyright © 2020 Santtu Söderholm
+*/
+
+use super::*;
+
+#[cfg(test)]
+#[test]
+fn sphinx_glossary_01() {
+    let src =
+r#"
+.. glossary::
+   :sorted:
+
+   source directory
+      The directory which, including its subdirectories,
+      contains all source files.
+
+   environment
+      A structure where information about all documents
+      is saved.
+
+See :term:`environment` and :term:`the sources <Source  Directory>`.
+"#
+    .lines()
+    .map(|s| s.to_string())
+    .collect::<Vec<String>>();
+
+    let mut doctree = DocTree::new(PathBuf::from("test"));
+
+    let mut parser = Parser::new(&src, doctree, 0, 0, State::Body, 0);
+
+    doctree = parser.parse().unwrap_tree();
+    doctree = doctree.walk_to_root();
+    doctree.print_tree();
+
+    if let TreeNodeType::SphinxGlossary { sorted, .. } = doctree
+        .shared_child(0).unwrap().shared_data() {
+        assert!(*sorted);
+    } else {
+        panic!()
+    }
+
+    let definition_list = doctree.shared_child(0).unwrap().shared_child(0).unwrap();
+    if let TreeNodeType::DefinitionListItem { term, .. } = definition_list.shared_child(0).unwrap().shared_data() {
+        assert_eq!(term, "environment");
+    } else {
+        panic!()
+    }
+    if let TreeNodeType::DefinitionListItem { term, .. } = definition_list.shared_child(1).unwrap().shared_data() {
+        assert_eq!(term, "source directory");
+    } else {
+        panic!()
+    }
+
+    assert!(doctree.shared_targets().contains_key("term-environment"));
+    assert!(doctree.shared_targets().contains_key("term-source-directory"));
+
+    if let TreeNodeType::SphinxTermReference { displayed_text, target_label, .. } = doctree
+        .shared_child(1).unwrap()
+        .shared_child(2).unwrap().shared_data() {
+        assert_eq!(displayed_text, "environment");
+        assert_eq!(target_label, "term-environment");
+    } else {
+        panic!()
+    }
+
+    if let TreeNodeType::SphinxTermReference { displayed_text, target_label, .. } = doctree
+        .shared_child(1).unwrap()
+        .shared_child(6).unwrap().shared_data() {
+        assert_eq!(displayed_text, "the sources");
+        assert_eq!(target_label, "term-source-directory");
+    } else {
+        panic!()
+    }
+}
@@ -0,0 +1,78 @@
+/*!
+A submodule for testing the Sphinx `highlight` directive.
+
+Copyright © 2020 Santtu Söderholm
+*/
+
+use super::*;
+
+#[cfg(test)]
+#[test]
+fn sphinx_highlight_01() {
+    let src =
+r#"
+::
+
+  print("No language set yet")
+
+.. highlight:: scala
+   :linenothreshold: 3
+
+::
+
+  object Main extends App {
+    println("Hello")
+  }
+
+.. code-block::
+
+  val x = 1
+  val y = 2
+
+.. code-block:: python
+
+  print("Explicit language")
+"#
+    .lines()
+    .map(|s| s.to_string())
+    .collect::<Vec<String>>();
+
+    let mut doctree = DocTree::new(PathBuf::from("test"));
+
+    let mut parser = Parser::new(&src, doctree, 0, 0, State::Body, 0);
+
+    doctree = parser.parse().unwrap_tree();
+    doctree = doctree.walk_to_root();
+    doctree.print_tree();
+
+    if let TreeNodeType::LiteralBlock { language, linenos, .. } = doctree
+        .shared_child(0).unwrap().shared_data() {
+        assert_eq!(*language, None);
+        assert!(! *linenos);
+    } else {
+        panic!()
+    }
+
+    if let TreeNodeType::LiteralBlock { language, linenos, .. } = doctree
+        .shared_child(1).unwrap().shared_data() {
+        assert_eq!(language.as_ref().unwrap(), "scala");
+        assert!(*linenos);
+    } else {
+        panic!()
+    }
+
+    if let TreeNodeType::SphinxCodeBlock { language, linenos, .. } = doctree
+        .shared_child(2).unwrap().shared_data() {
+        assert_eq!(language, "scala");
+        assert!(! *linenos);
+    } else {
+        panic!()
+    }
+
+    if let TreeNodeType::SphinxCodeBlock { language, .. } = doctree
+        .shared_child(3).unwrap().shared_data() {
+        assert_eq!(language, "python");
+    } else {
+        panic!()
+    }
+}
@@ -0,0 +1,121 @@
+/*!
+A submodule for testing the Sphinx `hlist` directive.
+
+Copyright © 2020 Santtu Söderholm
+*/
+
+use super::*;
+
+#[cfg(test)]
+#[test]
+fn sphinx_hlist_01() {
+    let src =
+r#"
+.. hlist::
+   :columns: 2
+
+   * A list of
+   * short items
+   * that should be
+
+     displayed
+   * horizontally
+   * in columns
+
+Paragraph after the list.
+"#
+    .lines()
+    .map(|s| s.to_string())
+    .collect::<Vec<String>>();
+
+    let mut doctree = DocTree::new(PathBuf::from("test"));
+
+    let mut parser = Parser::new(&src, doctree, 0, 0, State::Body, 0);
+
+    doctree = parser.parse().unwrap_tree();
+    doctree = doctree.walk_to_root();
+    doctree.print_tree();
+
+    if let TreeNodeType::SphinxHList { columns, .. } = doctree
+        .shared_child(0).unwrap().shared_data() {
+        assert_eq!(*columns, 2);
+    } else {
+        panic!()
+    }
+
+    let hlist = doctree.shared_child(0).unwrap();
+    assert_eq!(hlist.shared_children().as_ref().unwrap().len(), 2);
+
+    for (column_index, n_of_items) in [3, 2].iter().enumerate() {
+        let column = hlist.shared_child(column_index).unwrap();
+        if let TreeNodeType::SphinxHListColumn { .. } = column.shared_data() {
+        } else {
+            panic!()
+        }
+        let list = column.shared_child(0).unwrap();
+        if let TreeNodeType::BulletList { .. } = list.shared_data() {
+        } else {
+            panic!()
+        }
+        let items = list
+            .shared_children()
+            .as_ref()
+            .unwrap()
+            .iter()
+            .filter(|node| if let TreeNodeType::BulletListItem { .. } = node.shared_data() { true } else { false })
+            .count();
+        assert_eq!(items, *n_of_items);
+    }
+
+    if let TreeNodeType::Paragraph { .. } = doctree
+        .shared_child(1).unwrap().shared_data() {
+    } else {
+        panic!()
+    }
+}
+
+#[test]
+fn sphinx_hlist_02() {
+    let src =
+r#"
+.. hlist::
+   :columns: 3
+
+   - one
+   - two
+   - three
+   - four
+"#
+    .lines()
+    .map(|s| s.to_string())
+    .collect::<Vec<String>>();
+
+    let mut doctree = DocTree::new(PathBuf::from("test"));
+
+    let mut parser = Parser::new(&src, doctree, 0, 0, State::Body, 0);
+
+    doctree = parser.parse().unwrap_tree();
+    doctree = doctree.walk_to_root();
+    doctree.print_tree();
+
+    let hlist = doctree.shared_child(0).unwrap();
+    if let TreeNodeType::SphinxHList { columns, .. } = hlist.shared_data() {
+        assert_eq!(*columns, 3);
+    } else {
+        panic!()
+    }
+
+    for (column_index, n_of_items) in [2, 1, 1].iter().enumerate() {
+        let list = hlist
+            .shared_child(column_index).unwrap()
+            .shared_child(0).unwrap();
+        let items = list
+            .shared_children()
+            .as_ref()
+            .unwrap()
+            .iter()
+            .filter(|node| if let TreeNodeType::BulletListItem { .. } = node.shared_data() { true } else { false })
+            .count();
+        assert_eq!(items, *n_of_items);
+    }
+}
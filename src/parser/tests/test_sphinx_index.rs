@@ -0,0 +1,109 @@
+/*!
+A submodule for testing the Sphinx `index` directive and the `:index:` role.
+
+Copyright © 2020 Santtu Söderholm
+*/
+
+use super::*;
+use crate::common::{SphinxIndexEntry, SphinxIndexEntryKind};
+
+#[cfg(test)]
+#[test]
+fn sphinx_index_01() {
+    let src =
+r#"
+.. index::
+   single: loop; while
+   !pair: loop; statement
+   triple: module; search; path
+   see: iteration; loop
+   seealso: recursion; loop
+   execution, !context
+
+A paragraph about :index:`loops <single: loop; for>` and :index:`!recursion`.
+"#
+    .lines()
+    .map(|s| s.to_string())
+    .collect::<Vec<String>>();
+
+    let mut doctree = DocTree::new(PathBuf::from("test"));
+
+    let mut parser = Parser::new(&src, doctree, 0, 0, State::Body, 0);
+
+    doctree = parser.parse().unwrap_tree();
+    doctree = doctree.walk_to_root();
+    doctree.print_tree();
+
+    if let TreeNodeType::SphinxIndex { entries } = doctree.shared_child(0).unwrap().shared_data() {
+        assert_eq!(entries.len(), 7);
+        assert_eq!(
+            entries[0],
+            SphinxIndexEntry {
+                kind: SphinxIndexEntryKind::Single,
+                terms: vec![String::from("loop"), String::from("while")],
+                main: false,
+            }
+        );
+        assert_eq!(entries[1].kind, SphinxIndexEntryKind::Pair);
+        assert!(entries[1].main);
+        assert_eq!(entries[2].kind, SphinxIndexEntryKind::Triple);
+        assert_eq!(entries[2].terms.len(), 3);
+        assert_eq!(entries[3].kind, SphinxIndexEntryKind::See);
+        assert_eq!(entries[4].kind, SphinxIndexEntryKind::SeeAlso);
+        assert_eq!(entries[5].terms, vec![String::from("execution")]);
+        assert!(!entries[5].main);
+        assert_eq!(entries[6].terms, vec![String::from("context")]);
+        assert!(entries[6].main);
+    } else {
+        panic!()
+    }
+
+    let paragraph = doctree.shared_child(1).unwrap();
+
+    if let TreeNodeType::SphinxInlineIndex { displayed_text, entries } = paragraph
+        .shared_child(6).unwrap().shared_data() {
+        assert_eq!(displayed_text, "loops");
+        assert_eq!(entries[0].terms, vec![String::from("loop"), String::from("for")]);
+    } else {
+        panic!()
+    }
+
+    if let TreeNodeType::SphinxInlineIndex { displayed_text, entries } = paragraph
+        .shared_child(10).unwrap().shared_data() {
+        assert_eq!(displayed_text, "recursion");
+        assert!(entries[0].main);
+    } else {
+        panic!()
+    }
+
+    assert_eq!(doctree.shared_index_entries().len(), 9);
+
+    doctree = doctree.perform_restructuredtext_transforms();
+    if let TreeNodeType::Document { print_index, .. } = doctree.shared_node_data() {
+        assert!(*print_index);
+    } else {
+        panic!()
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn sphinx_index_02() {
+    let src =
+r#"
+.. index:: pair: loop
+"#
+    .lines()
+    .map(|s| s.to_string())
+    .collect::<Vec<String>>();
+
+    let doctree = DocTree::new(PathBuf::from("test"));
+
+    let mut parser = Parser::new(&src, doctree, 0, 0, State::Body, 0);
+
+    if let crate::common::ParsingResult::Failure { line, .. } = parser.parse() {
+        assert_eq!(line, 2);
+    } else {
+        panic!()
+    }
+}
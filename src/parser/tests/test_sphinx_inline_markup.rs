@@ -0,0 +1,109 @@
+/*!
+A submodule for testing the Sphinx inline semantic roles
+`:kbd:`, `:abbr:`, `:file:`, `:command:`, `:guilabel:`, `:menuselection:` and `:samp:`.
+
+Copyright © 2020 Santtu Söderholm
+*/
+
+use super::*;
+use crate::common::SphinxSampleSegment;
+
+#[cfg(test)]
+#[test]
+fn sphinx_inline_markup_01() {
+    let src =
+r#"
+Press :kbd:`Ctrl + C` or :kbd:`C-x C-f` to use a :abbr:`LIFO (last-in, first-out)` :abbr:`API`.
+
+Run :command:`rm` on :file:`/etc/{name}.conf` with :samp:`print(1+{variable})`.
+
+Choose :menuselection:`&Start --> &Programs` and click :guilabel:`Save && &Quit`.
+"#
+    .lines()
+    .map(|s| s.to_string())
+    .collect::<Vec<String>>();
+
+    let mut doctree = DocTree::new(PathBuf::from("test"));
+
+    let mut parser = Parser::new(&src, doctree, 0, 0, State::Body, 0);
+
+    doctree = parser.parse().unwrap_tree();
+    doctree = doctree.walk_to_root();
+    doctree.print_tree();
+
+    let paragraph = doctree.shared_child(0).unwrap();
+
+    if let TreeNodeType::SphinxKeyboard { keys } = paragraph.shared_child(2).unwrap().shared_data() {
+        assert_eq!(keys, &vec!["Ctrl", "+", "C"]);
+    } else {
+        panic!()
+    }
+
+    if let TreeNodeType::SphinxKeyboard { keys } = paragraph.shared_child(6).unwrap().shared_data() {
+        assert_eq!(keys, &vec!["C", "-", "x", " ", "C", "-", "f"]);
+    } else {
+        panic!()
+    }
+
+    if let TreeNodeType::Abbreviation { text, explanation } = paragraph.shared_child(14).unwrap().shared_data() {
+        assert_eq!(text, "LIFO");
+        assert_eq!(explanation.as_deref(), Some("last-in, first-out"));
+    } else {
+        panic!()
+    }
+
+    if let TreeNodeType::Abbreviation { text, explanation } = paragraph.shared_child(16).unwrap().shared_data() {
+        assert_eq!(text, "API");
+        assert_eq!(*explanation, None);
+    } else {
+        panic!()
+    }
+
+    let paragraph = doctree.shared_child(1).unwrap();
+
+    if let TreeNodeType::SphinxCommand { text } = paragraph.shared_child(2).unwrap().shared_data() {
+        assert_eq!(text, "rm");
+    } else {
+        panic!()
+    }
+
+    if let TreeNodeType::SphinxFile { segments } = paragraph.shared_child(6).unwrap().shared_data() {
+        assert_eq!(
+            segments,
+            &vec![
+                SphinxSampleSegment::Text(String::from("/etc/")),
+                SphinxSampleSegment::Variable(String::from("name")),
+                SphinxSampleSegment::Text(String::from(".conf")),
+            ]
+        );
+    } else {
+        panic!()
+    }
+
+    if let TreeNodeType::SphinxSample { segments } = paragraph.shared_child(10).unwrap().shared_data() {
+        assert_eq!(
+            segments,
+            &vec![
+                SphinxSampleSegment::Text(String::from("print(1+")),
+                SphinxSampleSegment::Variable(String::from("variable")),
+                SphinxSampleSegment::Text(String::from(")")),
+            ]
+        );
+    } else {
+        panic!()
+    }
+
+    let paragraph = doctree.shared_child(2).unwrap();
+
+    if let TreeNodeType::SphinxMenuSelection { items } = paragraph.shared_child(2).unwrap().shared_data() {
+        assert_eq!(items, &vec!["Start", "Programs"]);
+    } else {
+        panic!()
+    }
+
+    if let TreeNodeType::SphinxGUILabel { text } = paragraph.shared_child(8).unwrap().shared_data() {
+        assert_eq!(text, "Save \\& Quit");
+    } else {
+        panic!()
+    }
+}
@@ -0,0 +1,157 @@
+/*!
+A submodule for testing the Sphinx `literalinclude` directive.
+
+Copyright © 2020 Santtu Söderholm
+*/
+
+use super::*;
+
+/// Writes the given files into a fresh temporary folder
+/// and returns the path of a (non-existent) document in that folder.
+#[cfg(test)]
+fn literalinclude_test_document(folder_name: &str, files: &[(&str, &str)]) -> PathBuf {
+    let mut folder = std::env::temp_dir();
+    folder.push(folder_name);
+    let _ = std::fs::remove_dir_all(&folder);
+    std::fs::create_dir_all(&folder).unwrap();
+    for (file_name, contents) in files {
+        std::fs::write(folder.join(file_name), contents).unwrap();
+    }
+    folder.join("document.rst")
+}
+
+#[cfg(test)]
+#[test]
+fn sphinx_literalinclude_01() {
+    let src =
+r#"
+.. literalinclude:: example.py
+   :start-after: # start
+   :end-before: # end
+   :dedent: 4
+   :prepend: def main():
+   :emphasize-lines: 2-3
+   :caption: An example
+
+.. literalinclude:: example.py
+   :language: python3
+   :lines: 1, 3-
+   :linenos:
+"#
+    .lines()
+    .map(|s| s.to_string())
+    .collect::<Vec<String>>();
+
+    let document = literalinclude_test_document(
+        "rustla_literalinclude_01",
+        &[("example.py", "# start\n    x = 1\n    print(x)\n# end\n")],
+    );
+
+    let mut doctree = DocTree::new(document);
+
+    let mut parser = Parser::new(&src, doctree, 0, 0, State::Body, 0);
+
+    doctree = parser.parse().unwrap_tree();
+    doctree = doctree.walk_to_root();
+    doctree.print_tree();
+
+    if let TreeNodeType::SphinxCodeBlock { language, code_text, emphasize_lines, caption, .. } = doctree
+        .shared_child(0).unwrap().shared_data() {
+        assert_eq!(language, "python");
+        assert_eq!(code_text, "def main():\nx = 1\nprint(x)\n");
+        assert_eq!(emphasize_lines.as_ref().unwrap(), &vec![2, 3]);
+        assert_eq!(caption.as_ref().unwrap(), "An example");
+    } else {
+        panic!()
+    }
+
+    if let TreeNodeType::SphinxCodeBlock { language, code_text, linenos, .. } = doctree
+        .shared_child(1).unwrap().shared_data() {
+        assert_eq!(language, "python3");
+        assert_eq!(code_text, "# start\n    print(x)\n# end\n");
+        assert!(*linenos);
+    } else {
+        panic!()
+    }
+}
+
+#[test]
+fn sphinx_literalinclude_02() {
+    let src =
+r#"
+.. literalinclude:: new.txt
+   :diff: old.txt
+
+.. literalinclude:: missing.txt
+
+Paragraph after a missing file.
+"#
+    .lines()
+    .map(|s| s.to_string())
+    .collect::<Vec<String>>();
+
+    let document = literalinclude_test_document(
+        "rustla_literalinclude_02",
+        &[
+            ("old.txt", "a\nb\nc\n"),
+            ("new.txt", "a\nB\nc\n"),
+        ],
+    );
+
+    let mut doctree = DocTree::new(document);
+
+    let mut parser = Parser::new(&src, doctree, 0, 0, State::Body, 0);
+
+    doctree = parser.parse().unwrap_tree();
+    doctree = doctree.walk_to_root();
+    doctree.print_tree();
+
+    if let TreeNodeType::SphinxCodeBlock { language, code_text, .. } = doctree
+        .shared_child(0).unwrap().shared_data() {
+        assert_eq!(language, "udiff");
+        assert_eq!(code_text, "--- old.txt\n+++ new.txt\n@@ -1,3 +1,3 @@\n a\n-b\n+B\n c\n");
+    } else {
+        panic!()
+    }
+
+    if let TreeNodeType::Paragraph { .. } = doctree
+        .shared_child(1).unwrap().shared_data() {
+    } else {
+        panic!()
+    }
+}
+
+#[test]
+fn sphinx_literalinclude_missing_file() {
+    let src =
+r#"
+A paragraph before the directive.
+
+.. literalinclude:: missing.py
+   :language: python
+
+Paragraph after a missing file.
+"#
+    .lines()
+    .map(|s| s.to_string())
+    .collect::<Vec<String>>();
+
+    let document = literalinclude_test_document("rustla_literalinclude_missing_file", &[]);
+
+    let mut doctree = DocTree::new(document);
+
+    let mut parser = Parser::new(&src, doctree, 0, 0, State::Body, 0);
+
+    doctree = parser.parse().unwrap_tree();
+    doctree = doctree.walk_to_root();
+    doctree.print_tree();
+
+    assert_eq!(doctree.shared_children().unwrap().len(), 2);
+
+    let messages = doctree.shared_system_messages();
+    assert_eq!(messages.len(), 1);
+    assert_eq!(messages[0].level, crate::common::SystemMessageLevel::Error);
+    assert_eq!(messages[0].line, Some(4));
+    assert!(messages[0].text.starts_with("Could not include \"missing.py\" in a Sphinx \"literalinclude\" directive: "));
+    assert!(messages[0].text.ends_with(". Skipping."));
+}
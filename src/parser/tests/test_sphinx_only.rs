@@ -25,6 +25,9 @@ fn sphinx_only_01() {
     .collect::<Vec<String>>();
 
     let mut doctree = DocTree::new(PathBuf::from("test"));
+    doctree.set_sphinx_only_tags(
+        ["html", "latex"].iter().map(|tag| tag.to_string()).collect()
+    );
 
     let mut parser = Parser::new(&src, doctree, 0, 0, State::Body, 0);
 
@@ -55,3 +58,174 @@ fn sphinx_only_01() {
         panic!()
     }
 }
+
+#[test]
+fn sphinx_only_02() {
+    let src =
+        r#"
+.. only:: teacher
+
+  Model solution.
+
+.. only:: student and not teacher
+
+  Try it yourself first.
+
+.. only:: (html or latex) and not draft
+
+  Included in both editions.
+
+Paragraph
+"#
+    .lines()
+    .map(|s| s.to_string())
+    .collect::<Vec<String>>();
+
+    let mut doctree = DocTree::new(PathBuf::from("test"));
+    doctree.set_sphinx_only_tags(
+        ["latex", "teacher"].iter().map(|tag| tag.to_string()).collect()
+    );
+
+    let mut parser = Parser::new(&src, doctree, 0, 0, State::Body, 0);
+
+    doctree = parser.parse().unwrap_tree();
+    doctree = doctree.walk_to_root();
+    doctree.print_tree();
+
+    if let TreeNodeType::SphinxOnly { expression, .. } = doctree
+        .shared_child(0).unwrap().shared_data() {
+        assert_eq!(expression, "teacher");
+    } else {
+        panic!()
+    }
+
+    if let TreeNodeType::SphinxOnly { expression, .. } = doctree
+        .shared_child(1).unwrap().shared_data() {
+        assert_eq!(expression, "(html or latex) and not draft");
+    } else {
+        panic!()
+    }
+
+    if let TreeNodeType::Paragraph { .. } = doctree
+        .shared_child(2).unwrap().shared_data() {
+    } else {
+        panic!()
+    }
+}
+
+#[test]
+fn sphinx_only_false_branch_registers_nothing() {
+    let src =
+        r#"
+.. only:: html
+
+  .. _hidden-target:
+
+  A hidden paragraph.
+
+  .. questionnaire::
+    :feedback:
+
+    .. freetext::
+
+      Any comments?
+
+.. only:: latex
+
+  .. questionnaire::
+    :feedback:
+
+    .. freetext::
+
+      Any comments?
+
+A reference to :ref:`hidden-target`.
+"#
+    .lines()
+    .map(|s| s.to_string())
+    .collect::<Vec<String>>();
+
+    let mut doctree = DocTree::new(PathBuf::from("test"));
+    doctree.set_sphinx_only_tags(
+        ["latex"].iter().map(|tag| tag.to_string()).collect()
+    );
+
+    let mut parser = Parser::new(&src, doctree, 0, 0, State::Body, 0);
+
+    doctree = parser.parse().unwrap_tree();
+    doctree = doctree.perform_restructuredtext_transforms();
+    doctree.print_tree();
+
+    // The html branch is left out without registering its target or its feedback questionnaire
+    assert!(! doctree.has_target_label("hidden-target"));
+
+    if let TreeNodeType::SphinxOnly { expression, .. } = doctree
+        .shared_child(0).unwrap().shared_data() {
+        assert_eq!(expression, "latex");
+    } else {
+        panic!()
+    }
+
+    if let TreeNodeType::Paragraph { .. } = doctree
+        .shared_child(1).unwrap().shared_data() {
+    } else {
+        panic!()
+    }
+
+    let messages = doctree.shared_system_messages();
+    assert_eq!(messages.len(), 1);
+    assert_eq!(messages[0].level, crate::common::SystemMessageLevel::Warning);
+    assert_eq!(messages[0].text, "Cross-reference to an undefined label \"hidden-target\".");
+}
+
+#[test]
+fn sphinx_only_invalid_expression() {
+    let src =
+        r#"
+.. only:: latex and
+
+  Kept, as the expression cannot be evaluated.
+"#
+    .lines()
+    .map(|s| s.to_string())
+    .collect::<Vec<String>>();
+
+    let mut doctree = DocTree::new(PathBuf::from("test"));
+
+    let mut parser = Parser::new(&src, doctree, 0, 0, State::Body, 0);
+
+    doctree = parser.parse().unwrap_tree();
+    doctree = doctree.walk_to_root();
+    doctree.print_tree();
+
+    if let TreeNodeType::Paragraph { .. } = doctree
+        .shared_child(0).unwrap()
+        .shared_child(0).unwrap().shared_data() {
+    } else {
+        panic!()
+    }
+
+    let messages = doctree.shared_system_messages();
+    assert_eq!(messages.len(), 1);
+    assert_eq!(messages[0].level, crate::common::SystemMessageLevel::Error);
+    assert_eq!(messages[0].line, Some(2));
+}
+
+#[test]
+fn sphinx_only_expressions_01() {
+    use crate::common::evaluate_only_expression;
+
+    let tags = ["latex", "draft"]
+        .iter()
+        .map(|tag| tag.to_string())
+        .collect::<std::collections::HashSet<String>>();
+
+    assert_eq!(evaluate_only_expression("latex", &tags), Ok(true));
+    assert_eq!(evaluate_only_expression("html", &tags), Ok(false));
+    assert_eq!(evaluate_only_expression("html or latex and draft", &tags), Ok(true));
+    assert_eq!(evaluate_only_expression("(html or latex) and not draft", &tags), Ok(false));
+    assert_eq!(evaluate_only_expression("not not latex", &tags), Ok(true));
+    assert!(evaluate_only_expression("latex and", &tags).is_err());
+    assert!(evaluate_only_expression("(latex or html", &tags).is_err());
+    assert!(evaluate_only_expression("latex html", &tags).is_err());
+}
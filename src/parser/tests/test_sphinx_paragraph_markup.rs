@@ -0,0 +1,176 @@
+/*!
+A submodule for testing the Sphinx paragraph-level markup directives
+`versionadded`, `versionchanged`, `deprecated`, `seealso` and `centered`.
+
+Copyright © 2020 Santtu Söderholm
+*/
+
+use super::*;
+
+#[cfg(test)]
+#[test]
+fn sphinx_version_modified_01() {
+    let src =
+r#"
+.. versionadded:: 2.5
+   The *spam* parameter.
+
+.. versionchanged:: 3.0
+
+   The default value was changed.
+
+   * This is part of the explanation as well.
+
+.. deprecated:: 3.1
+
+Paragraph after the version notes.
+"#
+    .lines()
+    .map(|s| s.to_string())
+    .collect::<Vec<String>>();
+
+    let mut doctree = DocTree::new(PathBuf::from("test"));
+
+    let mut parser = Parser::new(&src, doctree, 0, 0, State::Body, 0);
+
+    doctree = parser.parse().unwrap_tree();
+    doctree = doctree.walk_to_root();
+    doctree.print_tree();
+
+    if let TreeNodeType::SphinxVersionAdded { version, .. } = doctree
+        .shared_child(0).unwrap().shared_data() {
+        assert_eq!(version, "2.5");
+        if let TreeNodeType::Paragraph { .. } = doctree
+            .shared_child(0).unwrap()
+            .shared_child(0).unwrap().shared_data() {
+        } else {
+            panic!()
+        }
+    } else {
+        panic!()
+    }
+
+    if let TreeNodeType::SphinxVersionChanged { version, .. } = doctree
+        .shared_child(1).unwrap().shared_data() {
+        assert_eq!(version, "3.0");
+        if let TreeNodeType::Paragraph { .. } = doctree
+            .shared_child(1).unwrap()
+            .shared_child(0).unwrap().shared_data() {
+        } else {
+            panic!()
+        }
+        if let TreeNodeType::BulletList { .. } = doctree
+            .shared_child(1).unwrap()
+            .shared_child(1).unwrap().shared_data() {
+        } else {
+            panic!()
+        }
+    } else {
+        panic!()
+    }
+
+    if let TreeNodeType::SphinxDeprecated { version, .. } = doctree
+        .shared_child(2).unwrap().shared_data() {
+        assert_eq!(version, "3.1");
+        assert!(doctree.shared_child(2).unwrap().shared_children().as_ref().unwrap().is_empty());
+    } else {
+        panic!()
+    }
+
+    if let TreeNodeType::Paragraph { .. } = doctree
+        .shared_child(3).unwrap().shared_data() {
+    } else {
+        panic!()
+    }
+}
+
+#[test]
+fn sphinx_seealso_01() {
+    let src =
+r#"
+.. seealso:: modules zipfile, tarfile
+
+.. seealso::
+
+   Module :py:mod:`zipfile`
+      Documentation of the zipfile standard module.
+
+   `GNU tar manual, Basic Tar Format <http://link>`_
+      Documentation for tar archive files, including GNU tar extensions.
+"#
+    .lines()
+    .map(|s| s.to_string())
+    .collect::<Vec<String>>();
+
+    let mut doctree = DocTree::new(PathBuf::from("test"));
+
+    let mut parser = Parser::new(&src, doctree, 0, 0, State::Body, 0);
+
+    doctree = parser.parse().unwrap_tree();
+    doctree = doctree.walk_to_root();
+    doctree.print_tree();
+
+    if let TreeNodeType::SphinxSeeAlso { .. } = doctree
+        .shared_child(0).unwrap().shared_data() {
+        if let TreeNodeType::Paragraph { .. } = doctree
+            .shared_child(0).unwrap()
+            .shared_child(0).unwrap().shared_data() {
+        } else {
+            panic!()
+        }
+    } else {
+        panic!()
+    }
+
+    if let TreeNodeType::SphinxSeeAlso { .. } = doctree
+        .shared_child(1).unwrap().shared_data() {
+        if let TreeNodeType::DefinitionList { .. } = doctree
+            .shared_child(1).unwrap()
+            .shared_child(0).unwrap().shared_data() {
+        } else {
+            panic!()
+        }
+    } else {
+        panic!()
+    }
+}
+
+#[test]
+fn sphinx_centered_01() {
+    let src =
+r#"
+.. centered:: LICENSE **AGREEMENT**
+
+A paragraph.
+"#
+    .lines()
+    .map(|s| s.to_string())
+    .collect::<Vec<String>>();
+
+    let mut doctree = DocTree::new(PathBuf::from("test"));
+
+    let mut parser = Parser::new(&src, doctree, 0, 0, State::Body, 0);
+
+    doctree = parser.parse().unwrap_tree();
+    doctree = doctree.walk_to_root();
+    doctree.print_tree();
+
+    if let TreeNodeType::SphinxCentered = doctree
+        .shared_child(0).unwrap().shared_data() {
+        if let TreeNodeType::StrongEmphasis { text } = doctree
+            .shared_child(0).unwrap()
+            .shared_child(2).unwrap().shared_data() {
+            assert_eq!(text, "AGREEMENT");
+        } else {
+            panic!()
+        }
+    } else {
+        panic!()
+    }
+
+    if let TreeNodeType::Paragraph { .. } = doctree
+        .shared_child(1).unwrap().shared_data() {
+    } else {
+        panic!()
+    }
+}
@@ -0,0 +1,205 @@
+/*!
+A submodule for testing the Sphinx `productionlist` directive.
+
+Copyright © 2020 Santtu Söderholm
+*/
+
+use super::*;
+use crate::common::SphinxProductionSegment;
+
+#[cfg(test)]
+#[test]
+fn sphinx_productionlist_01() {
+    let src =
+r#"
+.. productionlist::
+   try_stmt: `try1_stmt` | `try2_stmt`
+   try1_stmt: "try" ":" `suite`
+            : ("except" [`expression`] ":" `suite`)+
+
+A paragraph after the list.
+"#
+    .lines()
+    .map(|s| s.to_string())
+    .collect::<Vec<String>>();
+
+    let mut doctree = DocTree::new(PathBuf::from("test"));
+
+    let mut parser = Parser::new(&src, doctree, 0, 0, State::Body, 0);
+
+    doctree = parser.parse().unwrap_tree();
+    doctree = doctree.walk_to_root();
+    doctree.print_tree();
+
+    let list = doctree.shared_child(0).unwrap();
+    if let TreeNodeType::SphinxProductionList { group } = list.shared_data() {
+        assert_eq!(*group, None);
+    } else {
+        panic!()
+    }
+
+    if let TreeNodeType::SphinxProduction { name, target_label, definition } = list
+        .shared_child(0).unwrap().shared_data() {
+        assert_eq!(name, "try\\_stmt");
+        assert_eq!(target_label, "grammar-token-try_stmt");
+        assert_eq!(definition.len(), 1);
+        assert_eq!(
+            definition[0][0],
+            SphinxProductionSegment::Token {
+                text: String::from("try1\\_stmt"),
+                target_label: String::from("grammar-token-try1_stmt"),
+            }
+        );
+    } else {
+        panic!()
+    }
+
+    if let TreeNodeType::SphinxProduction { definition, .. } = list
+        .shared_child(1).unwrap().shared_data() {
+        assert_eq!(definition.len(), 2);
+        assert_eq!(
+            definition[1][1],
+            SphinxProductionSegment::Token {
+                text: String::from("expression"),
+                target_label: String::from("grammar-token-expression"),
+            }
+        );
+    } else {
+        panic!()
+    }
+
+    assert!(doctree.shared_targets().contains_key("grammar-token-try1_stmt"));
+    assert!(doctree.shared_references().contains_key("grammar-token-suite"));
+
+    if let TreeNodeType::Paragraph { .. } = doctree.shared_child(1).unwrap().shared_data() {
+    } else {
+        panic!()
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn sphinx_productionlist_02() {
+    let src =
+r#"
+.. productionlist:: arithmetic
+   expr: `term` ("+" `term`)*
+   term: `arithmetic:factor` | `~arithmetic:atom`
+"#
+    .lines()
+    .map(|s| s.to_string())
+    .collect::<Vec<String>>();
+
+    let mut doctree = DocTree::new(PathBuf::from("test"));
+
+    let mut parser = Parser::new(&src, doctree, 0, 0, State::Body, 0);
+
+    doctree = parser.parse().unwrap_tree();
+    doctree = doctree.walk_to_root();
+    doctree.print_tree();
+
+    let list = doctree.shared_child(0).unwrap();
+    if let TreeNodeType::SphinxProductionList { group } = list.shared_data() {
+        assert_eq!(group.as_deref(), Some("arithmetic"));
+    } else {
+        panic!()
+    }
+
+    if let TreeNodeType::SphinxProduction { target_label, definition, .. } = list
+        .shared_child(1).unwrap().shared_data() {
+        assert_eq!(target_label, "grammar-token-arithmetic-term");
+        assert_eq!(
+            definition[0],
+            vec![
+                SphinxProductionSegment::Token {
+                    text: String::from("arithmetic:factor"),
+                    target_label: String::from("grammar-token-arithmetic-factor"),
+                },
+                SphinxProductionSegment::Text(String::from(" | ")),
+                SphinxProductionSegment::Token {
+                    text: String::from("atom"),
+                    target_label: String::from("grammar-token-arithmetic-atom"),
+                },
+            ]
+        );
+    } else {
+        panic!()
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn sphinx_productionlist_unresolved_tokens() {
+    let src =
+r#"
+.. productionlist::
+   stmt: `expr` ";" | `block`
+   expr: "x"
+"#
+    .lines()
+    .map(|s| s.to_string())
+    .collect::<Vec<String>>();
+
+    let mut doctree = DocTree::new(PathBuf::from("test"));
+
+    let mut parser = Parser::new(&src, doctree, 0, 0, State::Body, 0);
+
+    doctree = parser.parse().unwrap_tree();
+    doctree = doctree.walk_to_root();
+    doctree = doctree.perform_restructuredtext_transforms();
+    doctree.print_tree();
+
+    // No production defines "block", so it is not linked
+    if let TreeNodeType::SphinxProduction { definition, .. } = doctree
+        .shared_child(0).unwrap()
+        .shared_child(0).unwrap()
+        .shared_data() {
+        assert_eq!(
+            definition[0],
+            vec![
+                SphinxProductionSegment::Token {
+                    text: String::from("expr"),
+                    target_label: String::from("grammar-token-expr"),
+                },
+                SphinxProductionSegment::Text(String::from(" \";\" | ")),
+                SphinxProductionSegment::Text(String::from("block")),
+            ]
+        );
+    } else {
+        panic!()
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn sphinx_productionlist_on_marker_line() {
+    let src =
+r#"
+.. productionlist:: name: `letter` (`letter` | `digit`)*
+A paragraph right after the list.
+"#
+    .lines()
+    .map(|s| s.to_string())
+    .collect::<Vec<String>>();
+
+    let mut doctree = DocTree::new(PathBuf::from("test"));
+
+    let mut parser = Parser::new(&src, doctree, 0, 0, State::Body, 0);
+
+    doctree = parser.parse().unwrap_tree();
+    doctree = doctree.walk_to_root();
+    doctree.print_tree();
+
+    if let TreeNodeType::SphinxProductionList { .. } = doctree
+        .shared_child(0).unwrap().shared_data() {
+        assert_eq!(doctree.shared_child(0).unwrap().shared_children().as_ref().unwrap().len(), 1);
+    } else {
+        panic!()
+    }
+
+    if let TreeNodeType::Paragraph { .. } = doctree
+        .shared_child(1).unwrap().shared_data() {
+    } else {
+        panic!()
+    }
+}
@@ -0,0 +1,58 @@
+/*!
+A submodule for testing the Sphinx `tabularcolumns` directive.
+
+Copyright © 2020 Santtu Söderholm
+*/
+
+use super::*;
+
+#[cfg(test)]
+#[test]
+fn sphinx_tabularcolumns_01() {
+    let src =
+r#"
+.. tabularcolumns:: |p{4cm}|l|
+
+A paragraph between the directive and the table.
+
+.. list-table::
+   :widths: 1 1
+
+   * - A cell
+     - Another cell
+
+.. list-table::
+   :widths: 1 1
+
+   * - A cell
+     - Another cell
+"#
+    .lines()
+    .map(|s| s.to_string())
+    .collect::<Vec<String>>();
+
+    let mut doctree = DocTree::new(PathBuf::from("test"));
+
+    let mut parser = Parser::new(&src, doctree, 0, 0, State::Body, 0);
+
+    doctree = parser.parse().unwrap_tree();
+    doctree = doctree.walk_to_root();
+    doctree.print_tree();
+
+    if let TreeNodeType::Paragraph { .. } = doctree.shared_child(0).unwrap().shared_data() {
+    } else {
+        panic!()
+    }
+
+    if let TreeNodeType::ListTable { column_spec, .. } = doctree.shared_child(1).unwrap().shared_data() {
+        assert_eq!(column_spec.as_deref(), Some("|p{4cm}|l|"));
+    } else {
+        panic!()
+    }
+
+    if let TreeNodeType::ListTable { column_spec, .. } = doctree.shared_child(2).unwrap().shared_data() {
+        assert_eq!(*column_spec, None);
+    } else {
+        panic!()
+    }
+}
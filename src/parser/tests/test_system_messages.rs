@@ -0,0 +1,162 @@
+/*!
+A submodule for testing the system messages generated while parsing and transforming a document.
+
+Copyright © 2020 Santtu Söderholm
+*/
+
+use super::*;
+use crate::common::{SystemMessageLevel, TableColWidths};
+
+#[cfg(test)]
+#[test]
+fn system_messages_01() {
+    let src =
+r#"
+A paragraph with :unknown-role:`text` in it.
+
+.. list-table::
+  :header-rows: many
+
+  * - A
+    - B
+
+Another paragraph with :emphasis:`two roles`:strong:.
+"#
+    .lines()
+    .map(|s| s.to_string())
+    .collect::<Vec<String>>();
+
+    let mut doctree = DocTree::new(PathBuf::from("test"));
+
+    let mut parser = Parser::new(&src, doctree, 0, 0, State::Body, 0);
+
+    doctree = parser.parse().unwrap_tree();
+    doctree = doctree.perform_restructuredtext_transforms();
+    doctree.print_tree();
+
+    let messages = doctree.shared_system_messages();
+    assert_eq!(messages.len(), 3);
+    assert_eq!(messages[0].level, SystemMessageLevel::Error);
+    assert_eq!(messages[0].line, Some(2));
+    assert_eq!(messages[0].text, "Unknown interpreted text role \"unknown-role\".");
+    assert_eq!(messages[1].level, SystemMessageLevel::Error);
+    assert_eq!(messages[1].line, Some(4));
+    assert_eq!(messages[1].text, "Invalid list-table \"header-rows\" setting \"many\". Ignoring.");
+    assert_eq!(messages[2].level, SystemMessageLevel::Error);
+    assert_eq!(messages[2].line, Some(10));
+
+    if let TreeNodeType::Problematic { text, message_label } = doctree
+        .shared_child(2).unwrap()
+        .shared_child(6).unwrap().shared_data()
+    {
+        assert_eq!(text, ":emphasis:`two roles`:strong:");
+        assert_eq!(message_label, "system-message-3");
+    } else {
+        panic!()
+    }
+
+    // The messages are appended to the end of the document,
+    // and only the ones linked to from the text are marked as referenced
+    let expected = [("system-message-1", true), ("system-message-2", false), ("system-message-3", true)];
+    for (index, (label, is_referenced)) in (3..6).zip(&expected) {
+        if let TreeNodeType::SystemMessage { label: message_label, referenced, .. } = doctree
+            .shared_child(index).unwrap().shared_data()
+        {
+            assert_eq!(message_label, label);
+            assert_eq!(referenced, is_referenced);
+        } else {
+            panic!()
+        }
+    }
+}
+
+#[test]
+fn system_messages_02() {
+    let src =
+r#"
+.. list-table::
+  :widths: 1 abc
+
+  * - A
+    - B
+"#
+    .lines()
+    .map(|s| s.to_string())
+    .collect::<Vec<String>>();
+
+    let mut doctree = DocTree::new(PathBuf::from("test"));
+
+    let mut parser = Parser::new(&src, doctree, 0, 0, State::Body, 0);
+
+    doctree = parser.parse().unwrap_tree();
+    doctree = doctree.walk_to_root();
+    doctree.print_tree();
+
+    let messages = doctree.shared_system_messages();
+    assert_eq!(messages.len(), 1);
+    assert_eq!(messages[0].level, SystemMessageLevel::Error);
+    assert_eq!(messages[0].line, Some(2));
+    assert_eq!(messages[0].text, "Invalid list-table \"widths\" setting \"1 abc\". Ignoring.");
+
+    if let TreeNodeType::ListTable { widths, .. } = doctree.shared_child(0).unwrap().shared_data() {
+        // The invalid setting is ignored in favour of equal column widths
+        if let Some(TableColWidths::Columns(widths)) = widths {
+            assert_eq!(widths, &vec![0.5, 0.5]);
+        } else {
+            panic!()
+        }
+    } else {
+        panic!()
+    }
+}
+
+#[test]
+fn system_messages_03() {
+    let src =
+r#"
+A paragraph with a reference to :ref:`nowhere`
+
+and another one, that spans two lines and refers to
+an undefined :term:`glossary term`, as well as :ref:`nowhere`.
+"#
+    .lines()
+    .map(|s| s.to_string())
+    .collect::<Vec<String>>();
+
+    let mut doctree = DocTree::new(PathBuf::from("test"));
+
+    let mut parser = Parser::new(&src, doctree, 0, 0, State::Body, 0);
+
+    doctree = parser.parse().unwrap_tree();
+    doctree = doctree.perform_restructuredtext_transforms();
+    doctree.print_tree();
+
+    let messages = doctree.shared_system_messages();
+    assert_eq!(messages.len(), 2);
+    assert_eq!(messages[0].text, "Reference to an undefined glossary term \"glossary-term\".");
+    assert_eq!(messages[0].line, Some(5));
+    assert_eq!(messages[1].text, "Cross-reference to an undefined label \"nowhere\".");
+    assert_eq!(messages[1].line, Some(2));
+}
+
+#[test]
+fn system_message_levels() {
+    use crate::rustla_options::ruSTLaOptions;
+
+    let args = ["rustla", "--report-level", "error", "--halt-level", "none", "file.rst"]
+        .iter()
+        .map(|arg| arg.to_string())
+        .collect::<Vec<String>>();
+    let options = ruSTLaOptions::new(&args);
+
+    assert!(! options.reports(SystemMessageLevel::Warning));
+    assert!(options.reports(SystemMessageLevel::Error));
+    assert!(! options.halts_on(SystemMessageLevel::Severe));
+
+    let options = ruSTLaOptions::new(&vec![String::from("rustla")]);
+
+    assert!(options.reports(SystemMessageLevel::Warning));
+    assert!(! options.reports(SystemMessageLevel::Info));
+    assert!(options.halts_on(SystemMessageLevel::Severe));
+    assert!(! options.halts_on(SystemMessageLevel::Error));
+}
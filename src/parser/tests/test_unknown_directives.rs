@@ -38,6 +38,7 @@ A paragraph.
             argument,
             options,
             body_indent,
+            ..
         } => {
             assert_eq!(directive_name, "some-unknown-dirctive");
             assert_eq!(argument, "some argument here...");
@@ -269,3 +270,129 @@ fn unknown_directive_no_options_but_argument() {
         panic!()
     }
 }
+
+#[test]
+fn unknown_directive_report() {
+    let src =
+"
+.. unknown:: argument
+  :option: 1
+
+  Content paragraph.
+
+.. another-unknown::
+
+.. unknown::
+  :other: 2
+
+Paragraph.
+"
+    .lines()
+    .map(|s| s.to_string())
+    .collect::<Vec<String>>();
+
+    let mut doctree = DocTree::new(PathBuf::from("test"));
+
+    let mut parser = Parser::new(&src, doctree, 0, 0, State::Body, 0);
+
+    doctree = parser.parse().unwrap_tree();
+    doctree = doctree.walk_to_root();
+    doctree.print_tree();
+
+    if let TreeNodeType::UnknownDirective { line, source_lines, .. } = doctree
+        .shared_child(0).unwrap().shared_data()
+    {
+        assert_eq!(*line, 2);
+        assert_eq!(
+            source_lines,
+            &vec![
+                ".. unknown:: argument".to_string(),
+                "  :option: 1".to_string(),
+                String::new(),
+                "  Content paragraph.".to_string()
+            ]
+        );
+    } else {
+        panic!()
+    }
+
+    let report = doctree.unknown_directive_report();
+    let entries = report.shared_entries();
+
+    assert_eq!(report.n_of_occurrences(), 3);
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0].directive_name, "unknown");
+    assert_eq!(entries[0].lines, vec![2, 9]);
+    assert_eq!(
+        entries[0].options.iter().cloned().collect::<Vec<String>>(),
+        vec!["option".to_string(), "other".to_string()]
+    );
+    assert_eq!(entries[1].directive_name, "another-unknown");
+    assert_eq!(entries[1].lines, vec![7]);
+    assert!(entries[1].options.is_empty());
+
+    doctree = doctree.perform_unknown_directive_transforms(
+        &crate::rustla_options::UnknownDirectiveOutput::Drop
+    );
+
+    assert_eq!(doctree.shared_node().shared_children().as_ref().unwrap().len(), 1);
+    if let TreeNodeType::Paragraph { .. } = doctree
+        .shared_child(0).unwrap().shared_data() {
+    } else {
+        panic!()
+    }
+}
+
+#[test]
+fn unknown_directive_without_contents() {
+    let src =
+"
+.. unknown:: argument
+
+A paragraph after the directive.
+
+* .. unknown::
+
+  A paragraph in the list item, after the directive.
+"
+    .lines()
+    .map(|s| s.to_string())
+    .collect::<Vec<String>>();
+
+    let mut doctree = DocTree::new(PathBuf::from("test"));
+
+    let mut parser = Parser::new(&src, doctree, 0, 0, State::Body, 0);
+
+    doctree = parser.parse().unwrap_tree();
+    doctree = doctree.walk_to_root();
+    doctree.print_tree();
+
+    // A directive followed by a line that is not indented relative to the marker has no contents
+    if let TreeNodeType::UnknownDirective { argument, .. } = doctree
+        .shared_child(0).unwrap().shared_data()
+    {
+        assert_eq!(argument, "argument");
+        assert!(doctree.shared_child(0).unwrap().shared_children().as_ref().unwrap().is_empty());
+    } else {
+        panic!()
+    }
+
+    if let TreeNodeType::Paragraph { .. } = doctree
+        .shared_child(1).unwrap().shared_data() {
+    } else {
+        panic!()
+    }
+
+    let list_item = doctree.shared_child(2).unwrap().shared_child(0).unwrap();
+
+    if let TreeNodeType::UnknownDirective { .. } = list_item.shared_child(0).unwrap().shared_data() {
+        assert!(list_item.shared_child(0).unwrap().shared_children().as_ref().unwrap().is_empty());
+    } else {
+        panic!()
+    }
+
+    if let TreeNodeType::Paragraph { .. } = list_item.shared_child(1).unwrap().shared_data() {
+    } else {
+        panic!()
+    }
+}
@@ -0,0 +1,27 @@
+/*!
+A submodule for testing the conversion of UTF-8 text into LaTeX.
+
+Copyright © 2020 Santtu Söderholm
+*/
+
+use super::*;
+use crate::utf8_to_latex::unicode_text_to_latex;
+
+#[cfg(test)]
+#[test]
+fn control_symbol_spacing() {
+    // Control symbols are not followed by a space
+    assert_eq!(unicode_text_to_latex("try_stmt"), "try\\_stmt");
+    assert_eq!(unicode_text_to_latex("50%off"), "50\\%off");
+    assert_eq!(unicode_text_to_latex("A&B"), "A\\&B");
+    assert_eq!(unicode_text_to_latex("#tag"), "\\#tag");
+}
+
+#[cfg(test)]
+#[test]
+fn control_word_spacing() {
+    // Control words are separated from a following letter, but not from other characters
+    assert_eq!(unicode_text_to_latex("x^y"), "x\\textasciicircum y");
+    assert_eq!(unicode_text_to_latex("x^2"), "x\\textasciicircum2");
+    assert_eq!(unicode_text_to_latex("x^"), "x\\textasciicircum");
+}
@@ -4,11 +4,20 @@ This submodule contains the type definition and implementation of ruSTLaOptions.
 Copyright © 2020 Santtu Söderholm
 */
 
+use crate::common::SystemMessageLevel;
+
 /// A container for the flags and settings of the ruSTLa transpiler at a type level.
 /// The options include
 /// 1. the output stream (stdout or file), set with the `--to-stdout` and `--to-file` flags.
 /// 2. whether ruSTLa should surround its object code with the LaTeX `document` environment. Set with the `--full-doc` flag.
 /// 3. whether the `aplus.cls` file should be generated next to the source file with the `--aplus-cls` flag.
+/// 4. the tags used in evaluating the expressions of Sphinx `only` directives, added with `--tag NAME`.
+/// 5. how the authors given by Sphinx `sectionauthor` and `codeauthor` directives are displayed, set with `--authors MODE`.
+/// 6. how directives unknown to ruSTLa are written out, set with `--unknown-directives MODE`.
+/// 7. whether the run should fail if the document contains unknown directives. Set with the `--strict` flag.
+/// 8. the lowest level of system messages that are reported, set with `--report-level LEVEL`.
+/// 9. the lowest level of system messages that abort the run, set with `--halt-level LEVEL`.
+/// 10. the folder the A+ questionnaires are exported to as grader configurations, set with `--aplus-export DIR`.
 #[allow(non_camel_case_types)]
 #[derive(Debug)]
 pub struct ruSTLaOptions {
@@ -19,9 +28,33 @@ pub struct ruSTLaOptions {
     print_full_document: bool,
 
     /// A flag that specifies whether the A+ class file should be written next to the source file.
-    generate_class_file: bool
+    generate_class_file: bool,
+
+    /// The tags that are considered defined, when evaluating the expressions of Sphinx `only` directives.
+    tags: std::collections::HashSet<String>,
+
+    /// How the authors of sections and code are displayed in the output.
+    author_display: AuthorDisplay,
+
+    /// How the directives unknown to ruSTLa are written to the output.
+    unknown_directive_output: UnknownDirectiveOutput,
+
+    /// Whether encountering an unknown directive fails the run.
+    strict: bool,
+
+    /// The lowest level of system messages that are reported. `None` if no messages are reported.
+    report_level: Option<SystemMessageLevel>,
+
+    /// The lowest level of system messages that abort the run. `None` if the run is never aborted.
+    halt_level: Option<SystemMessageLevel>,
+
+    /// The folder the A+ questionnaires are exported to. `None` if they are not exported.
+    aplus_export_folder: Option<std::path::PathBuf>,
 }
 
+/// The `only` directive tags that are always defined, as ruSTLa builds LaTeX.
+const ALWAYS_DEFINED_TAGS: &[&str] = &["latex"];
+
 impl ruSTLaOptions {
 
     /// The ruSTLaOptions constructor. Receives the command line arguments in a vector of strings,
@@ -33,7 +66,14 @@ impl ruSTLaOptions {
         let mut options = Self {
             output_stream: OutputStream::StdOut,
             print_full_document: false,
-            generate_class_file: false
+            generate_class_file: false,
+            tags: ALWAYS_DEFINED_TAGS.iter().map(|tag| tag.to_string()).collect(),
+            author_display: AuthorDisplay::None,
+            unknown_directive_output: UnknownDirectiveOutput::Environment,
+            strict: false,
+            report_level: Some(SystemMessageLevel::Warning),
+            halt_level: Some(SystemMessageLevel::Severe),
+            aplus_export_folder: None,
         };
 
         while arg_index < args_len {
@@ -48,6 +88,64 @@ impl ruSTLaOptions {
                 "--to-file"     => options.output_stream = OutputStream::File,
                 "--full-doc"    => options.print_full_document = true,
                 "--aplus-cls"   => options.generate_class_file = true,
+                "--strict"      => options.strict = true,
+                "--tag"         => {
+                    if let Some(tag) = args.get(arg_index + 1) {
+                        options.tags.insert(tag.to_string());
+                        arg_index += 1;
+                    } else {
+                        eprintln!("No tag name given after \"--tag\". Ignoring...");
+                    }
+                }
+                "--authors"     => {
+                    match args.get(arg_index + 1).map(|mode| mode.as_str()) {
+                        Some("none") => options.author_display = AuthorDisplay::None,
+                        Some("footnote") => options.author_display = AuthorDisplay::Footnote,
+                        Some("margin") => options.author_display = AuthorDisplay::MarginNote,
+                        Some(mode) => eprintln!("Unknown author display mode \"{}\". Ignoring...", mode),
+                        None => eprintln!("No author display mode given after \"--authors\". Ignoring..."),
+                    }
+                    if arg_index + 1 < args_len {
+                        arg_index += 1;
+                    }
+                }
+                "--unknown-directives" => {
+                    match args.get(arg_index + 1) {
+                        Some(mode) => options.set_unknown_directive_output(mode),
+                        None => eprintln!("No output mode given after \"--unknown-directives\". Ignoring..."),
+                    }
+                    if arg_index + 1 < args_len {
+                        arg_index += 1;
+                    }
+                }
+                "--report-level" | "--halt-level" => {
+                    match (args.get(arg_index + 1).map(|level| level.as_str()), arg.as_str()) {
+                        (Some(level), "--report-level") => match system_message_threshold(level) {
+                            Ok(threshold) => options.report_level = threshold,
+                            Err(()) => eprintln!("Unknown report level \"{}\". Ignoring...", level),
+                        },
+                        (Some(level), _) => match system_message_threshold(level) {
+                            Ok(threshold) => options.halt_level = threshold,
+                            Err(()) => eprintln!("Unknown halt level \"{}\". Ignoring...", level),
+                        },
+                        (None, option) => eprintln!("No level given after \"{}\". Ignoring...", option),
+                    }
+                    if arg_index + 1 < args_len {
+                        arg_index += 1;
+                    }
+                }
+                "--aplus-export" => {
+                    match args.get(arg_index + 1) {
+                        Some(folder) => options.aplus_export_folder = Some(std::path::PathBuf::from(folder)),
+                        None => eprintln!("No folder given after \"--aplus-export\". Ignoring..."),
+                    }
+                    if arg_index + 1 < args_len {
+                        arg_index += 1;
+                    }
+                }
+                arg if arg.starts_with("--unknown-directives=") => {
+                    options.set_unknown_directive_output(&arg["--unknown-directives=".len()..])
+                }
                 _ => {}
             }
 
@@ -71,6 +169,95 @@ impl ruSTLaOptions {
     pub fn create_class_file (&self) -> bool {
         self.generate_class_file
     }
+
+    /// Returns a shared reference to the set of tags defined for Sphinx `only` directives.
+    pub fn shared_tags(&self) -> &std::collections::HashSet<String> {
+        &self.tags
+    }
+
+    /// Returns a shared reference to the chosen way of displaying section and code authors.
+    pub fn shared_author_display(&self) -> &AuthorDisplay {
+        &self.author_display
+    }
+
+    /// Returns a shared reference to the chosen way of writing out unknown directives.
+    pub fn shared_unknown_directive_output(&self) -> &UnknownDirectiveOutput {
+        &self.unknown_directive_output
+    }
+
+    /// Returns a copy of the flag which determines whether unknown directives fail the run.
+    pub fn is_strict(&self) -> bool {
+        self.strict
+    }
+
+    /// Returns a shared reference to the folder the A+ questionnaires are exported to, if one was given.
+    pub fn shared_aplus_export_folder(&self) -> Option<&std::path::PathBuf> {
+        self.aplus_export_folder.as_ref()
+    }
+
+    /// Checks whether system messages of the given level should be reported.
+    pub fn reports(&self, level: SystemMessageLevel) -> bool {
+        match self.report_level {
+            Some(threshold) => level >= threshold,
+            None => false,
+        }
+    }
+
+    /// Checks whether system messages of the given level should abort the run.
+    pub fn halts_on(&self, level: SystemMessageLevel) -> bool {
+        match self.halt_level {
+            Some(threshold) => level >= threshold,
+            None => false,
+        }
+    }
+
+    /// Sets the unknown directive output mode based on the given mode name.
+    fn set_unknown_directive_output(&mut self, mode: &str) {
+        match mode {
+            "environment" => self.unknown_directive_output = UnknownDirectiveOutput::Environment,
+            "comment" => self.unknown_directive_output = UnknownDirectiveOutput::Comment,
+            "literal" => self.unknown_directive_output = UnknownDirectiveOutput::Literal,
+            "drop" => self.unknown_directive_output = UnknownDirectiveOutput::Drop,
+            "error" => self.unknown_directive_output = UnknownDirectiveOutput::Error,
+            _ => eprintln!("Unknown directive output mode \"{}\". Ignoring...", mode),
+        }
+    }
+}
+
+/// An enumeration of the ways the authors given by the Sphinx `sectionauthor` and `codeauthor` directives
+/// can be displayed. These can be set with the `--authors MODE` command line option.
+#[derive(Debug, PartialEq)]
+pub enum AuthorDisplay {
+    /// The authors are only collected into the document-level author summary.
+    None,
+    /// Each author is displayed in a footnote.
+    Footnote,
+    /// Each author is displayed in a margin note.
+    MarginNote,
+}
+/// Converts the value of `--report-level` or `--halt-level` into a message level threshold.
+/// The value `none` (or `5`, as in docutils) results in `None`, so that no message reaches the threshold.
+fn system_message_threshold(level: &str) -> Result<Option<SystemMessageLevel>, ()> {
+    match level {
+        "none" | "5" => Ok(None),
+        _ => level.parse::<SystemMessageLevel>().map(Some),
+    }
+}
+
+/// An enumeration of the ways directives unknown to ruSTLa can be written out.
+/// These can be set with the `--unknown-directives MODE` command line option.
+#[derive(Debug, PartialEq)]
+pub enum UnknownDirectiveOutput {
+    /// The directive is written as a LaTeX environment with the same name. This is the default.
+    Environment,
+    /// The source of the directive is written out as LaTeX comments.
+    Comment,
+    /// The source of the directive is written out in a code block.
+    Literal,
+    /// The directive and its contents are left out of the output.
+    Drop,
+    /// The run fails, if the document contains unknown directives.
+    Error,
 }
 #[derive(Debug)]
 /// An enumeration of the different output streams of ruSTLa.
@@ -34,6 +34,10 @@ pub fn unicode_math_to_latex(utf_str: &str) -> String {
 ///
 /// For example, `'_' ↦ "\_"` and `'@' ↦ "\@"`. If a character is not recognized as a control character,
 /// it is added to the generated `String` as is.
+///
+/// A control word such as `\textasciicircum` is separated from a following letter with a space,
+/// as TeX would otherwise read the letter as a part of the command name.
+/// Control symbols such as `\_` end at the symbol, so they are written right next to the following text.
 pub fn unicode_text_to_latex(utf_str: &str) -> String {
     let source_char_count = utf_str.chars().count();
     let mut latex_string = String::with_capacity(source_char_count);
@@ -63,6 +67,12 @@ pub fn unicode_text_to_latex(utf_str: &str) -> String {
         };
 
         if let Some(latex_str) = UTF8_TEXT_TO_LATEX_MAP.get(&c1) {
+            let is_control_word = latex_str.ends_with(|c: char| c.is_ascii_alphabetic());
+            let space_based_on_next_char = if is_control_word {
+                space_based_on_next_char
+            } else {
+                ""
+            };
             latex_string = latex_string + latex_str + space_based_on_next_char;
         } else {
             latex_string.push(c1);